@@ -0,0 +1,3 @@
+-- Add up migration script here
+alter table collections
+    add rules jsonb;
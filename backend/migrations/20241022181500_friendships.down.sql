@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS friendships CASCADE;
+DROP TYPE IF EXISTS friendship_status CASCADE;
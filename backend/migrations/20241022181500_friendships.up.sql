@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TYPE FRIENDSHIP_STATUS AS ENUM ('pending', 'accepted', 'declined', 'blocked');
+
+CREATE TABLE IF NOT EXISTS friendships
+(
+    id              UUID                PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    requester_id    UUID                NOT NULL,
+    addressee_id    UUID                NOT NULL,
+    status          FRIENDSHIP_STATUS   NOT NULL DEFAULT 'pending',
+    created_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_friendship_requester FOREIGN KEY(requester_id)
+        REFERENCES users(id),
+    CONSTRAINT FK_friendship_addressee FOREIGN KEY(addressee_id)
+        REFERENCES users(id),
+    CONSTRAINT friendships_no_self CHECK (requester_id <> addressee_id)
+);
+
+-- One row per pair of users, whichever direction the request went.
+CREATE UNIQUE INDEX friendships_pair_idx ON friendships
+    (LEAST(requester_id, addressee_id), GREATEST(requester_id, addressee_id));
@@ -0,0 +1,18 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS collection_revisions
+(
+    id              UUID                PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    collection_id   UUID                NOT NULL,
+    actor_id        UUID                NOT NULL,
+    created_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    added           JSONB,
+    removed         JSONB,
+    snapshot        JSONB,
+    CONSTRAINT FK_revision_collection FOREIGN KEY(collection_id)
+        REFERENCES collections(id) ON DELETE CASCADE,
+    CONSTRAINT FK_revision_actor FOREIGN KEY(actor_id)
+        REFERENCES users(id)
+);
+
+CREATE INDEX collection_revisions_collection_idx ON collection_revisions
+    (collection_id, created_at DESC);
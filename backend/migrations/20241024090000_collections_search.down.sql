@@ -0,0 +1,5 @@
+-- Add down migration script here
+drop index if exists collections_search_vector_idx;
+
+alter table collections
+    drop column if exists search_vector;
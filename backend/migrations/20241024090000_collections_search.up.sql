@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- Everything searchable in a collection, so the GIN index can narrow a search down to the
+-- collections worth unpacking. Matching single entries happens at query time.
+alter table collections
+    add search_vector tsvector generated always as (
+        setweight(jsonb_to_tsvector('english', coalesce(collection, '{}'), '["string"]'), 'B') ||
+        setweight(jsonb_to_tsvector('english', coalesce(tags, '[]'), '["string"]'), 'C')
+    ) stored;
+
+create index collections_search_vector_idx
+    on collections using gin (search_vector);
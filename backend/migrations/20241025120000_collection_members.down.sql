@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS collection_members;
+
+DROP TYPE IF EXISTS COLLECTION_ROLE;
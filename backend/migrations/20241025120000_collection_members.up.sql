@@ -0,0 +1,21 @@
+-- Add up migration script here
+-- The owner stays on collections.owner_id; these are the other people let in.
+CREATE TYPE COLLECTION_ROLE AS ENUM ('viewer', 'editor');
+
+CREATE TABLE IF NOT EXISTS collection_members
+(
+    collection_id   UUID                NOT NULL,
+    user_id         UUID                NOT NULL,
+    role            COLLECTION_ROLE     NOT NULL DEFAULT 'viewer',
+    invited_by      UUID                NOT NULL,
+    created_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (collection_id, user_id),
+    CONSTRAINT FK_member_collection FOREIGN KEY(collection_id)
+        REFERENCES collections(id) ON DELETE CASCADE,
+    CONSTRAINT FK_member_user FOREIGN KEY(user_id)
+        REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT FK_member_invited_by FOREIGN KEY(invited_by)
+        REFERENCES users(id)
+);
+
+CREATE INDEX collection_members_user_idx ON collection_members (user_id);
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Entries are always kept in order; this says the order means something.
+alter table collections
+    add ranked boolean not null default false;
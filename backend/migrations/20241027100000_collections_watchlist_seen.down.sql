@@ -0,0 +1,2 @@
+-- Add down migration script here
+delete from collections where special in ('watchlist', 'seen');
@@ -0,0 +1,14 @@
+-- Add up migration script here
+insert into collections (owner_id, name, active, sharing, collection, locked, tags, special)
+select u.id, '📋 Watchlist', true, 'private', '{"entries": []}', true, '["watchlist"]', 'watchlist'
+from users u
+where not exists (
+    select 1 from collections c where c.owner_id = u.id and c.special = 'watchlist'
+);
+
+insert into collections (owner_id, name, active, sharing, collection, locked, tags, special)
+select u.id, '👁 Seen', true, 'private', '{"entries": []}', true, '["seen"]', 'seen'
+from users u
+where not exists (
+    select 1 from collections c where c.owner_id = u.id and c.special = 'seen'
+);
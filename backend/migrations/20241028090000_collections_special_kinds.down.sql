@@ -0,0 +1,5 @@
+-- Add down migration script here
+drop index if exists collections_owner_special_idx;
+
+alter table collections
+    drop constraint if exists collections_special_kind;
@@ -0,0 +1,45 @@
+-- Add up migration script here
+-- Older rows may spell the kind differently, e.g. 'Thumbs Up' or 'thumbs_down'.
+update collections
+set special = lower(regexp_replace(special, '[^a-zA-Z]', '', 'g'))
+where special is not null;
+
+-- Anything still unrecognised was never special to the app, so it stays as an ordinary collection.
+update collections
+set special = null
+where special not in ('thumbsup', 'thumbsdown', 'skipped', 'watchlist', 'seen');
+
+-- Extra copies of a user's special collections, keeping the oldest: empty ones go, and ones with
+-- anything in them become ordinary collections so nothing is lost.
+delete from collections c
+using (
+    select id, row_number() over (
+        partition by owner_id, special order by created_at nulls last, id
+    ) as n
+    from collections
+    where special is not null
+) d
+where c.id = d.id and d.n > 1
+and case jsonb_typeof(c.collection->'entries')
+    when 'array' then jsonb_array_length(c.collection->'entries') = 0
+    else true
+end;
+
+update collections c
+set special = null, locked = false
+from (
+    select id, row_number() over (
+        partition by owner_id, special order by created_at nulls last, id
+    ) as n
+    from collections
+    where special is not null
+) d
+where c.id = d.id and d.n > 1;
+
+alter table collections
+    add constraint collections_special_kind
+        check (special in ('thumbsup', 'thumbsdown', 'skipped', 'watchlist', 'seen'));
+
+-- One of each per user, so creating missing ones can't race itself into duplicates.
+create unique index collections_owner_special_idx on collections (owner_id, special)
+    where special is not null;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+-- Filled from data/starter_packs.json on startup; admins can change them from there.
+CREATE TABLE IF NOT EXISTS starter_packs
+(
+    id              UUID                PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    slug            VARCHAR(64)         NOT NULL UNIQUE,
+    name            VARCHAR(100)        NOT NULL,
+    description     TEXT                NOT NULL DEFAULT '',
+    entries         JSONB,
+    created_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,14 @@
+-- Add up migration script here
+-- Where each user is in each show. `show_key` is the show's identity ("tv:1396"), `show` the
+-- TvShow as last marked, so next-up can use its seasons.
+CREATE TABLE IF NOT EXISTS series_progress
+(
+    user_id         UUID                NOT NULL,
+    show_key        VARCHAR(512)        NOT NULL,
+    show            JSONB,
+    watched         JSONB,
+    updated_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, show_key),
+    CONSTRAINT FK_progress_user FOREIGN KEY(user_id)
+        REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- One secret per user for calendar subscriptions, which can't log in. Rotating it cuts off every
+-- URL handed out with the old one.
+CREATE TABLE IF NOT EXISTS calendar_tokens
+(
+    user_id         UUID                PRIMARY KEY NOT NULL,
+    token           VARCHAR(64)         NOT NULL UNIQUE,
+    created_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_calendar_token_user FOREIGN KEY(user_id)
+        REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE schedules DROP COLUMN IF EXISTS source_collection;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- A schedule with a source collection is a channel: it loops over the collection forever, starting
+-- from when it was made, instead of having slots of its own.
+ALTER TABLE schedules
+    ADD COLUMN IF NOT EXISTS source_collection UUID
+        REFERENCES collections(id) ON DELETE SET NULL;
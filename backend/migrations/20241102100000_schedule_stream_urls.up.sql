@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- Where each entry of a schedule points when it's exported as an M3U playlist.
+CREATE TABLE IF NOT EXISTS schedule_stream_urls
+(
+    schedule_id     UUID                PRIMARY KEY NOT NULL,
+    urls            JSONB               NOT NULL DEFAULT '{}',
+    updated_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_stream_urls_schedule FOREIGN KEY(schedule_id)
+        REFERENCES schedules(id) ON DELETE CASCADE
+);
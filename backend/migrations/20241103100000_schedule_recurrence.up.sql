@@ -0,0 +1,12 @@
+-- Add up migration script here
+-- A schedule with recurrence rules is a standing routine. Its slots are laid out as they come into
+-- view, up to its horizon, and `materialized_until` is how far that has got.
+CREATE TABLE IF NOT EXISTS schedule_recurrence
+(
+    schedule_id         UUID                PRIMARY KEY NOT NULL,
+    recurrence          JSONB               NOT NULL,
+    materialized_until  TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    updated_at          TIMESTAMPTZ         NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_recurrence_schedule FOREIGN KEY(schedule_id)
+        REFERENCES schedules(id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- Slugs of bundled packs an admin deleted, so seeding doesn't bring them back.
+CREATE TABLE IF NOT EXISTS deleted_starter_packs
+(
+    slug            VARCHAR(64)         PRIMARY KEY NOT NULL,
+    deleted_at      TIMESTAMPTZ         NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE collection_revisions DROP COLUMN IF EXISTS reverts;
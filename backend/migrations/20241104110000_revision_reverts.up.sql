@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- The revision an undo took back, so undo walks back through history rather than redoing itself.
+ALTER TABLE collection_revisions
+    ADD COLUMN IF NOT EXISTS reverts UUID REFERENCES collection_revisions(id) ON DELETE SET NULL;
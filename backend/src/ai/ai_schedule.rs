@@ -0,0 +1,271 @@
+use crate::ai::{get_typed_special_collections, get_with_instructions};
+use crate::tmdb::{add_tv_seasons, get_movie_runtime, search_movie, search_tv_show};
+use crate::ApiKeys;
+use common::model::collections::{Media, UserCollection};
+use common::model::core::{DiscoveryMeta, Movie, TvShow, TvShowDetails};
+use common::model::progress::EpisodeNumber;
+use common::model::schedule_generator::{
+    ScheduleItem, DEFAULT_EPISODE_RUNTIME, DEFAULT_MOVIE_RUNTIME,
+};
+use common::model::schedule_themes::{ThemedLineup, ThemedPick};
+use common::model::schedules::EpisodeInfo;
+use common::model::special::SpecialCollection;
+use log::{debug, warn};
+use std::collections::{HashMap, HashSet};
+use std::error;
+
+pub async fn get_themed(
+    api_keys: &ApiKeys,
+    collections: Vec<UserCollection>,
+    theme: &str,
+    count: usize,
+    windows: &str,
+) -> Result<ThemedLineup, Box<dyn error::Error>> {
+    // Schedules mix films and shows, so the user's taste in both counts.
+    let movies =
+        get_typed_special_collections(Media::Movie(Movie::default()), collections.clone()).await;
+    let tv_shows =
+        get_typed_special_collections(Media::TvShow(TvShow::default()), collections).await;
+    let both = |key: &str| {
+        format!(
+            "{} {}",
+            movies.get(key).expect("Missing Special Collection"),
+            tv_shows.get(key).expect("Missing Special Collection")
+        )
+    };
+
+    let main_prompt = "You are bAIng, an AI assistant that helps create curated lists of TV shows and Movies. You respond only with JSON.";
+    let message = format!("\
+        Plan a viewing schedule of {count} slots for the User's Theme, to be watched {windows}. \
+        Return it in the form a JSON Array named 'slots', in the order they should be watched so the schedule follows the theme from start to finish, with the fields \
+            'media_type' containing either \"movie\" or \"tv\", \
+            'name' containing the name of the movie or TV show as a string, \
+            'year' containing the year of its release or first airing as a number, \
+            'season' and 'episode' containing the season and episode numbers for a TV episode, or null for a movie, \
+            and 'rationale' containing why this slot belongs here in the schedule. \n \
+        User's Theme: {theme} \n \
+        Take the following collections of titles into consideration when making you recommendations and never include the ones they disliked: \
+        Titles they disliked: {unliked_list} \n \
+        Titles they liked: {liked_list} \n \
+        Titles they weren't interested in: {skipped_list} \n \
+        Titles already on their watchlist, a good sign of what they want more of: {watchlist_list} \n \
+        Titles they rated or watched, with their rating and notes: {rated_list} \n \
+        Titles grouped by the tags they gave them: {tagged_list}",
+        unliked_list = both(SpecialCollection::ThumbsDown.key()),
+        liked_list = both(SpecialCollection::ThumbsUp.key()),
+        skipped_list = both(SpecialCollection::Skipped.key()),
+        watchlist_list = both(SpecialCollection::Watchlist.key()),
+        rated_list = both("rated"),
+        tagged_list = both("tagged")
+    );
+    let instructions = format!("{} {}", main_prompt, message);
+    debug!("AI Instructions: {instructions}");
+
+    get_with_instructions(api_keys, &instructions).await
+}
+
+// A title can be matched on its TMDB id or, for entries TMDB never saw, on how it reads.
+fn exclusion_keys(media: &Media) -> [String; 2] {
+    [media.identity(), format!("{}:{media}", media.kind())]
+}
+
+// Everything the user has thumbed down, which the AI is told to leave out but may not.
+pub fn thumbed_down(collections: &[UserCollection]) -> HashSet<String> {
+    collections
+        .iter()
+        .filter(|uc| uc.is_special(SpecialCollection::ThumbsDown))
+        .flat_map(|uc| uc.collection.entries.iter())
+        .flat_map(exclusion_keys)
+        .collect()
+}
+
+// Turns the AI's picks into things to schedule, in order, looking each title up on TMDB for its
+// runtime when there's a key to do it with. Returns what's left out too, with why.
+pub async fn resolve_picks(
+    tmdb_key: Option<&str>,
+    lineup: ThemedLineup,
+    theme: &str,
+    excluded: &HashSet<String>,
+) -> (Vec<ScheduleItem>, Vec<String>) {
+    let mut items = vec![];
+    let mut dropped = vec![];
+    // Each show is looked up once, however many of its episodes are picked.
+    let mut shows: HashMap<(String, i32), Result<Option<TvShowDetails>, String>> = HashMap::new();
+    let mut last_episode: HashMap<String, EpisodeNumber> = HashMap::new();
+
+    for pick in lineup.slots {
+        let meta = Some(DiscoveryMeta {
+            query: theme.to_string(),
+            reason: pick.rationale.clone(),
+            streamers: String::new(),
+        });
+
+        let item = match pick.media_type.as_str() {
+            "movie" => movie_item(tmdb_key, &pick, meta).await,
+            "tv" => {
+                let key = (pick.name.clone(), pick.year);
+                if !shows.contains_key(&key) {
+                    let details = match tmdb_key {
+                        Some(tmdb_key) => lookup_tv_show(tmdb_key, &pick).await,
+                        None => Ok(None),
+                    };
+                    shows.insert(key.clone(), details);
+                }
+                shows[&key]
+                    .clone()
+                    .and_then(|details| tv_item(&pick, details, meta, &mut last_episode))
+            }
+            other => Err(format!("can't schedule a {other}")),
+        };
+
+        match item {
+            Ok(item)
+                if exclusion_keys(&item.media)
+                    .iter()
+                    .any(|k| excluded.contains(k)) =>
+            {
+                dropped.push(format!("{}: thumbed down", item.media))
+            }
+            Ok(item) => items.push(item),
+            Err(why) => dropped.push(format!("{} ({}): {why}", pick.name, pick.year)),
+        }
+    }
+
+    (items, dropped)
+}
+
+async fn movie_item(
+    tmdb_key: Option<&str>,
+    pick: &ThemedPick,
+    baing_meta: Option<DiscoveryMeta>,
+) -> Result<ScheduleItem, String> {
+    let details = match tmdb_key {
+        Some(key) => match search_movie(key, &pick.name, pick.year).await {
+            Ok(Some(mut details)) => {
+                match get_movie_runtime(key, details.tmdb_id).await {
+                    Ok(runtime) => details.runtime = Some(runtime.unwrap_or(0)),
+                    Err(e) => warn!("TMDB runtime for {} failed: {e}", pick.name),
+                }
+                Some(details)
+            }
+            Ok(None) => return Err("not found on TMDB".to_string()),
+            Err(e) => {
+                warn!("TMDB search for {} failed: {e}", pick.name);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let duration = details
+        .as_ref()
+        .and_then(|d| d.runtime)
+        .filter(|r| *r > 0)
+        .unwrap_or(DEFAULT_MOVIE_RUNTIME);
+    Ok(ScheduleItem {
+        media: Media::Movie(Movie {
+            name: pick.name.clone(),
+            year: pick.year,
+            details,
+            baing_meta,
+            entry_meta: None,
+        }),
+        duration,
+        episode: None,
+    })
+}
+
+// The show with its seasons, or `None` when TMDB can't be reached.
+async fn lookup_tv_show(
+    tmdb_key: &str,
+    pick: &ThemedPick,
+) -> Result<Option<TvShowDetails>, String> {
+    let mut details = match search_tv_show(tmdb_key, &pick.name, pick.year).await {
+        Ok(Some(details)) => details,
+        Ok(None) => return Err("not found on TMDB".to_string()),
+        Err(e) => {
+            warn!("TMDB search for {} failed: {e}", pick.name);
+            return Ok(None);
+        }
+    };
+    match add_tv_seasons(tmdb_key, &mut details).await {
+        Ok(()) => {}
+        Err(e) => warn!("TMDB seasons for {} failed: {e}", pick.name),
+    }
+    Ok(Some(details))
+}
+
+fn tv_item(
+    pick: &ThemedPick,
+    details: Option<TvShowDetails>,
+    baing_meta: Option<DiscoveryMeta>,
+    last_episode: &mut HashMap<String, EpisodeNumber>,
+) -> Result<ScheduleItem, String> {
+    let show = TvShow {
+        name: pick.name.clone(),
+        first_air_date: details
+            .as_ref()
+            .map(|d| d.first_air_date.clone())
+            .unwrap_or_else(|| pick.year.to_string()),
+        language: details
+            .as_ref()
+            .map(|d| d.original_language.clone())
+            .unwrap_or_default(),
+        details,
+        baing_meta,
+        entry_meta: None,
+    };
+    let media = Media::TvShow(show.clone());
+
+    let requested = match (pick.season, pick.episode) {
+        (Some(season), Some(episode)) => Some(EpisodeNumber { season, episode }),
+        _ => None,
+    };
+    let last = last_episode.get(&media.identity()).copied();
+
+    let (number, duration, name) = match show.details.as_ref().filter(|d| d.seasons.is_some()) {
+        Some(details) => {
+            let (season, episode) = details
+                .episodes()
+                .find(|(season, e)| {
+                    let number = EpisodeNumber {
+                        season: *season,
+                        episode: e.episode_number,
+                    };
+                    match requested {
+                        Some(requested) => number == requested,
+                        None => last.is_none_or(|last| number > last),
+                    }
+                })
+                .ok_or_else(|| match requested {
+                    Some(requested) => format!("has no {requested}"),
+                    None => "has no more episodes".to_string(),
+                })?;
+            (
+                EpisodeNumber {
+                    season,
+                    episode: episode.episode_number,
+                },
+                episode.runtime.unwrap_or(DEFAULT_EPISODE_RUNTIME),
+                Some(episode.name.clone()).filter(|n| !n.is_empty()),
+            )
+        }
+        None => (
+            requested
+                .unwrap_or_else(|| last.map_or(EpisodeNumber::first(), EpisodeNumber::following)),
+            DEFAULT_EPISODE_RUNTIME,
+            None,
+        ),
+    };
+
+    last_episode.insert(media.identity(), number);
+    Ok(ScheduleItem {
+        media,
+        duration,
+        episode: Some(EpisodeInfo {
+            season: number.season,
+            episode: number.episode,
+            name,
+        }),
+    })
+}
@@ -7,6 +7,7 @@ use allms::Completions;
 use common::model::collections::{Media, UserCollection};
 use common::model::core::TvShow;
 use common::model::discovery::{RandomMovieResponseData, RandomTvShowsResponseData};
+use common::model::special::SpecialCollection;
 use log::debug;
 use std::error;
 
@@ -32,10 +33,18 @@ pub async fn get_random(
         Take the following collections of titles into consideration when making you recommendations but do not include any of them with your final output: \
         Titles they disliked: {unliked_list} \n \
         Titles they liked: {liked_list} \n \
-        Titles they skipped: {skipped_list}",
-        unliked_list = sp_collections.get("thumbsdown").expect("Missing Special Collection"),
-        liked_list = sp_collections.get("thumbsup").expect("Missing Special Collection"),
-        skipped_list = sp_collections.get("skipped").expect("Missing Special Collection")
+        Titles they weren't interested in: {skipped_list} \n \
+        Titles already on their watchlist, a good sign of what they want more of: {watchlist_list} \n \
+        Titles they've already seen, without saying whether they liked them: {seen_list} \n \
+        Titles they rated or watched, with their rating and notes: {rated_list} \n \
+        Titles grouped by the tags they gave them: {tagged_list}",
+        unliked_list = sp_collections.get(SpecialCollection::ThumbsDown.key()).expect("Missing Special Collection"),
+        liked_list = sp_collections.get(SpecialCollection::ThumbsUp.key()).expect("Missing Special Collection"),
+        skipped_list = sp_collections.get(SpecialCollection::Skipped.key()).expect("Missing Special Collection"),
+        watchlist_list = sp_collections.get(SpecialCollection::Watchlist.key()).expect("Missing Special Collection"),
+        seen_list = sp_collections.get(SpecialCollection::Seen.key()).expect("Missing Special Collection"),
+        rated_list = sp_collections.get("rated").expect("Missing Special Collection"),
+        tagged_list = sp_collections.get("tagged").expect("Missing Special Collection")
     );
     let instructions = format!("{} {}", main_prompt, message);
     debug!("AI Instructions: {instructions}");
@@ -67,10 +76,18 @@ pub async fn get_guided(
         Take the following collections of titles into consideration when making you recommendations but do not include any of them with your final output: \
         Titles they disliked: {unliked_list} \n \
         Titles they liked: {liked_list} \n \
-        Titles they skipped: {skipped_list}",
-        unliked_list = sp_collections.get("thumbsdown").expect("Missing Special Collection"),
-        liked_list = sp_collections.get("thumbsup").expect("Missing Special Collection"),
-        skipped_list = sp_collections.get("skipped").expect("Missing Special Collection")
+        Titles they weren't interested in: {skipped_list} \n \
+        Titles already on their watchlist, a good sign of what they want more of: {watchlist_list} \n \
+        Titles they've already seen, without saying whether they liked them: {seen_list} \n \
+        Titles they rated or watched, with their rating and notes: {rated_list} \n \
+        Titles grouped by the tags they gave them: {tagged_list}",
+        unliked_list = sp_collections.get(SpecialCollection::ThumbsDown.key()).expect("Missing Special Collection"),
+        liked_list = sp_collections.get(SpecialCollection::ThumbsUp.key()).expect("Missing Special Collection"),
+        skipped_list = sp_collections.get(SpecialCollection::Skipped.key()).expect("Missing Special Collection"),
+        watchlist_list = sp_collections.get(SpecialCollection::Watchlist.key()).expect("Missing Special Collection"),
+        seen_list = sp_collections.get(SpecialCollection::Seen.key()).expect("Missing Special Collection"),
+        rated_list = sp_collections.get("rated").expect("Missing Special Collection"),
+        tagged_list = sp_collections.get("tagged").expect("Missing Special Collection")
     );
     let instructions = format!("{} {}", main_prompt, message);
     debug!("AI Instructions: {instructions}");
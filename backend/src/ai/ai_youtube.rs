@@ -6,6 +6,7 @@ use allms::Completions;
 use common::model::collections::{Media, UserCollection};
 use common::model::core::{TvShow, YTChannel};
 use common::model::discovery::RandomYTChannelsResponseData;
+use common::model::special::SpecialCollection;
 use std::error;
 
 pub async fn get_random(
@@ -18,17 +19,17 @@ pub async fn get_random(
 
     let unliked_list = common::model::collections::extract_special_collection_to_entries(
         &special_collections,
-        "thumbsdown",
+        SpecialCollection::ThumbsDown,
         &media_type,
     );
     let liked_list = common::model::collections::extract_special_collection_to_entries(
         &special_collections,
-        "thumbsup",
+        SpecialCollection::ThumbsUp,
         &media_type,
     );
     let skipped_list = common::model::collections::extract_special_collection_to_entries(
         &special_collections,
-        "skipped",
+        SpecialCollection::Skipped,
         &media_type,
     );
 
@@ -49,17 +50,17 @@ pub async fn get_guided(
     let media_type = Media::TvShow(TvShow::default());
     let unliked_list = common::model::collections::extract_special_collection_to_entries(
         &special_collections,
-        "thumbsdown",
+        SpecialCollection::ThumbsDown,
         &media_type,
     );
     let liked_list = common::model::collections::extract_special_collection_to_entries(
         &special_collections,
-        "thumbsup",
+        SpecialCollection::ThumbsUp,
         &media_type,
     );
     let skipped_list = common::model::collections::extract_special_collection_to_entries(
         &special_collections,
-        "skipped",
+        SpecialCollection::Skipped,
         &media_type,
     );
 
@@ -2,11 +2,13 @@ use crate::ApiKeys;
 use allms::llm::{AnthropicModels, GoogleModels, OpenAIModels};
 use allms::Completions;
 use common::model::collections::{Media, UserCollection};
+use common::model::special::SpecialCollection;
 use std::collections::HashMap;
 use std::error;
 
 pub mod ai_movie;
 pub mod ai_online_content;
+pub mod ai_schedule;
 pub mod ai_tv;
 pub mod ai_youtube;
 
@@ -25,31 +27,27 @@ pub async fn get_typed_special_collections(
 ) -> HashMap<String, String> {
     let mut colls = HashMap::new();
 
-    colls.insert(
-        String::from("thumbsup"),
-        common::model::collections::extract_special_collection_to_entries(
-            &collections,
-            "thumbsup",
-            &media,
-        ),
-    );
+    for special in SpecialCollection::ALL {
+        colls.insert(
+            special.key().to_string(),
+            common::model::collections::extract_special_collection_to_entries(
+                &collections,
+                special,
+                &media,
+            ),
+        );
+    }
 
+    // Not a special collection, but the user's ratings and watch history are the best signal
+    // we have.
     colls.insert(
-        String::from("thumbsdown"),
-        common::model::collections::extract_special_collection_to_entries(
-            &collections,
-            "thumbsdown",
-            &media,
-        ),
+        String::from("rated"),
+        common::model::collections::extract_rated_entries(&collections, &media),
     );
 
     colls.insert(
-        String::from("skipped"),
-        common::model::collections::extract_special_collection_to_entries(
-            &collections,
-            "skipped",
-            &media,
-        ),
+        String::from("tagged"),
+        common::model::tags::extract_tagged_entries(&collections, &media),
     );
 
     colls
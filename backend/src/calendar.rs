@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use common::model::schedules::{Schedule, ScheduleSlot};
+
+// How often calendar apps should come back for changes, for the ones that listen.
+const REFRESH_INTERVAL: &str = "PT1H";
+
+// Backslashes, semicolons, commas and newlines need escaping in TEXT values (RFC 5545 3.3.11).
+fn ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+fn ics_time(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Lines longer than 75 octets are folded onto continuation lines starting with a space, never in
+// the middle of a character (RFC 5545 3.1).
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+// Stays the same for as long as the slot does, even as it's moved around, so calendar apps update
+// events rather than duplicating them.
+fn event_uid(schedule: &Schedule, slot: &ScheduleSlot) -> String {
+    if !slot.id.is_nil() {
+        return format!("{}-{}@baing", schedule.id, slot.id);
+    }
+
+    // Slots saved before they had ids go by what's on and when, which is as stable as they get.
+    let episode = slot
+        .episode
+        .as_ref()
+        .map(|e| format!("-s{}e{}", e.season, e.episode))
+        .unwrap_or_default();
+    let what: String = slot
+        .media
+        .identity()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+
+    format!(
+        "{}-{}-{what}{episode}@baing",
+        schedule.id,
+        slot.start.timestamp()
+    )
+}
+
+fn event_lines(schedule: &Schedule, slot: &ScheduleSlot, stamp: DateTime<Utc>) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", event_uid(schedule, slot)),
+        format!("DTSTAMP:{}", ics_time(stamp)),
+        format!("DTSTART:{}", ics_time(slot.start)),
+        format!("DURATION:PT{}M", slot.duration),
+        format!("SUMMARY:{}", ics_text(&slot.title())),
+    ];
+    if let Some(overview) = slot.media.overview() {
+        lines.push(format!("DESCRIPTION:{}", ics_text(overview)));
+    }
+    if let Some(link) = slot.media.link() {
+        lines.push(format!("URL:{link}"));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines
+}
+
+// Renders a schedule as an iCalendar feed, one event per slot.
+pub fn render_calendar(schedule: &Schedule) -> String {
+    let stamp = Utc::now();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//baing//schedules//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", ics_text(&schedule.name)),
+        format!("REFRESH-INTERVAL;VALUE=DURATION:{REFRESH_INTERVAL}"),
+        format!("X-PUBLISHED-TTL:{REFRESH_INTERVAL}"),
+    ];
+    for slot in &schedule.schedule.slots {
+        lines.extend(event_lines(schedule, slot, stamp));
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut body: String = lines
+        .iter()
+        .map(|l| fold_line(l))
+        .collect::<Vec<String>>()
+        .join("\r\n");
+    body.push_str("\r\n");
+    body
+}
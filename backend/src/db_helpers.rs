@@ -1,5 +1,22 @@
 use crate::AppState;
-use common::model::collections::UserCollection;
+use chrono::{DateTime, Duration, Utc};
+use common::model::channel::channel_lineup;
+use common::model::collections::{Media, MediaCollection, ShareType, UserCollection};
+use common::model::friends::{Friendship, FriendshipStatus};
+use common::model::members::CollectionRole;
+use common::model::playlist::StreamUrls;
+use common::model::progress::SeriesProgress;
+use common::model::revisions::{diff_entries, CollectionRevision};
+use common::model::rules::{resolve_smart_collections, CollectionRules};
+use common::model::schedule_generator::ScheduleItem;
+use common::model::schedule_recurrence::{materialize, Recurrence, RuleSources, TOP_UP_DAYS};
+use common::model::schedules::Schedule;
+use common::model::special::SpecialCollection;
+use common::model::tags::Tags;
+use log::{debug, warn};
+use serde_json::json;
+use sqlx::PgConnection;
+use std::collections::{HashMap, HashSet};
 use std::error;
 use uuid::Uuid;
 
@@ -10,7 +27,7 @@ pub async fn get_user_special_collections(
     let collections = sqlx::query_as!(
         UserCollection,
         r#"SELECT
-        id, owner_id,name, created_at, active, collection, locked, tags, special,
+        id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
         CAST(sharing as text)
         FROM collections
         WHERE
@@ -25,3 +42,757 @@ pub async fn get_user_special_collections(
 
     Ok(collections?)
 }
+
+// Creates whichever special collections the user doesn't have yet. Cheap when they're all there,
+// so it runs on every login and new kinds reach existing users without a backfill.
+pub async fn ensure_special_collections(owner_id: Uuid, app: &AppState) -> Result<(), sqlx::Error> {
+    let keys: Vec<String> = SpecialCollection::ALL
+        .iter()
+        .map(|s| s.key().to_string())
+        .collect();
+    let names: Vec<String> = SpecialCollection::ALL
+        .iter()
+        .map(|s| s.name().to_string())
+        .collect();
+    let tags: Vec<String> = SpecialCollection::ALL
+        .iter()
+        .map(|s| s.tag().to_string())
+        .collect();
+
+    sqlx::query!(
+        r#"INSERT INTO collections
+            (owner_id, name, active, sharing, collection, locked, tags, special)
+        SELECT
+            $1, s.name, true, 'private'::SHARE_TYPE, '{"entries": []}'::jsonb, true,
+            jsonb_build_array(s.tag), s.special
+        FROM UNNEST($2::text[], $3::text[], $4::text[]) AS s(special, name, tag)
+        ON CONFLICT (owner_id, special) WHERE special IS NOT NULL DO NOTHING
+        "#,
+        owner_id,
+        &keys,
+        &names,
+        &tags
+    )
+    .execute(&app.db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_user_collections(
+    owner_id: Uuid,
+    app: &AppState,
+) -> Result<Vec<UserCollection>, Box<dyn error::Error>> {
+    let collections = sqlx::query_as!(
+        UserCollection,
+        r#"SELECT
+        id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+        CAST(sharing as text)
+        FROM collections
+        WHERE owner_id = $1
+        "#,
+        owner_id
+    )
+    .fetch_all(&app.db)
+    .await;
+
+    Ok(resolve_smart_collections(collections?))
+}
+
+// Replaces a (non-smart) collection's entries and records what changed as a revision, in one
+// transaction. `collection` only holds `member`'s own take on the entries, so everyone else's is
+// kept as it is now. `None` if there's no such collection `actor_id` may edit.
+pub async fn save_collection_entries(
+    actor_id: Uuid,
+    collection_id: Uuid,
+    member: Option<Uuid>,
+    collection: &MediaCollection,
+    app: &AppState,
+) -> Result<Option<UserCollection>, sqlx::Error> {
+    let mut tx = app.db.begin().await?;
+
+    let updated = edit_collection_entries(actor_id, collection_id, &mut tx, |entries| {
+        let mut collection = collection.clone();
+        collection.keep_personal(entries, member);
+        *entries = collection;
+    })
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+// Takes `revision`'s changes back out of the collection as it is now, recording that as the undo
+// of it. `None` if there's no such collection `actor_id` may edit.
+pub async fn undo_collection_revision(
+    actor_id: Uuid,
+    collection_id: Uuid,
+    revision: &CollectionRevision,
+    app: &AppState,
+) -> Result<Option<UserCollection>, sqlx::Error> {
+    let mut tx = app.db.begin().await?;
+
+    let updated = update_collection_entries(
+        actor_id,
+        collection_id,
+        &mut tx,
+        Revision::Undo(revision.id),
+        |entries| *entries = revision.revert(entries),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+// Copies the TMDB details of `looked_up` over the matching entries, leaving everything else
+// about the collection as it is now. `None` if there's no such collection `actor_id` may edit.
+pub async fn save_entry_details(
+    actor_id: Uuid,
+    collection_id: Uuid,
+    looked_up: &MediaCollection,
+    app: &AppState,
+) -> Result<Option<UserCollection>, sqlx::Error> {
+    let by_identity: HashMap<String, &Media> = looked_up
+        .entries
+        .iter()
+        .map(|m| (m.identity(), m))
+        .collect();
+
+    let mut tx = app.db.begin().await?;
+
+    // Details aren't an edit anyone would want to undo, so they leave no revision.
+    let updated = update_collection_entries(
+        actor_id,
+        collection_id,
+        &mut tx,
+        Revision::Skip,
+        |entries| {
+            for media in entries.entries.iter_mut() {
+                let found = by_identity.get(&media.identity()).copied();
+                match (media, found) {
+                    (Media::Movie(m), Some(Media::Movie(found))) => {
+                        m.details = found.details.clone()
+                    }
+                    (Media::TvShow(t), Some(Media::TvShow(found))) => {
+                        t.details = found.details.clone()
+                    }
+                    _ => {}
+                }
+            }
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+// Locks a (non-smart) collection, applies `edit` to its entries and saves them, with a revision if
+// anything changed. Runs on the caller's transaction, so several collections can change together.
+// Only the owner and editors get through; new entries are attributed to `actor_id`.
+pub async fn edit_collection_entries<F>(
+    actor_id: Uuid,
+    collection_id: Uuid,
+    conn: &mut PgConnection,
+    edit: F,
+) -> Result<Option<UserCollection>, sqlx::Error>
+where
+    F: FnOnce(&mut MediaCollection),
+{
+    update_collection_entries(actor_id, collection_id, conn, Revision::Record, edit).await
+}
+
+// How an edit shows up in the collection's history.
+enum Revision {
+    // Left out, for changes nobody would want to undo.
+    Skip,
+    Record,
+    // Recorded as taking back the revision with this id.
+    Undo(Uuid),
+}
+
+async fn update_collection_entries<F>(
+    actor_id: Uuid,
+    collection_id: Uuid,
+    conn: &mut PgConnection,
+    revision: Revision,
+    edit: F,
+) -> Result<Option<UserCollection>, sqlx::Error>
+where
+    F: FnOnce(&mut MediaCollection),
+{
+    let before: MediaCollection = match sqlx::query!(
+        r#"SELECT c.collection FROM collections c
+        WHERE
+            c.id = $2 AND c.rules IS NULL
+        AND (
+            c.owner_id = $1
+            OR EXISTS (
+                SELECT 1 FROM collection_members m
+                WHERE m.collection_id = c.id AND m.user_id = $1 AND m.role = 'editor'
+            )
+        )
+        FOR UPDATE OF c
+        "#,
+        actor_id,
+        collection_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    {
+        Some(rec) => rec.collection.into(),
+        None => return Ok(None),
+    };
+
+    let mut collection = before.clone();
+    edit(&mut collection);
+
+    let present: HashSet<String> = before.entries.iter().map(|m| m.identity()).collect();
+    collection
+        .entries
+        .iter_mut()
+        .filter(|m| !present.contains(&m.identity()))
+        .for_each(|m| {
+            m.entry_meta_mut().added_by.get_or_insert(actor_id);
+        });
+
+    let updated = sqlx::query_as!(
+        UserCollection,
+        r#"
+        UPDATE
+            collections
+        SET
+            collection = $1
+        WHERE
+            id = $2
+        RETURNING
+            id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+            CAST(sharing as text)
+        "#,
+        serde_json::json!(collection),
+        collection_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let reverts = match revision {
+        Revision::Skip => return Ok(Some(updated)),
+        Revision::Record => None,
+        Revision::Undo(id) => Some(id),
+    };
+    // An undo is kept even when there was nothing left to take back, so it isn't offered again.
+    if before != collection || reverts.is_some() {
+        let (added, removed) = diff_entries(&before, &collection);
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                collection_revisions (collection_id, actor_id, added, removed, snapshot, reverts)
+            VALUES
+                ($1, $2, $3, $4, $5, $6)
+            "#,
+            collection_id,
+            actor_id,
+            serde_json::json!(MediaCollection { entries: added }),
+            serde_json::json!(MediaCollection { entries: removed }),
+            serde_json::json!(collection),
+            reverts
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(Some(updated))
+}
+
+// Writes back a collection's own tags. Entries' tags go through `edit_collection_entries`.
+pub async fn save_collection_tags(
+    owner_id: Uuid,
+    collection_id: Uuid,
+    tags: &Tags,
+    conn: &mut PgConnection,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE
+            collections
+        SET
+            tags = $1
+        WHERE
+            owner_id = $2 AND id = $3
+        "#,
+        serde_json::json!(tags),
+        owner_id,
+        collection_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn save_collection_rules(
+    owner_id: Uuid,
+    collection_id: Uuid,
+    rules: &CollectionRules,
+    conn: &mut PgConnection,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE
+            collections
+        SET
+            rules = $1
+        WHERE
+            owner_id = $2 AND id = $3 AND rules IS NOT NULL
+        "#,
+        serde_json::json!(rules),
+        owner_id,
+        collection_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_friendship_between(
+    user_id: Uuid,
+    other_id: Uuid,
+    app: &AppState,
+) -> Result<Option<Friendship>, Box<dyn error::Error>> {
+    let friendship = sqlx::query_as!(
+        Friendship,
+        r#"SELECT
+            id, requester_id, addressee_id, CAST(status as text), created_at, updated_at
+        FROM friendships
+        WHERE
+            (requester_id = $1 AND addressee_id = $2)
+        OR
+            (requester_id = $2 AND addressee_id = $1)
+        "#,
+        user_id,
+        other_id
+    )
+    .fetch_optional(&app.db)
+    .await;
+
+    Ok(friendship?)
+}
+
+pub async fn are_friends(
+    user_id: Uuid,
+    other_id: Uuid,
+    app: &AppState,
+) -> Result<bool, Box<dyn error::Error>> {
+    let friendship = get_friendship_between(user_id, other_id, app).await?;
+
+    Ok(friendship.is_some_and(|f| f.friendship_status() == FriendshipStatus::Accepted))
+}
+
+pub async fn can_view_collection(
+    viewer_id: Option<Uuid>,
+    collection: &UserCollection,
+    app: &AppState,
+) -> Result<bool, Box<dyn error::Error>> {
+    match (viewer_id, collection.share_type()) {
+        (Some(viewer_id), _) if viewer_id == collection.owner_id => Ok(true),
+        (_, ShareType::Public) => Ok(true),
+        (Some(viewer_id), share_type) => {
+            // Members see the collection however it's shared.
+            if get_collection_role(viewer_id, collection.id, app)
+                .await?
+                .is_some()
+            {
+                return Ok(true);
+            }
+            match share_type {
+                ShareType::Friends => are_friends(viewer_id, collection.owner_id, app).await,
+                _ => Ok(false),
+            }
+        }
+        _ => Ok(false),
+    }
+}
+
+// Looks up any user's collection, returning it only if `viewer_id` is allowed to see it.
+pub async fn get_shared_collection(
+    collection_id: Uuid,
+    viewer_id: Option<Uuid>,
+    app: &AppState,
+) -> Result<Option<UserCollection>, Box<dyn error::Error>> {
+    let collection = sqlx::query_as!(
+        UserCollection,
+        r#"SELECT
+        id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+        CAST(sharing as text)
+        FROM collections
+        WHERE id = $1
+        "#,
+        collection_id
+    )
+    .fetch_optional(&app.db)
+    .await?;
+
+    let mut col = match collection {
+        Some(col) => col,
+        None => return Ok(None),
+    };
+
+    if !can_view_collection(viewer_id, &col, app).await? {
+        return Ok(None);
+    }
+
+    if col.is_smart() {
+        let sources = get_user_collections(col.owner_id, app).await?;
+        col.collection.entries = col.rules.evaluate(&sources);
+    }
+
+    // The owner and members see their own take on the entries; anyone else, nobody's.
+    match viewer_id {
+        Some(viewer_id) if viewer_id == col.owner_id => col.collection.personalize(None),
+        Some(viewer_id) if get_collection_role(viewer_id, col.id, app).await?.is_some() => {
+            col.collection.personalize(Some(viewer_id))
+        }
+        _ => col.collection.strip_personal(),
+    }
+
+    Ok(Some(col))
+}
+
+// `None` if the collection doesn't exist or `user_id` has no part in it.
+pub async fn get_collection_role(
+    user_id: Uuid,
+    collection_id: Uuid,
+    app: &AppState,
+) -> Result<Option<CollectionRole>, sqlx::Error> {
+    let role = sqlx::query!(
+        r#"SELECT
+            CASE
+                WHEN c.owner_id = $1 THEN 'owner'
+                ELSE CAST(m.role as text)
+            END as role
+        FROM collections c
+        LEFT JOIN collection_members m ON m.collection_id = c.id AND m.user_id = $1
+        WHERE c.id = $2
+        "#,
+        user_id,
+        collection_id
+    )
+    .fetch_optional(&app.db)
+    .await?;
+
+    Ok(role.and_then(|r| CollectionRole::from_name(r.role.as_deref())))
+}
+
+// Any collection by id, smart ones resolved against their owner's collections. Callers check
+// access first, with `get_collection_role`.
+pub async fn get_collection(
+    collection_id: Uuid,
+    app: &AppState,
+) -> Result<Option<UserCollection>, Box<dyn error::Error>> {
+    let collection = sqlx::query_as!(
+        UserCollection,
+        r#"SELECT
+        id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+        CAST(sharing as text)
+        FROM collections
+        WHERE id = $1
+        "#,
+        collection_id
+    )
+    .fetch_optional(&app.db)
+    .await?;
+
+    let mut col = match collection {
+        Some(col) => col,
+        None => return Ok(None),
+    };
+
+    if col.is_smart() {
+        let sources = get_user_collections(col.owner_id, app).await?;
+        col.collection.entries = col.rules.evaluate(&sources);
+    }
+
+    Ok(Some(col))
+}
+
+// Other people's collections `user_id` has been let into.
+pub async fn get_member_collections(
+    user_id: Uuid,
+    app: &AppState,
+) -> Result<Vec<UserCollection>, Box<dyn error::Error>> {
+    let mut collections = sqlx::query_as!(
+        UserCollection,
+        r#"SELECT
+        c.id, c.owner_id, c.name, c.created_at, c.active, c.collection, c.locked, c.tags,
+        c.special, c.rules, c.ranked, CAST(c.sharing as text)
+        FROM collections c
+        JOIN collection_members m ON m.collection_id = c.id
+        WHERE m.user_id = $1
+        ORDER BY c.name
+        "#,
+        user_id
+    )
+    .fetch_all(&app.db)
+    .await?;
+
+    for col in collections.iter_mut().filter(|col| col.is_smart()) {
+        let sources = get_user_collections(col.owner_id, app).await?;
+        col.collection.entries = col.rules.evaluate(&sources);
+    }
+
+    Ok(collections)
+}
+
+// Just the row. Recurring schedules are topped up with `topped_up`, once the caller knows the
+// viewer may see it, so anonymous reads don't write.
+pub async fn get_schedule(
+    schedule_id: Uuid,
+    app: &AppState,
+) -> Result<Option<Schedule>, sqlx::Error> {
+    sqlx::query_as!(
+        Schedule,
+        r#"SELECT
+        id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+        source_collection
+        FROM schedules
+        WHERE id = $1
+        "#,
+        schedule_id
+    )
+    .fetch_optional(&app.db)
+    .await
+}
+
+// A recurring schedule's rules and how far ahead they've been laid out; `None` if it doesn't recur.
+pub async fn get_recurrence(
+    schedule_id: Uuid,
+    app: &AppState,
+) -> Result<Option<(Recurrence, DateTime<Utc>)>, Box<dyn error::Error>> {
+    let row = sqlx::query!(
+        "SELECT recurrence, materialized_until FROM schedule_recurrence WHERE schedule_id = $1",
+        schedule_id
+    )
+    .fetch_optional(&app.db)
+    .await?;
+
+    match row {
+        Some(row) => Ok(Some((
+            serde_json::from_value(row.recurrence)?,
+            row.materialized_until,
+        ))),
+        None => Ok(None),
+    }
+}
+
+// Tops a recurring schedule's slots up to its horizon when it's due, saving them. Anything else
+// comes back as it was, locked schedules included, so locking one pauses the routine.
+async fn materialize_recurring(
+    schedule: Schedule,
+    app: &AppState,
+) -> Result<Schedule, Box<dyn error::Error>> {
+    if schedule.locked || schedule.is_channel() {
+        return Ok(schedule);
+    }
+    let Some((recurrence, materialized_until)) = get_recurrence(schedule.id, app).await? else {
+        return Ok(schedule);
+    };
+    let now = Utc::now();
+    let until = now + Duration::days(recurrence.horizon_days as i64);
+    if materialized_until > until - Duration::days(TOP_UP_DAYS) {
+        return Ok(schedule);
+    }
+
+    // Only what the owner can still see.
+    let mut sources = RuleSources {
+        collections: HashMap::new(),
+        progress: get_series_progress(schedule.owner_id, app).await?,
+    };
+    for id in recurrence
+        .rules
+        .iter()
+        .filter_map(|r| r.source.collection())
+    {
+        if get_collection_role(schedule.owner_id, id, app)
+            .await?
+            .is_none()
+        {
+            continue;
+        }
+        if let Some(col) = get_collection(id, app).await? {
+            sources.collections.insert(id, col);
+        }
+    }
+
+    let mut tx = app.db.begin().await?;
+
+    // Whoever moves `materialized_until` on first lays the slots out; anyone racing them gets theirs.
+    let claimed = sqlx::query_scalar!(
+        r#"
+        UPDATE
+            schedule_recurrence
+        SET
+            materialized_until = $3
+        WHERE
+            schedule_id = $1 AND materialized_until = $2
+        RETURNING
+            schedule_id
+        "#,
+        schedule.id,
+        materialized_until,
+        until
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    if claimed.is_none() {
+        return Ok(get_schedule(schedule.id, app).await?.unwrap_or(schedule));
+    }
+
+    // Laid out over the slots as they are now, held until we're done so an edit or re-flow made in
+    // the meantime isn't overwritten.
+    let Some(current) = sqlx::query_as!(
+        Schedule,
+        r#"SELECT
+        id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+        source_collection
+        FROM schedules
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+        schedule.id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(schedule);
+    };
+    if current.locked {
+        return Ok(current);
+    }
+
+    // Nobody was looking while it lapsed, so nothing is laid out in the past.
+    let (new_slots, missed) = materialize(
+        &recurrence,
+        &current.schedule,
+        &sources,
+        materialized_until.max(now),
+        until,
+    );
+    if !missed.is_empty() {
+        debug!("Recurring schedule {} missed: {missed:?}", schedule.id);
+    }
+    let mut slots = current.schedule;
+    slots.slots.extend(new_slots);
+    slots.sort();
+
+    let saved = sqlx::query_as!(
+        Schedule,
+        r#"
+        UPDATE
+            schedules
+        SET
+            schedule = $2
+        WHERE
+            id = $1
+        RETURNING
+            id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+            source_collection
+        "#,
+        schedule.id,
+        json!(slots)
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(saved)
+}
+
+// `materialize_recurring` for reads, after the caller has checked the viewer may see it. If laying
+// out fails, the schedule comes back as it was.
+pub async fn topped_up(schedule: Schedule, app: &AppState) -> Schedule {
+    let schedule_id = schedule.id;
+    match materialize_recurring(schedule.clone(), app).await {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            warn!("Laying out recurring schedule {schedule_id} failed: {e}");
+            schedule
+        }
+    }
+}
+
+// What a channel loops over today, straight from its collection; nothing if that's gone.
+pub async fn get_channel_lineup(
+    channel: &Schedule,
+    app: &AppState,
+) -> Result<Vec<ScheduleItem>, Box<dyn error::Error>> {
+    // A collection the owner has lost access to plays nothing.
+    let source = match channel.source_collection {
+        Some(id)
+            if get_collection_role(channel.owner_id, id, app)
+                .await?
+                .is_some() =>
+        {
+            get_collection(id, app).await?
+        }
+        _ => None,
+    };
+    // Channels can be public, so nobody's ratings or notes go out with what's on.
+    Ok(source
+        .map(|mut col| {
+            col.collection.strip_personal();
+            channel_lineup(&col, Utc::now().date_naive())
+        })
+        .unwrap_or_default())
+}
+
+// Where a schedule's playlist entries point; nothing set up yet is the same as empty.
+pub async fn get_stream_urls(schedule_id: Uuid, app: &AppState) -> Result<StreamUrls, sqlx::Error> {
+    let urls = sqlx::query_scalar!(
+        "SELECT urls FROM schedule_stream_urls WHERE schedule_id = $1",
+        schedule_id
+    )
+    .fetch_optional(&app.db)
+    .await?;
+    Ok(urls.into())
+}
+
+// Schedules are shared the same way collections are, minus the members.
+pub async fn can_view_schedule(
+    viewer_id: Option<Uuid>,
+    schedule: &Schedule,
+    app: &AppState,
+) -> Result<bool, Box<dyn error::Error>> {
+    match (viewer_id, schedule.share_type()) {
+        (Some(viewer_id), _) if viewer_id == schedule.owner_id => Ok(true),
+        (_, ShareType::Public) => Ok(true),
+        (Some(viewer_id), ShareType::Friends) => {
+            are_friends(viewer_id, schedule.owner_id, app).await
+        }
+        _ => Ok(false),
+    }
+}
+
+// Every show the user has marked something of, most recently watched first.
+pub async fn get_series_progress(
+    user_id: Uuid,
+    app: &AppState,
+) -> Result<Vec<SeriesProgress>, sqlx::Error> {
+    sqlx::query_as!(
+        SeriesProgress,
+        r#"SELECT
+        user_id, show_key, show, watched, updated_at
+        FROM series_progress
+        WHERE user_id = $1
+        ORDER BY updated_at DESC
+        "#,
+        user_id
+    )
+    .fetch_all(&app.db)
+    .await
+}
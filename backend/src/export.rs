@@ -0,0 +1,121 @@
+use common::model::collections::UserCollection;
+use common::model::export::{CollectionExport, ExportFormat};
+
+// Quote a CSV field only when it needs it (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_row(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|f| csv_field(f))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn to_csv(export: &CollectionExport) -> String {
+    let mut rows = vec![csv_row(&[
+        "collection".to_string(),
+        "special".to_string(),
+        "media_type".to_string(),
+        "title".to_string(),
+        "name".to_string(),
+        "year".to_string(),
+        "tmdb_id".to_string(),
+        "reason".to_string(),
+        "query".to_string(),
+        "streamers".to_string(),
+        "watch_status".to_string(),
+        "watched_dates".to_string(),
+        "rating".to_string(),
+        "notes".to_string(),
+        "rewatch_count".to_string(),
+    ])];
+
+    rows.extend(export.entries.iter().map(|e| {
+        csv_row(&[
+            e.collection.to_owned(),
+            e.special.to_owned().unwrap_or_default(),
+            e.media_type.to_owned(),
+            e.title.to_owned(),
+            e.name.to_owned(),
+            e.year.map(|y| y.to_string()).unwrap_or_default(),
+            e.tmdb_id.map(|id| id.to_string()).unwrap_or_default(),
+            e.reason.to_owned().unwrap_or_default(),
+            e.query.to_owned().unwrap_or_default(),
+            e.streamers.to_owned().unwrap_or_default(),
+            e.watch_status.to_string(),
+            e.watched_dates
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<String>>()
+                .join(";"),
+            e.rating.map(|r| r.to_string()).unwrap_or_default(),
+            e.notes.to_owned().unwrap_or_default(),
+            e.rewatch_count.to_string(),
+        ])
+    }));
+
+    rows.join("\r\n")
+}
+
+// Letterboxd's importer only understands films, so everything else is dropped.
+// See: https://letterboxd.com/about/importing-data/
+fn to_letterboxd(export: &CollectionExport) -> String {
+    let mut rows = vec![csv_row(&[
+        "tmdbID".to_string(),
+        "Title".to_string(),
+        "Year".to_string(),
+        "Tags".to_string(),
+        "Rating10".to_string(),
+        "WatchedDate".to_string(),
+    ])];
+
+    rows.extend(
+        export
+            .entries
+            .iter()
+            .filter(|e| e.media_type == "movie")
+            .map(|e| {
+                let mut tags = vec![e.collection.to_owned()];
+                if let Some(special) = &e.special {
+                    tags.push(special.to_owned());
+                }
+
+                csv_row(&[
+                    e.tmdb_id.map(|id| id.to_string()).unwrap_or_default(),
+                    e.name.to_owned(),
+                    e.year.map(|y| y.to_string()).unwrap_or_default(),
+                    tags.join(", "),
+                    e.rating.map(|r| r.to_string()).unwrap_or_default(),
+                    e.last_watched().map(|d| d.to_string()).unwrap_or_default(),
+                ])
+            }),
+    );
+
+    rows.join("\r\n")
+}
+
+// Renders the given collections, personalized for whoever's exporting them, returning the content
+// type, file extension and body.
+pub fn render_collections(
+    format: ExportFormat,
+    collections: &[UserCollection],
+) -> (&'static str, &'static str, String) {
+    let export = CollectionExport::from(collections);
+
+    match format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv", to_csv(&export)),
+        ExportFormat::Letterboxd => ("text/csv; charset=utf-8", "csv", to_letterboxd(&export)),
+        ExportFormat::Json => (
+            "application/json",
+            "json",
+            serde_json::to_string_pretty(&export).unwrap_or_default(),
+        ),
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use common::model::playlist::StreamUrls;
+use common::model::schedule_generator::ScheduleItem;
+use common::model::schedules::{Schedule, ScheduleSlot};
+
+fn xml_text(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xmltv_time(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%d%H%M%S +0000").to_string()
+}
+
+// What players match the guide and the playlist up on.
+fn channel_id(schedule: &Schedule) -> String {
+    format!("{}.baing", schedule.id)
+}
+
+fn programme(channel: &str, slot: &ScheduleSlot) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "  <programme start=\"{}\" stop=\"{}\" channel=\"{channel}\">",
+            xmltv_time(slot.start),
+            xmltv_time(slot.end())
+        ),
+        format!(
+            "    <title lang=\"en\">{}</title>",
+            xml_text(slot.media.name())
+        ),
+    ];
+    if let Some(name) = slot.episode.as_ref().and_then(|e| e.name.as_deref()) {
+        lines.push(format!(
+            "    <sub-title lang=\"en\">{}</sub-title>",
+            xml_text(name)
+        ));
+    }
+    if let Some(overview) = slot.media.overview() {
+        lines.push(format!(
+            "    <desc lang=\"en\">{}</desc>",
+            xml_text(overview)
+        ));
+    }
+    if let Some(year) = slot.media.year() {
+        lines.push(format!("    <date>{year}</date>"));
+    }
+    if let Some(episode) = &slot.episode {
+        // xmltv_ns counts from zero.
+        lines.push(format!(
+            "    <episode-num system=\"xmltv_ns\">{}.{}.</episode-num>",
+            episode.season.saturating_sub(1),
+            episode.episode.saturating_sub(1)
+        ));
+        lines.push(format!(
+            "    <episode-num system=\"onscreen\">S{:02}E{:02}</episode-num>",
+            episode.season, episode.episode
+        ));
+    }
+    if let Some(link) = slot.media.link() {
+        lines.push(format!("    <url>{}</url>", xml_text(&link)));
+    }
+    lines.push("  </programme>".to_string());
+    lines
+}
+
+// Renders slots of a schedule as an XMLTV guide with the schedule as its one channel.
+pub fn render_xmltv(schedule: &Schedule, slots: &[ScheduleSlot]) -> String {
+    let channel = channel_id(schedule);
+    let mut lines = vec![
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string(),
+        "<!DOCTYPE tv SYSTEM \"xmltv.dtd\">".to_string(),
+        "<tv generator-info-name=\"baing\">".to_string(),
+        format!("  <channel id=\"{channel}\">"),
+        format!(
+            "    <display-name lang=\"en\">{}</display-name>",
+            xml_text(&schedule.name)
+        ),
+        "  </channel>".to_string(),
+    ];
+    for slot in slots {
+        lines.extend(programme(&channel, slot));
+    }
+    lines.push("</tv>".to_string());
+
+    let mut body = lines.join("\n");
+    body.push('\n');
+    body
+}
+
+// Attribute values can't hold quotes and nothing in an entry can span lines.
+fn m3u_text(value: &str) -> String {
+    value.replace('"', "'").replace(['\r', '\n'], " ")
+}
+
+// Renders an M3U playlist of the schedule's entries, in order, each pointing wherever the
+// schedule's stream URLs say. Entries with nowhere to point are left as comments to fill in.
+pub fn render_m3u(schedule: &Schedule, items: &[ScheduleItem], urls: &StreamUrls) -> String {
+    let channel = channel_id(schedule);
+    let group = m3u_text(&schedule.name);
+    let mut lines = vec!["#EXTM3U".to_string(), format!("#PLAYLIST:{group}")];
+    for item in items {
+        let title = m3u_text(&item.title());
+        match urls.url_for(&item.media, item.episode.as_ref()) {
+            Some(url) => {
+                lines.push(format!(
+                    "#EXTINF:{} tvg-id=\"{channel}\" tvg-name=\"{title}\" group-title=\"{group}\",{title}",
+                    item.duration as u64 * 60
+                ));
+                lines.push(m3u_text(&url));
+            }
+            None => lines.push(format!("# {title}: no stream URL")),
+        }
+    }
+
+    let mut body = lines.join("\n");
+    body.push('\n');
+    body
+}
@@ -1,5 +1,5 @@
 use crate::ai::{ai_movie, ai_online_content, ai_tv, ai_youtube};
-use crate::db_helpers::get_user_special_collections;
+use crate::db_helpers::{get_user_collections, get_user_special_collections};
 use crate::{jwt_auth, AppState};
 use actix_web::web::service;
 use actix_web::{get, web, HttpResponse, Responder};
@@ -26,19 +26,18 @@ async fn get_discovery_movies_rand_n(
     data: web::Data<AppState>,
 ) -> impl Responder {
     let count = path.into_inner();
-    let user_special_collections = get_user_special_collections(jwt_guard.user.id, &data)
+    // All collections, not just the special ones, so ratings and watch history count too.
+    let user_collections = get_user_collections(jwt_guard.user.id, &data)
         .await
-        .expect("Missing User's Special Collections?");
+        .expect("Missing User's Collections?");
 
     let query_type = match !dq.query.is_empty() {
-        false => {
-            ai_movie::get_random(&data.api_keys, count, user_special_collections.to_owned()).await
-        }
+        false => ai_movie::get_random(&data.api_keys, count, user_collections.to_owned()).await,
         true => {
             ai_movie::get_guided(
                 &data.api_keys,
                 count,
-                user_special_collections.to_owned(),
+                user_collections.to_owned(),
                 &dq.query,
             )
             .await
@@ -72,18 +71,17 @@ async fn get_discovery_tv_shows_rand_n(
     data: web::Data<AppState>,
 ) -> impl Responder {
     let count = path.into_inner().0;
-    let user_special_collections = get_user_special_collections(jwt_guard.user.id, &data)
+    // All collections, not just the special ones, so ratings and watch history count too.
+    let user_collections = get_user_collections(jwt_guard.user.id, &data)
         .await
-        .expect("Missing User's Special Collections?");
+        .expect("Missing User's Collections?");
     let query_type = match !dq.query.is_empty() {
-        false => {
-            ai_tv::get_random(&data.api_keys, count, user_special_collections.to_owned()).await
-        }
+        false => ai_tv::get_random(&data.api_keys, count, user_collections.to_owned()).await,
         true => {
             ai_tv::get_guided(
                 &data.api_keys,
                 count,
-                user_special_collections.to_owned(),
+                user_collections.to_owned(),
                 &dq.query,
             )
             .await
@@ -1,3 +1,4 @@
+use crate::db_helpers::ensure_special_collections;
 use crate::{jwt_auth, token, AppState};
 use actix_web::{
     cookie::{time::Duration as ActixWebDuration, Cookie},
@@ -64,19 +65,8 @@ async fn register_user_handler(
                 "user": filtered_user
             })});
 
-            // Insert some default special collections.
-            let _insert_thumbs_up_collection = sqlx::query!(
-                r#"insert into public.collections (owner_id, name, active, sharing, collection, locked, tags, special) values ($1, '👍 Thumbs Up', true, 'private', '{"entries": []}', true, '["Thumbs Up"]', 'thumbsup');"#,
-                user_id
-            ).fetch_one(&data.db).await;
-            let _insert_thumbs_down_collection = sqlx::query!(
-                r#"insert into public.collections (owner_id, name, active, sharing, collection, locked, tags, special) values ($1, '👎 Thumbs Down', true, 'private', '{"entries": []}', true, '["Thumbs Down"]', 'thumbsdown');"#,
-                user_id
-            ).fetch_one(&data.db).await;
-            let _insert_skipped_collection = sqlx::query!(
-                r#"insert into public.collections (owner_id, name, active, sharing, collection, locked, tags, special) values ($1, '🤔 Skipped', true, 'private', '{"entries": []}', true, '["Skipped"]', 'skipped');"#,
-                user_id
-            ).fetch_one(&data.db).await;
+            // Insert the default special collections.
+            let _insert_special_collections = ensure_special_collections(user_id, &data).await;
 
             HttpResponse::Ok().json(user_response)
         }
@@ -120,6 +110,10 @@ async fn login_user_handler(
             .json(serde_json::json!({"status": "fail", "message": "Invalid email or password"}));
     }
 
+    // Accounts made before a special collection existed get it now. Not worth failing the login
+    // over, discovery will just be missing that button's collection.
+    let _ensure_special_collections = ensure_special_collections(user.id, &data).await;
+
     let access_token_details = match token::generate_jwt_token(
         user.id.to_owned(),
         data.env.access_token_max_age,
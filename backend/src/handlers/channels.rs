@@ -0,0 +1,121 @@
+use crate::db_helpers::{can_view_schedule, get_channel_lineup, get_schedule};
+use crate::handlers::schedules::{load_source_collection, schedule_response};
+use crate::{jwt_auth, AppState};
+use actix_web::{get, post, web, HttpResponse, Responder};
+use chrono::Utc;
+use common::model::channel::{
+    channel_now, ChannelGuide, ChannelGuideResponse, NewChannelSchema, DEFAULT_UP_NEXT, MAX_UP_NEXT,
+};
+use common::model::schedules::{Schedule, ScheduleSlots};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+struct NowQuery {
+    next: Option<usize>,
+}
+
+// A channel is a schedule that loops over a collection from the moment it's made.
+#[post("/channels")]
+async fn post_channel_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<NewChannelSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let tmdb_key = jwt_guard.user.tmdb_api_key.filter(|k| !k.is_empty());
+
+    if body.name.trim().is_empty() {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Channels need a name"}));
+    }
+    // Looks the runtimes up now, so tuning in never has to wait on TMDB.
+    if let Err(res) =
+        load_source_collection(user_id, body.collection, tmdb_key.as_deref(), &data).await
+    {
+        return res;
+    }
+
+    let channel = sqlx::query_as!(
+        Schedule,
+        r#"
+        INSERT INTO
+            schedules (owner_id, name, active, sharing, schedule, locked, tags, source_collection)
+        VALUES
+            ($1, $2, true, 'private', $3, false, '[]', $4)
+        RETURNING
+            id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+            source_collection
+        "#,
+        user_id,
+        body.name.trim(),
+        json!(ScheduleSlots::default()),
+        body.collection
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    schedule_response(channel)
+}
+
+// What's on now and next. Worked out from the clock each time, so everyone tuned in sees the same
+// thing; editing the collection moves everything after the change along.
+#[get("/channel/{id}/now")]
+async fn get_channel_now_handler(
+    jwt_guard: Option<jwt_auth::JwtMiddleware>,
+    path: web::Path<Uuid>,
+    query: web::Query<NowQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let channel = match get_schedule(path.into_inner(), &data).await {
+        Ok(Some(schedule)) if schedule.is_channel() => schedule,
+        Ok(_) => {
+            return HttpResponse::NotFound()
+                .json(json!({"status": "fail", "message": "Channel not found"}));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    match can_view_schedule(jwt_guard.map(|g| g.user.id), &channel, &data).await {
+        Ok(true) => {}
+        // Same as not existing, so private channels can't be probed for.
+        Ok(false) => {
+            return HttpResponse::NotFound()
+                .json(json!({"status": "fail", "message": "Channel not found"}));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    }
+
+    // Whoever can see the channel sees what's on it, whatever the collection's own sharing.
+    let lineup = match get_channel_lineup(&channel, &data).await {
+        Ok(lineup) => lineup,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let now = Utc::now();
+    let up_next = query.next.unwrap_or(DEFAULT_UP_NEXT).min(MAX_UP_NEXT);
+
+    HttpResponse::Ok().json(ChannelGuideResponse {
+        status: "success".to_string(),
+        data: ChannelGuide {
+            id: channel.id,
+            name: channel.name,
+            now: channel_now(&lineup, channel.created_at.unwrap_or(now), now, up_next),
+        },
+    })
+}
+
+pub fn config(conf: &mut web::ServiceConfig) {
+    conf.service(post_channel_handler)
+        .service(get_channel_now_handler);
+}
@@ -1,17 +1,71 @@
-use crate::db_helpers::get_user_special_collections;
+use crate::db_helpers::{
+    are_friends, edit_collection_entries, get_collection, get_collection_role,
+    get_member_collections, get_shared_collection, get_user_collections,
+    get_user_special_collections, save_collection_entries, save_entry_details,
+};
+use crate::export::render_collections;
+use crate::handlers::members::require_role;
+use crate::tmdb::add_entry_details;
 use crate::{jwt_auth, AppState};
-use actix_web::{get, patch, web, HttpResponse, Responder};
-use common::model::collections::UserCollection;
-use common::model::collections::UserCollectionPatchResponse;
+use actix_web::{get, patch, post, web, HttpResponse, Responder};
+use chrono::Utc;
+use common::model::collections::{
+    BulkEntriesAction, BulkEntriesSchema, Media, MoveEntrySchema, NewSmartCollection, ShareType,
+    UpdateEntryMetaSchema, UpdateRankedSchema, UpdateSharingSchema, UserCollection,
+    UserCollectionData, UserCollectionPatchResponse, UserCollectionResponse,
+};
+use common::model::core::EntryMeta;
+use common::model::export::ExportFormat;
+use common::model::members::CollectionRole;
+use common::model::rules::{resolve_smart_collections, CollectionRules};
+use common::model::search::{group_search_hits, SearchData, SearchHitCollection, SearchResponse};
+use common::model::stats::{collection_stats, StatsResponse};
+use serde::Deserialize;
+use std::collections::HashSet;
 use uuid::Uuid;
 
+const SEARCH_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    q: Option<String>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    format: Option<ExportFormat>,
+}
+
+fn export_response(
+    format: ExportFormat,
+    collections: &[UserCollection],
+    file_stem: &str,
+) -> HttpResponse {
+    let (content_type, extension, body) = render_collections(format, collections);
+
+    HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{file_stem}.{extension}\""),
+        ))
+        .body(body)
+}
+
 #[get("/collections/special")]
 async fn get_user_collections_special_handler(
     jwt_guard: jwt_auth::JwtMiddleware,
     data: web::Data<AppState>,
 ) -> impl Responder {
     let owner_id = jwt_guard.user.id;
-    let collections = get_user_special_collections(owner_id, &data).await;
+    let collections = get_user_special_collections(owner_id, &data)
+        .await
+        .map(|cols| {
+            cols.into_iter()
+                .map(|col| col.personalized_for(owner_id))
+                .collect::<Vec<UserCollection>>()
+        });
 
     let json = match collections {
         Ok(cols) => {
@@ -34,23 +88,51 @@ async fn get_user_collections_special_handler(
     HttpResponse::Ok().json(json)
 }
 
+#[derive(Debug, Deserialize)]
+struct CollectionsQuery {
+    tag: Option<String>,
+}
+
 #[get("/collections")]
 async fn get_user_collections_handler(
     jwt_guard: jwt_auth::JwtMiddleware,
+    query: web::Query<CollectionsQuery>,
     data: web::Data<AppState>,
 ) -> impl Responder {
     let owner_id = jwt_guard.user.id;
     let collections = sqlx::query_as!(
         UserCollection,
         r#"SELECT
-        id, owner_id,name, created_at, active, collection, locked, tags, special,
+        id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
         CAST(sharing as text)
         FROM collections
         WHERE owner_id = $1"#,
         owner_id
     )
     .fetch_all(&data.db)
-    .await;
+    .await
+    .map(resolve_smart_collections);
+
+    // Then whatever other people have shared with us as members.
+    let collections = match collections {
+        Ok(mut cols) => get_member_collections(owner_id, &data)
+            .await
+            .map(|shared| {
+                cols.extend(shared);
+                cols
+            })
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+    .map(|cols| match &query.tag {
+        Some(tag) => cols.into_iter().filter(|c| c.has_tag(tag)).collect(),
+        None => cols,
+    })
+    .map(|cols: Vec<UserCollection>| {
+        cols.into_iter()
+            .map(|col| col.personalized_for(owner_id))
+            .collect::<Vec<_>>()
+    });
 
     let json = match collections {
         Ok(cols) => {
@@ -73,16 +155,291 @@ async fn get_user_collections_handler(
     HttpResponse::Ok().json(json)
 }
 
+// Full-text search over the entries of all the user's collections: titles, TMDB overviews, the
+// user's notes and tags, and the tags of the collections they're in. Takes web search syntax, so
+// `dune -part`, `"blade runner"` and `horror or slasher` all work.
+#[get("/collections/search")]
+async fn get_search_collections_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    query: web::Query<SearchQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let q = query.q.clone().unwrap_or_default();
+    if q.trim().is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "status": "fail",
+            "message": "Nothing to search for"
+        }));
+    }
+
+    // The indexed `search_vector` picks the collections, then each entry is matched on its own,
+    // on the searcher's own notes rather than the owner's.
+    let user_id = jwt_guard.user.id;
+    let matches = sqlx::query!(
+        r#"
+        WITH search AS (SELECT websearch_to_tsquery('english', $2) AS query)
+        SELECT
+            c.id, c.owner_id, c.name, e.entry,
+            ts_rank(doc.vector, search.query) AS "rank!"
+        FROM collections c
+        CROSS JOIN search
+        CROSS JOIN LATERAL jsonb_array_elements(c.collection->'entries') AS e(entry)
+        CROSS JOIN LATERAL (SELECT value AS media FROM jsonb_each(e.entry) LIMIT 1) m
+        CROSS JOIN LATERAL (
+            SELECT
+                setweight(to_tsvector('english', coalesce(m.media->>'name', '')), 'A') ||
+                setweight(to_tsvector('english', coalesce(
+                    CASE
+                        WHEN c.owner_id = $1 THEN m.media->'entry_meta'->>'notes'
+                        ELSE m.media->'entry_meta'->'members'->($1::text)->>'notes'
+                    END, '')), 'B') ||
+                setweight(jsonb_to_tsvector('english', coalesce(m.media->'entry_meta'->'tags', '[]'), '["string"]'), 'B') ||
+                setweight(jsonb_to_tsvector('english', coalesce(c.tags, '[]'), '["string"]'), 'C') ||
+                setweight(to_tsvector('english', coalesce(m.media->'details'->>'overview', '')), 'D')
+            AS vector
+        ) doc
+        WHERE (
+            c.owner_id = $1
+            OR EXISTS (
+                SELECT 1 FROM collection_members cm
+                WHERE cm.collection_id = c.id AND cm.user_id = $1
+            )
+        )
+        AND
+            c.search_vector @@ search.query
+        AND
+            doc.vector @@ search.query
+        ORDER BY 5 DESC
+        LIMIT $3
+        "#,
+        user_id,
+        q,
+        query.limit.unwrap_or(SEARCH_LIMIT).clamp(1, SEARCH_LIMIT)
+    )
+    .fetch_all(&data.db)
+    .await;
+
+    match matches {
+        Ok(rows) => {
+            let matches = rows
+                .into_iter()
+                .filter_map(|row| {
+                    let mut media: Media = serde_json::from_value(row.entry?).ok()?;
+                    media.personalize((row.owner_id != user_id).then_some(user_id));
+                    let collection = SearchHitCollection {
+                        id: row.id,
+                        name: row.name,
+                    };
+                    Some((collection, media, row.rank))
+                })
+                .collect();
+
+            HttpResponse::Ok().json(SearchResponse {
+                status: "success".to_string(),
+                data: SearchData {
+                    results: group_search_hits(matches),
+                },
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": e.to_string()
+        })),
+    }
+}
+
+// The user's taste profile, from everything in their own collections.
+#[get("/collections/stats")]
+async fn get_collections_stats_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match get_user_collections(jwt_guard.user.id, &data).await {
+        Ok(cols) => HttpResponse::Ok().json(StatsResponse {
+            status: "success".to_string(),
+            data: collection_stats(&cols),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": e.to_string()
+        })),
+    }
+}
+
 #[patch("/collection/{id}")]
 async fn patch_user_collection_handler(
     jwt_guard: jwt_auth::JwtMiddleware,
-    _path: web::Path<Uuid>,
+    path: web::Path<Uuid>,
     body: web::Json<UserCollection>,
     data: web::Data<AppState>,
+) -> impl Responder {
+    let actor_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+    let mut collection = body.collection.clone();
+
+    let member = match require_role(actor_id, collection_id, CollectionRole::Editor, &data).await {
+        Ok(role) => (role != CollectionRole::Owner).then_some(actor_id),
+        Err(res) => return res,
+    };
+
+    // Anything new to the collection gets stamped on its way in.
+    let now = Utc::now();
+    collection.entries.iter_mut().for_each(|media| {
+        media.entry_meta_mut().added_at.get_or_insert(now);
+    });
+
+    // Smart collections are computed, so there is nothing to store for them. The entries come back
+    // with only the sender's own ratings and notes, so everyone else's are kept as they were.
+    let collection =
+        save_collection_entries(actor_id, collection_id, member, &collection, &data).await;
+
+    let json = match collection {
+        Ok(Some(rec)) => {
+            serde_json::json!(UserCollectionPatchResponse {
+                status: "success".to_string(),
+                data: rec.personalized_for(actor_id)
+            })
+        }
+        Ok(None) => {
+            serde_json::json!({
+                "status": "error",
+                "message": "Update failed: collection not found"
+            })
+        }
+        Err(e) => {
+            serde_json::json!({
+                "status": "error",
+                "message": format!("Update failed: {}", e)
+            })
+        }
+    };
+
+    HttpResponse::Ok().json(json)
+}
+
+#[post("/collections/smart")]
+async fn post_user_smart_collection_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<NewSmartCollection>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+
+    if !body.rules.is_smart() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "status": "fail",
+            "message": "A smart collection needs at least one rule"
+        }));
+    }
+
+    let collection = sqlx::query_as!(
+        UserCollection,
+        r#"
+        INSERT INTO
+            collections (owner_id, name, active, sharing, collection, locked, tags, rules)
+        VALUES
+            ($1, $2, true, 'private', '{"entries": []}', true, '[]', $3)
+        RETURNING
+            id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+            CAST(sharing as text)
+        "#,
+        owner_id,
+        body.name.to_owned(),
+        serde_json::json!(body.rules)
+    )
+    .fetch_one(&data.db)
+    .await;
+
+    smart_collection_response(owner_id, collection, &data).await
+}
+
+#[patch("/collection/{id}/rules")]
+async fn patch_user_collection_rules_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<CollectionRules>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    if let Err(res) = require_role(owner_id, collection_id, CollectionRole::Owner, &data).await {
+        return res;
+    }
+    if !body.is_smart() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "status": "fail",
+            "message": "A smart collection needs at least one rule"
+        }));
+    }
+
+    let collection = sqlx::query_as!(
+        UserCollection,
+        r#"
+        UPDATE
+            collections
+        SET
+            rules = $1
+        WHERE
+            owner_id = $2 AND id = $3 AND rules IS NOT NULL
+        RETURNING
+            id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+            CAST(sharing as text)
+        "#,
+        serde_json::json!(body.into_inner()),
+        owner_id,
+        collection_id
+    )
+    .fetch_one(&data.db)
+    .await;
+
+    smart_collection_response(owner_id, collection, &data).await
+}
+
+// Evaluates a freshly written smart collection against the rest of the user's collections.
+async fn smart_collection_response(
+    owner_id: Uuid,
+    collection: Result<UserCollection, sqlx::Error>,
+    data: &AppState,
+) -> HttpResponse {
+    let mut collection = match collection {
+        Ok(rec) => rec,
+        Err(e) => {
+            return HttpResponse::Ok().json(serde_json::json!({
+                "status": "error",
+                "message": format!("Update failed: {}", e)
+            }));
+        }
+    };
+
+    match get_user_collections(owner_id, data).await {
+        Ok(cols) => {
+            collection.collection.entries = collection.rules.evaluate(&cols);
+            HttpResponse::Ok().json(UserCollectionPatchResponse {
+                status: "success".to_string(),
+                data: collection.personalized_for(owner_id),
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": e.to_string()
+        })),
+    }
+}
+
+#[patch("/collection/{id}/sharing")]
+async fn patch_user_collection_sharing_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateSharingSchema>,
+    data: web::Data<AppState>,
 ) -> impl Responder {
     let owner_id = jwt_guard.user.id;
-    let user_collection = body.clone();
-    let collection = user_collection.collection;
+    let collection_id = path.into_inner();
+
+    if let Err(res) = require_role(owner_id, collection_id, CollectionRole::Owner, &data).await {
+        return res;
+    }
 
     let collection = sqlx::query_as!(
         UserCollection,
@@ -90,16 +447,16 @@ async fn patch_user_collection_handler(
         UPDATE
             collections
         SET
-            collection = $1
+            sharing = CAST($1::text AS SHARE_TYPE)
         WHERE
             owner_id = $2 AND id = $3
         RETURNING
-            id, owner_id,name, created_at, active, collection, locked, tags, special,
+            id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
             CAST(sharing as text)
         "#,
-        serde_json::json!(collection),
+        body.sharing.to_string(),
         owner_id,
-        user_collection.id
+        collection_id
     )
     .fetch_one(&data.db)
     .await;
@@ -108,7 +465,7 @@ async fn patch_user_collection_handler(
         Ok(rec) => {
             serde_json::json!(UserCollectionPatchResponse {
                 status: "success".to_string(),
-                data: rec
+                data: rec.personalized_for(owner_id)
             })
         }
         Err(e) => {
@@ -122,7 +479,624 @@ async fn patch_user_collection_handler(
     HttpResponse::Ok().json(json)
 }
 
+// Smart collections are ordered by their sources, so only plain ones can be ranked.
+#[patch("/collection/{id}/ranked")]
+async fn patch_user_collection_ranked_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateRankedSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    if let Err(res) = require_role(owner_id, collection_id, CollectionRole::Owner, &data).await {
+        return res;
+    }
+
+    let collection = sqlx::query_as!(
+        UserCollection,
+        r#"
+        UPDATE
+            collections
+        SET
+            ranked = $1
+        WHERE
+            owner_id = $2 AND id = $3 AND rules IS NULL
+        RETURNING
+            id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+            CAST(sharing as text)
+        "#,
+        body.ranked,
+        owner_id,
+        collection_id
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    match collection {
+        Ok(Some(rec)) => HttpResponse::Ok().json(UserCollectionPatchResponse {
+            status: "success".to_string(),
+            data: rec.personalized_for(owner_id),
+        }),
+        Ok(None) => HttpResponse::BadRequest().json(serde_json::json!({
+            "status": "fail",
+            "message": "Smart collections can't be ranked"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": format!("Update failed: {}", e)
+        })),
+    }
+}
+
+async fn move_collection_entry(
+    actor_id: Uuid,
+    collection_id: Uuid,
+    entry: &str,
+    position: usize,
+    data: &AppState,
+) -> Result<Option<UserCollection>, sqlx::Error> {
+    let mut tx = data.db.begin().await?;
+    let mut moved = false;
+
+    let updated = edit_collection_entries(actor_id, collection_id, &mut tx, |col| {
+        moved = col.move_entry(entry, position);
+    })
+    .await?;
+
+    // Not there, so nothing to save.
+    if !moved {
+        return Ok(None);
+    }
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+// Moves one entry to a new position, which for ranked collections is its new rank.
+#[post("/collection/{id}/entries/move")]
+async fn post_move_collection_entry_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<MoveEntrySchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let actor_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    if let Err(res) = require_role(actor_id, collection_id, CollectionRole::Editor, &data).await {
+        return res;
+    }
+
+    let collection =
+        move_collection_entry(actor_id, collection_id, &body.entry, body.position, &data).await;
+
+    match collection {
+        Ok(Some(rec)) => HttpResponse::Ok().json(UserCollectionPatchResponse {
+            status: "success".to_string(),
+            data: rec.personalized_for(actor_id),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "status": "fail",
+            "message": "Entry not found in collection"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": format!("Update failed: {}", e)
+        })),
+    }
+}
+
+// Watch status, rating, notes etc. for a single title in a (non-smart) collection.
+#[patch("/collection/{id}/entry")]
+async fn patch_user_collection_entry_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateEntryMetaSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let actor_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    let member = match require_role(actor_id, collection_id, CollectionRole::Editor, &data).await {
+        Ok(role) => (role != CollectionRole::Owner).then_some(actor_id),
+        Err(res) => return res,
+    };
+    if body
+        .meta
+        .personal
+        .rating
+        .is_some_and(|r| !EntryMeta::RATING_RANGE.contains(&r))
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "status": "fail",
+            "message": "Ratings go from 1 to 10"
+        }));
+    }
+
+    // Read and written under the same lock, so edits to other entries in between aren't lost.
+    let mut found = false;
+    let collection = async {
+        let mut tx = data.db.begin().await?;
+        let updated = edit_collection_entries(actor_id, collection_id, &mut tx, |entries| {
+            let Some(entry) = entries
+                .entries
+                .iter_mut()
+                .find(|media| media.identity() == body.entry)
+            else {
+                return;
+            };
+            found = true;
+            // Tags are shared; the rest is the sender's own take on the title.
+            let meta = entry.entry_meta_mut();
+            meta.tags = body.meta.tags.clone();
+            *meta.personal_mut(member) = body.meta.personal.clone();
+        })
+        .await?;
+        if found {
+            tx.commit().await?;
+        }
+        Ok::<_, sqlx::Error>(updated)
+    }
+    .await;
+
+    if matches!(collection, Ok(Some(_))) && !found {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "status": "fail",
+            "message": "Entry not found in collection"
+        }));
+    }
+
+    let json = match collection {
+        Ok(Some(rec)) => {
+            serde_json::json!(UserCollectionPatchResponse {
+                status: "success".to_string(),
+                data: rec.personalized_for(actor_id)
+            })
+        }
+        Ok(None) => {
+            serde_json::json!({
+                "status": "error",
+                "message": "Update failed: collection not found"
+            })
+        }
+        Err(e) => {
+            serde_json::json!({
+                "status": "error",
+                "message": format!("Update failed: {}", e)
+            })
+        }
+    };
+
+    HttpResponse::Ok().json(json)
+}
+
+// Applies one bulk action to the selected entries of a collection, and for moves and copies adds
+// them to the target too. Each collection touched gets a single revision, all in one transaction.
+async fn bulk_edit_entries(
+    actor_id: Uuid,
+    collection_id: Uuid,
+    member: Option<Uuid>,
+    identities: &HashSet<String>,
+    action: &BulkEntriesAction,
+    data: &AppState,
+) -> Result<Option<Vec<UserCollection>>, sqlx::Error> {
+    let selected = |media: &Media| identities.contains(&media.identity());
+    let mut tx = data.db.begin().await?;
+    let mut outgoing: Vec<Media> = vec![];
+
+    let source = edit_collection_entries(actor_id, collection_id, &mut tx, |col| match action {
+        BulkEntriesAction::Move { .. } => outgoing = col.take_entries(identities),
+        BulkEntriesAction::Copy { .. } => {
+            outgoing = col
+                .entries
+                .iter()
+                .filter(|m| selected(m))
+                .cloned()
+                .collect()
+        }
+        BulkEntriesAction::Delete => {
+            col.take_entries(identities);
+        }
+        BulkEntriesAction::Tag { add, remove } => {
+            for media in col.entries.iter_mut().filter(|m| selected(m)) {
+                let tags = &mut media.entry_meta_mut().tags;
+                add.iter().for_each(|t| {
+                    tags.insert(t);
+                });
+                remove.iter().for_each(|t| {
+                    tags.remove(t);
+                });
+            }
+        }
+        BulkEntriesAction::Rate { rating } => col
+            .entries
+            .iter_mut()
+            .filter(|m| selected(m))
+            .for_each(|media| media.entry_meta_mut().personal_mut(member).rating = *rating),
+    })
+    .await?;
+
+    let mut updated = match source {
+        Some(col) => vec![col],
+        None => return Ok(None),
+    };
+
+    if let BulkEntriesAction::Move { to } | BulkEntriesAction::Copy { to } = action {
+        // They're new to the target, whenever and by whoever they went into the source. Only the
+        // actor's own take on them goes along, as theirs in the target.
+        let target_member = (get_collection_role(actor_id, *to, data).await?
+            != Some(CollectionRole::Owner))
+        .then_some(actor_id);
+        let now = Utc::now();
+        outgoing.iter_mut().for_each(|media| {
+            let meta = media.entry_meta_mut();
+            meta.added_at = Some(now);
+            meta.added_by = None;
+            let mine = meta.personal_of(member);
+            meta.personal = Default::default();
+            meta.members.clear();
+            *meta.personal_mut(target_member) = mine;
+        });
+
+        match edit_collection_entries(actor_id, *to, &mut tx, |col| {
+            col.add_entries(outgoing);
+        })
+        .await?
+        {
+            Some(col) => updated.push(col),
+            // Not there, or not ours to edit. Dropping the transaction rolls the source back too.
+            None => return Ok(None),
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(Some(updated))
+}
+
+#[post("/collection/{id}/entries/bulk")]
+async fn post_bulk_collection_entries_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<BulkEntriesSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let actor_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    let member = match require_role(actor_id, collection_id, CollectionRole::Editor, &data).await {
+        Ok(role) => (role != CollectionRole::Owner).then_some(actor_id),
+        Err(res) => return res,
+    };
+
+    let invalid = match &body.action {
+        BulkEntriesAction::Rate { rating: Some(r) } if !EntryMeta::RATING_RANGE.contains(r) => {
+            Some("Ratings go from 1 to 10")
+        }
+        BulkEntriesAction::Move { to } | BulkEntriesAction::Copy { to } if *to == collection_id => {
+            Some("Pick a different collection to move or copy to")
+        }
+        _ if body.entries.is_empty() => Some("No entries selected"),
+        _ => None,
+    };
+    if let Some(message) = invalid {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "status": "fail",
+            "message": message
+        }));
+    }
+
+    let identities: HashSet<String> = body.entries.iter().cloned().collect();
+
+    match bulk_edit_entries(
+        actor_id,
+        collection_id,
+        member,
+        &identities,
+        &body.action,
+        &data,
+    )
+    .await
+    {
+        Ok(Some(collections)) => HttpResponse::Ok().json(UserCollectionResponse {
+            status: "success".to_string(),
+            data: UserCollectionData {
+                collections: collections
+                    .into_iter()
+                    .map(|col| col.personalized_for(actor_id))
+                    .collect(),
+            },
+        }),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "status": "fail",
+            "message": "Collection not found"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": format!("Update failed: {}", e)
+        })),
+    }
+}
+
+// Share links work without logging in, so the guard here is optional.
+#[get("/shared/collection/{id}")]
+async fn get_shared_collection_handler(
+    jwt_guard: Option<jwt_auth::JwtMiddleware>,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let viewer_id = jwt_guard.map(|g| g.user.id);
+
+    match get_shared_collection(path.into_inner(), viewer_id, &data).await {
+        Ok(Some(col)) => HttpResponse::Ok().json(UserCollectionPatchResponse {
+            status: "success".to_string(),
+            data: col,
+        }),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "status": "fail",
+            "message": "Collection not found"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": e.to_string()
+        })),
+    }
+}
+
+// Everything of another user's that the caller is allowed to see.
+#[get("/shared/user/{id}/collections")]
+async fn get_shared_user_collections_handler(
+    jwt_guard: Option<jwt_auth::JwtMiddleware>,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let viewer_id = jwt_guard.map(|g| g.user.id);
+    let owner_id = path.into_inner();
+
+    let visible = async {
+        let friends = match viewer_id {
+            Some(viewer_id) => are_friends(viewer_id, owner_id, &data).await?,
+            None => false,
+        };
+        let cols = get_user_collections(owner_id, &data).await?;
+
+        Ok::<_, Box<dyn std::error::Error>>(
+            cols.into_iter()
+                .filter(|col| match col.share_type() {
+                    _ if viewer_id == Some(owner_id) => true,
+                    ShareType::Public => true,
+                    ShareType::Friends => friends,
+                    ShareType::Private => false,
+                })
+                .collect::<Vec<UserCollection>>(),
+        )
+    };
+
+    let json = match visible.await {
+        Ok(cols) => {
+            serde_json::json!(UserCollectionResponse {
+                status: "success".to_string(),
+                data: UserCollectionData {
+                    collections: cols
+                        .into_iter()
+                        .map(|col| col.personalized_for(owner_id))
+                        .collect()
+                }
+            })
+        }
+        Err(e) => {
+            serde_json::json!({
+                "status":  "error",
+                "message": e.to_string()
+            })
+        }
+    };
+
+    HttpResponse::Ok().json(json)
+}
+
+#[post("/collection/{id}/copy")]
+async fn post_copy_collection_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+
+    let source = match get_shared_collection(path.into_inner(), Some(owner_id), &data).await {
+        Ok(Some(col)) => col,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "status": "fail",
+                "message": "Collection not found"
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "status": "error",
+                "message": e.to_string()
+            }));
+        }
+    };
+
+    // The copy is the copier's own: only their take on the entries goes along, and the entries
+    // are new to it.
+    let now = Utc::now();
+    let mut entries = source.collection.clone();
+    entries.entries.iter_mut().for_each(|media| {
+        let meta = media.entry_meta_mut();
+        meta.added_at = Some(now);
+        meta.added_by = None;
+    });
+
+    // Copies are always plain, private collections, even when the source is smart.
+    let collection = sqlx::query_as!(
+        UserCollection,
+        r#"
+        INSERT INTO
+            collections (owner_id, name, active, sharing, collection, locked, tags, ranked)
+        VALUES
+            ($1, $2, true, 'private', $3, false, $4, $5)
+        RETURNING
+            id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+            CAST(sharing as text)
+        "#,
+        owner_id,
+        format!("{} (copy)", source.name),
+        serde_json::json!(entries),
+        serde_json::json!(source.tags),
+        source.ranked && !source.is_smart()
+    )
+    .fetch_one(&data.db)
+    .await;
+
+    let json = match collection {
+        Ok(rec) => {
+            serde_json::json!(UserCollectionPatchResponse {
+                status: "success".to_string(),
+                data: rec
+            })
+        }
+        Err(e) => {
+            serde_json::json!({
+                "status": "error",
+                "message": format!("Copy failed: {}", e)
+            })
+        }
+    };
+
+    HttpResponse::Ok().json(json)
+}
+
+// Looks up runtimes, seasons and episodes on TMDB for the entries that don't have them yet, or
+// whose seasons are stale, with the user's own API key.
+#[post("/collection/{id}/details")]
+async fn post_collection_details_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let actor_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    let Some(key) = jwt_guard.user.tmdb_api_key.filter(|k| !k.is_empty()) else {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "status": "fail",
+            "message": "Add a TMDB API key to your profile first"
+        }));
+    };
+    if let Err(res) = require_role(actor_id, collection_id, CollectionRole::Editor, &data).await {
+        return res;
+    }
+
+    let mut looked_up = match get_collection(collection_id, &data).await {
+        Ok(Some(col)) => col.collection,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "status": "fail",
+                "message": "Collection not found"
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "status": "error",
+                "message": e.to_string()
+            }));
+        }
+    };
+
+    add_entry_details(&key, &mut looked_up).await;
+
+    match save_entry_details(actor_id, collection_id, &looked_up, &data).await {
+        Ok(Some(rec)) => HttpResponse::Ok().json(UserCollectionPatchResponse {
+            status: "success".to_string(),
+            data: rec.personalized_for(actor_id),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "status": "fail",
+            "message": "Collection not found"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": format!("Update failed: {}", e)
+        })),
+    }
+}
+
+#[get("/collections/export")]
+async fn get_user_collections_export_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    query: web::Query<ExportQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+    let format = query.format.unwrap_or_default();
+
+    match get_user_collections(owner_id, &data).await {
+        Ok(cols) => {
+            let cols: Vec<UserCollection> = cols
+                .into_iter()
+                .map(|col| col.personalized_for(owner_id))
+                .collect();
+            export_response(format, &cols, "baing-collections")
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": e.to_string()
+        })),
+    }
+}
+
+#[get("/collection/{id}/export")]
+async fn get_user_collection_export_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    query: web::Query<ExportQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+    let format = query.format.unwrap_or_default();
+
+    if let Err(res) = require_role(user_id, collection_id, CollectionRole::Viewer, &data).await {
+        return res;
+    }
+
+    match get_collection(collection_id, &data).await {
+        Ok(Some(col)) => export_response(
+            format,
+            &[col.personalized_for(user_id)],
+            &format!("baing-{collection_id}"),
+        ),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "status": "fail",
+            "message": "Collection not found"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "status": "error",
+            "message": e.to_string()
+        })),
+    }
+}
+
 pub fn config(conf: &mut web::ServiceConfig) {
-    conf.service(get_user_collections_handler)
-        .service(patch_user_collection_handler);
+    conf.service(get_user_collections_export_handler)
+        .service(get_user_collection_export_handler)
+        .service(get_user_collections_handler)
+        .service(get_search_collections_handler)
+        .service(get_collections_stats_handler)
+        .service(post_user_smart_collection_handler)
+        .service(patch_user_collection_handler)
+        .service(patch_user_collection_rules_handler)
+        .service(patch_user_collection_sharing_handler)
+        .service(patch_user_collection_entry_handler)
+        .service(post_bulk_collection_entries_handler)
+        .service(patch_user_collection_ranked_handler)
+        .service(post_move_collection_entry_handler)
+        .service(get_shared_collection_handler)
+        .service(get_shared_user_collections_handler)
+        .service(post_copy_collection_handler)
+        .service(post_collection_details_handler);
 }
@@ -0,0 +1,426 @@
+use crate::db_helpers::get_friendship_between;
+use crate::{jwt_auth, AppState};
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use common::model::friends::{
+    Friend, FriendRequestSchema, FriendsData, FriendsResponse, Friendship, FriendshipResponse,
+    FriendshipStatus,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn get_friends_with_status(
+    user_id: Uuid,
+    status: FriendshipStatus,
+    data: &AppState,
+) -> Result<Vec<Friend>, sqlx::Error> {
+    let status = status.to_string();
+
+    sqlx::query_as!(
+        Friend,
+        r#"SELECT
+            f.id as friendship_id, u.id as user_id, u.name,
+            CAST(f.status as text), (f.addressee_id = $1) as incoming, f.updated_at
+        FROM friendships f
+        JOIN users u ON u.id = CASE
+            WHEN f.requester_id = $1 THEN f.addressee_id
+            ELSE f.requester_id
+        END
+        WHERE
+            (f.requester_id = $1 OR f.addressee_id = $1)
+        AND
+            f.status = CAST($2::text AS FRIENDSHIP_STATUS)
+        ORDER BY u.name
+        "#,
+        user_id,
+        status
+    )
+    .fetch_all(&data.db)
+    .await
+}
+
+fn friends_response(friends: Result<Vec<Friend>, sqlx::Error>) -> HttpResponse {
+    match friends {
+        Ok(friends) => HttpResponse::Ok().json(FriendsResponse {
+            status: "success".to_string(),
+            data: FriendsData { friends },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": e.to_string()
+        })),
+    }
+}
+
+fn friendship_response(friendship: Result<Friendship, sqlx::Error>) -> HttpResponse {
+    match friendship {
+        Ok(rec) => HttpResponse::Ok().json(FriendshipResponse {
+            status: "success".to_string(),
+            data: rec,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("Update failed: {}", e)
+        })),
+    }
+}
+
+// (Re)points a friendship from `requester_id` to `addressee_id` with the given status.
+async fn set_friendship(
+    friendship_id: Uuid,
+    requester_id: Uuid,
+    addressee_id: Uuid,
+    status: FriendshipStatus,
+    data: &AppState,
+) -> Result<Friendship, sqlx::Error> {
+    let status = status.to_string();
+
+    sqlx::query_as!(
+        Friendship,
+        r#"
+        UPDATE
+            friendships
+        SET
+            requester_id = $1,
+            addressee_id = $2,
+            status = CAST($3::text AS FRIENDSHIP_STATUS),
+            updated_at = NOW()
+        WHERE
+            id = $4
+        RETURNING
+            id, requester_id, addressee_id, CAST(status as text), created_at, updated_at
+        "#,
+        requester_id,
+        addressee_id,
+        status,
+        friendship_id
+    )
+    .fetch_one(&data.db)
+    .await
+}
+
+#[get("/friends")]
+async fn get_friends_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    friends_response(
+        get_friends_with_status(jwt_guard.user.id, FriendshipStatus::Accepted, &data).await,
+    )
+}
+
+#[get("/friends/requests")]
+async fn get_friend_requests_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    friends_response(
+        get_friends_with_status(jwt_guard.user.id, FriendshipStatus::Pending, &data).await,
+    )
+}
+
+// The same whether or not there's anyone with the email, so requests can't be used to find out
+// who has an account.
+fn request_sent_response() -> HttpResponse {
+    HttpResponse::Ok().json(json!({"status": "success", "message": "Friend request sent"}))
+}
+
+// Only the people the user blocked; being blocked by someone isn't something to show.
+#[get("/friends/blocked")]
+async fn get_blocked_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    friends_response(
+        get_friends_with_status(jwt_guard.user.id, FriendshipStatus::Blocked, &data)
+            .await
+            .map(|blocked| {
+                blocked
+                    .into_iter()
+                    .filter(|f| f.incoming == Some(false))
+                    .collect()
+            }),
+    )
+}
+
+#[post("/friends/requests")]
+async fn post_friend_request_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<FriendRequestSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+
+    let addressee = sqlx::query!(
+        "SELECT id FROM users WHERE email = $1",
+        body.email.to_lowercase()
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    let addressee_id = match addressee {
+        Ok(Some(rec)) if rec.id != user_id => rec.id,
+        Ok(_) => return request_sent_response(),
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let existing = match get_friendship_between(user_id, addressee_id, &data).await {
+        Ok(existing) => existing,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let friendship = match existing {
+        None => {
+            sqlx::query_as!(
+                Friendship,
+                r#"
+                INSERT INTO
+                    friendships (requester_id, addressee_id)
+                VALUES
+                    ($1, $2)
+                RETURNING
+                    id, requester_id, addressee_id, CAST(status as text), created_at, updated_at
+                "#,
+                user_id,
+                addressee_id
+            )
+            .fetch_one(&data.db)
+            .await
+        }
+        Some(f) => match f.friendship_status() {
+            // Asking someone we blocked unblocks them.
+            FriendshipStatus::Blocked if f.requester_id == user_id => {
+                set_friendship(
+                    f.id,
+                    user_id,
+                    addressee_id,
+                    FriendshipStatus::Pending,
+                    &data,
+                )
+                .await
+            }
+            // Don't let on that the other side has blocked us.
+            FriendshipStatus::Blocked => return request_sent_response(),
+            FriendshipStatus::Accepted => {
+                return HttpResponse::Conflict()
+                    .json(json!({"status": "fail", "message": "You are already friends"}));
+            }
+            // They asked us first, so asking back is as good as accepting.
+            FriendshipStatus::Pending if f.addressee_id == user_id => {
+                set_friendship(
+                    f.id,
+                    f.requester_id,
+                    f.addressee_id,
+                    FriendshipStatus::Accepted,
+                    &data,
+                )
+                .await
+            }
+            // Sending again has to look the same as sending to an unknown email.
+            FriendshipStatus::Pending => return request_sent_response(),
+            FriendshipStatus::Declined => {
+                set_friendship(
+                    f.id,
+                    user_id,
+                    addressee_id,
+                    FriendshipStatus::Pending,
+                    &data,
+                )
+                .await
+            }
+        },
+    };
+
+    match friendship {
+        Ok(_) => request_sent_response(),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+async fn get_own_friendship(
+    user_id: Uuid,
+    friendship_id: Uuid,
+    data: &AppState,
+) -> Result<Option<Friendship>, sqlx::Error> {
+    sqlx::query_as!(
+        Friendship,
+        r#"SELECT
+            id, requester_id, addressee_id, CAST(status as text), created_at, updated_at
+        FROM friendships
+        WHERE
+            id = $1
+        AND
+            (requester_id = $2 OR addressee_id = $2)
+        "#,
+        friendship_id,
+        user_id
+    )
+    .fetch_optional(&data.db)
+    .await
+}
+
+// Only the addressee of a pending request can answer it.
+async fn answer_friend_request(
+    user_id: Uuid,
+    friendship_id: Uuid,
+    status: FriendshipStatus,
+    data: &AppState,
+) -> HttpResponse {
+    match get_own_friendship(user_id, friendship_id, data).await {
+        Ok(Some(f))
+            if f.addressee_id == user_id && f.friendship_status() == FriendshipStatus::Pending =>
+        {
+            friendship_response(
+                set_friendship(f.id, f.requester_id, f.addressee_id, status, data).await,
+            )
+        }
+        Ok(_) => HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Friend request not found"})),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+#[post("/friends/requests/{id}/accept")]
+async fn post_accept_friend_request_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    answer_friend_request(
+        jwt_guard.user.id,
+        path.into_inner(),
+        FriendshipStatus::Accepted,
+        &data,
+    )
+    .await
+}
+
+#[post("/friends/requests/{id}/decline")]
+async fn post_decline_friend_request_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    answer_friend_request(
+        jwt_guard.user.id,
+        path.into_inner(),
+        FriendshipStatus::Declined,
+        &data,
+    )
+    .await
+}
+
+// Either side can block, whatever state the friendship is in. The blocker becomes the requester
+// so we always know who did it.
+#[post("/friends/requests/{id}/block")]
+async fn post_block_friend_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+
+    match get_own_friendship(user_id, path.into_inner(), &data).await {
+        Ok(Some(f)) => friendship_response(
+            set_friendship(
+                f.id,
+                user_id,
+                f.other(user_id),
+                FriendshipStatus::Blocked,
+                &data,
+            )
+            .await,
+        ),
+        Ok(None) => HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Friend request not found"})),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+async fn delete_friendship(
+    friendship_id: Uuid,
+    data: &AppState,
+) -> Result<Friendship, sqlx::Error> {
+    sqlx::query_as!(
+        Friendship,
+        r#"
+        DELETE FROM friendships
+        WHERE
+            id = $1
+        RETURNING
+            id, requester_id, addressee_id, CAST(status as text), created_at, updated_at
+        "#,
+        friendship_id
+    )
+    .fetch_one(&data.db)
+    .await
+}
+
+// Either side can end a friendship, and whoever sent a request can take it back. Blocks stay
+// until they're lifted.
+#[delete("/friends/{id}")]
+async fn delete_friend_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+
+    match get_own_friendship(user_id, path.into_inner(), &data).await {
+        Ok(Some(f))
+            if f.friendship_status() == FriendshipStatus::Accepted
+                || (f.friendship_status() == FriendshipStatus::Pending
+                    && f.requester_id == user_id) =>
+        {
+            friendship_response(delete_friendship(f.id, &data).await)
+        }
+        Ok(_) => {
+            HttpResponse::NotFound().json(json!({"status": "fail", "message": "Friend not found"}))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Only the blocker can lift a block. It leaves them strangers again, free to send a new request.
+#[post("/friends/requests/{id}/unblock")]
+async fn post_unblock_friend_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+
+    match get_own_friendship(user_id, path.into_inner(), &data).await {
+        Ok(Some(f))
+            if f.friendship_status() == FriendshipStatus::Blocked && f.requester_id == user_id =>
+        {
+            friendship_response(delete_friendship(f.id, &data).await)
+        }
+        Ok(_) => {
+            HttpResponse::NotFound().json(json!({"status": "fail", "message": "Block not found"}))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+pub fn config(conf: &mut web::ServiceConfig) {
+    conf.service(get_friends_handler)
+        .service(get_friend_requests_handler)
+        .service(get_blocked_handler)
+        .service(post_friend_request_handler)
+        .service(post_accept_friend_request_handler)
+        .service(post_decline_friend_request_handler)
+        .service(post_block_friend_handler)
+        .service(post_unblock_friend_handler)
+        .service(delete_friend_handler);
+}
@@ -0,0 +1,274 @@
+use crate::db_helpers::get_collection_role;
+use crate::{jwt_auth, AppState};
+use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use common::model::members::{
+    CollectionMember, CollectionRole, InviteMemberSchema, MembersData, MembersResponse,
+    UpdateMemberSchema,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+// Gate for collection endpoints: the caller's role, or the response to send back instead. Anyone
+// with no part in the collection is told it doesn't exist.
+pub async fn require_role(
+    user_id: Uuid,
+    collection_id: Uuid,
+    needed: CollectionRole,
+    data: &AppState,
+) -> Result<CollectionRole, HttpResponse> {
+    match get_collection_role(user_id, collection_id, data).await {
+        Ok(Some(role)) if role >= needed => Ok(role),
+        Ok(Some(_)) => Err(HttpResponse::Forbidden().json(json!({
+            "status": "fail",
+            "message": format!("Only the collection's {needed}s can do that")
+        }))),
+        Ok(None) => Err(HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Collection not found"}))),
+        Err(e) => Err(HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}))),
+    }
+}
+
+async fn get_collection_members(
+    collection_id: Uuid,
+    data: &AppState,
+) -> Result<Vec<CollectionMember>, sqlx::Error> {
+    sqlx::query_as!(
+        CollectionMember,
+        r#"SELECT
+            c.id as "collection_id!", u.id as "user_id!", u.name as "name!",
+            'owner' as role, c.created_at
+        FROM collections c
+        JOIN users u ON u.id = c.owner_id
+        WHERE c.id = $1
+        UNION ALL
+        SELECT
+            m.collection_id, u.id, u.name, CAST(m.role as text), m.created_at
+        FROM collection_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.collection_id = $1
+        "#,
+        collection_id
+    )
+    .fetch_all(&data.db)
+    .await
+}
+
+async fn members_response(collection_id: Uuid, data: &AppState) -> HttpResponse {
+    match get_collection_members(collection_id, data).await {
+        Ok(members) => HttpResponse::Ok().json(MembersResponse {
+            status: "success".to_string(),
+            data: MembersData { members },
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+#[get("/collection/{id}/members")]
+async fn get_collection_members_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let collection_id = path.into_inner();
+
+    if let Err(res) = require_role(
+        jwt_guard.user.id,
+        collection_id,
+        CollectionRole::Viewer,
+        &data,
+    )
+    .await
+    {
+        return res;
+    }
+
+    members_response(collection_id, &data).await
+}
+
+// The same whether or not there's anyone with the email, so invites can't be used to find out who
+// has an account.
+fn invite_sent_response() -> HttpResponse {
+    HttpResponse::Ok().json(json!({"status": "success", "message": "Invite sent"}))
+}
+
+// Inviting someone already on the collection just changes their role.
+#[post("/collection/{id}/members")]
+async fn post_collection_member_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<InviteMemberSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    if let Err(res) = require_role(user_id, collection_id, CollectionRole::Owner, &data).await {
+        return res;
+    }
+    if body.role == CollectionRole::Owner {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Collections have a single owner"}));
+    }
+
+    let invitee = sqlx::query!(
+        "SELECT id FROM users WHERE email = $1",
+        body.email.to_lowercase()
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    let invitee_id = match invitee {
+        Ok(Some(rec)) if rec.id != user_id => rec.id,
+        Ok(_) => return invite_sent_response(),
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let added = sqlx::query!(
+        r#"
+        INSERT INTO
+            collection_members (collection_id, user_id, role, invited_by)
+        VALUES
+            ($1, $2, CAST($3::text AS COLLECTION_ROLE), $4)
+        ON CONFLICT (collection_id, user_id)
+            DO UPDATE SET role = EXCLUDED.role
+        "#,
+        collection_id,
+        invitee_id,
+        body.role.to_string(),
+        user_id
+    )
+    .execute(&data.db)
+    .await;
+
+    match added {
+        Ok(_) => invite_sent_response(),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+#[patch("/collection/{id}/members/{user_id}")]
+async fn patch_collection_member_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdateMemberSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let (collection_id, member_id) = path.into_inner();
+
+    if let Err(res) = require_role(
+        jwt_guard.user.id,
+        collection_id,
+        CollectionRole::Owner,
+        &data,
+    )
+    .await
+    {
+        return res;
+    }
+    if body.role == CollectionRole::Owner {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Collections have a single owner"}));
+    }
+
+    let updated = sqlx::query!(
+        r#"
+        UPDATE
+            collection_members
+        SET
+            role = CAST($1::text AS COLLECTION_ROLE)
+        WHERE
+            collection_id = $2 AND user_id = $3
+        "#,
+        body.role.to_string(),
+        collection_id,
+        member_id
+    )
+    .execute(&data.db)
+    .await;
+
+    match updated {
+        Ok(res) if res.rows_affected() == 0 => HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Not a member of this collection"})),
+        Ok(_) => members_response(collection_id, &data).await,
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// The owner can remove anyone; members can remove themselves to leave.
+#[delete("/collection/{id}/members/{user_id}")]
+async fn delete_collection_member_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<(Uuid, Uuid)>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let (collection_id, member_id) = path.into_inner();
+    let needed = match member_id == user_id {
+        true => CollectionRole::Viewer,
+        false => CollectionRole::Owner,
+    };
+
+    if let Err(res) = require_role(user_id, collection_id, needed, &data).await {
+        return res;
+    }
+
+    let removed = sqlx::query!(
+        "DELETE FROM collection_members WHERE collection_id = $1 AND user_id = $2",
+        collection_id,
+        member_id
+    )
+    .execute(&data.db)
+    .await;
+
+    match removed {
+        Ok(res) if res.rows_affected() == 0 => HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Not a member of this collection"})),
+        Ok(_) => members_response(collection_id, &data).await,
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// The caller's roles on other people's collections.
+#[get("/collections/memberships")]
+async fn get_memberships_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let memberships = sqlx::query_as!(
+        CollectionMember,
+        r#"SELECT
+            m.collection_id, u.id as user_id, u.name, CAST(m.role as text), m.created_at
+        FROM collection_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.user_id = $1
+        "#,
+        jwt_guard.user.id
+    )
+    .fetch_all(&data.db)
+    .await;
+
+    match memberships {
+        Ok(members) => HttpResponse::Ok().json(MembersResponse {
+            status: "success".to_string(),
+            data: MembersData { members },
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+pub fn config(conf: &mut web::ServiceConfig) {
+    conf.service(get_memberships_handler)
+        .service(get_collection_members_handler)
+        .service(post_collection_member_handler)
+        .service(patch_collection_member_handler)
+        .service(delete_collection_member_handler);
+}
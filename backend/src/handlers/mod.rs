@@ -1,4 +1,12 @@
 pub mod ai;
 pub mod auth;
+pub mod channels;
 pub mod collections;
+pub mod friends;
+pub mod members;
+pub mod progress;
+pub mod revisions;
+pub mod schedules;
+pub mod starter_packs;
+pub mod tags;
 pub mod user;
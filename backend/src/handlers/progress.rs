@@ -0,0 +1,127 @@
+use crate::db_helpers::get_series_progress;
+use crate::{jwt_auth, AppState};
+use actix_web::{get, post, web, HttpResponse, Responder};
+use common::model::collections::Media;
+use common::model::core::TvShow;
+use common::model::progress::{
+    MarkWatchedSchema, ProgressData, ProgressListResponse, ProgressResponse, SeriesProgress,
+    WatchedEpisodes,
+};
+use serde_json::json;
+
+#[get("/progress")]
+async fn get_progress_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match get_series_progress(jwt_guard.user.id, &data).await {
+        Ok(progress) => HttpResponse::Ok().json(ProgressListResponse {
+            status: "success".to_string(),
+            data: ProgressData {
+                progress: progress.into_iter().map(Into::into).collect(),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Marks an episode, a season or a range watched (or not) for the user, whichever collection the
+// show is in.
+#[post("/progress/watched")]
+async fn post_watched_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<MarkWatchedSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let mut body = body.into_inner();
+    let show_key = Media::TvShow(body.show.clone()).identity();
+    // Progress is the user's own, not any one collection's entry.
+    body.show.entry_meta = None;
+
+    let mut tx = match data.db.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let existing = sqlx::query_as!(
+        SeriesProgress,
+        r#"SELECT
+        user_id, show_key, show, watched, updated_at
+        FROM series_progress
+        WHERE user_id = $1 AND show_key = $2
+        FOR UPDATE
+        "#,
+        user_id,
+        show_key
+    )
+    .fetch_optional(&mut *tx)
+    .await;
+    let existing = match existing {
+        Ok(existing) => existing,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    // Hang on to the episode list we have if this copy of the show hasn't got one.
+    let has_seasons = |show: &TvShow| show.details.as_ref().is_some_and(|d| d.seasons.is_some());
+    let (show, mut watched) = match existing {
+        Some(p) if has_seasons(&p.show) && !has_seasons(&body.show) => (p.show, p.watched),
+        Some(p) => (body.show, p.watched),
+        None => (body.show, WatchedEpisodes::default()),
+    };
+
+    let episodes = match body.mark.episodes(&show) {
+        Ok(episodes) => episodes,
+        Err(message) => {
+            return HttpResponse::BadRequest().json(json!({"status": "fail", "message": message}));
+        }
+    };
+    watched.mark(episodes, !body.unwatch);
+
+    let progress = sqlx::query_as!(
+        SeriesProgress,
+        r#"
+        INSERT INTO
+            series_progress (user_id, show_key, show, watched, updated_at)
+        VALUES
+            ($1, $2, $3, $4, NOW())
+        ON CONFLICT (user_id, show_key) DO UPDATE SET
+            show = EXCLUDED.show,
+            watched = EXCLUDED.watched,
+            updated_at = NOW()
+        RETURNING
+            user_id, show_key, show, watched, updated_at
+        "#,
+        user_id,
+        show_key,
+        json!(show),
+        json!(watched)
+    )
+    .fetch_one(&mut *tx)
+    .await;
+
+    match progress {
+        Ok(progress) => match tx.commit().await {
+            Ok(()) => HttpResponse::Ok().json(ProgressResponse {
+                status: "success".to_string(),
+                data: progress.into(),
+            }),
+            Err(e) => HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()})),
+        },
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+pub fn config(conf: &mut web::ServiceConfig) {
+    conf.service(get_progress_handler)
+        .service(post_watched_handler);
+}
@@ -0,0 +1,205 @@
+use crate::db_helpers::{save_collection_entries, undo_collection_revision};
+use crate::handlers::members::require_role;
+use crate::{jwt_auth, AppState};
+use actix_web::{get, post, web, HttpResponse, Responder};
+use common::model::collections::{UserCollection, UserCollectionPatchResponse};
+use common::model::members::CollectionRole;
+use common::model::revisions::{CollectionRevision, RevisionsData, RevisionsResponse};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+const DEFAULT_REVISIONS_LIMIT: i64 = 50;
+const MAX_REVISIONS_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+struct RevisionsQuery {
+    limit: Option<i64>,
+}
+
+fn restored_response(
+    actor_id: Uuid,
+    collection: Result<Option<UserCollection>, sqlx::Error>,
+) -> HttpResponse {
+    match collection {
+        Ok(Some(rec)) => HttpResponse::Ok().json(UserCollectionPatchResponse {
+            status: "success".to_string(),
+            data: rec.personalized_for(actor_id),
+        }),
+        Ok(None) => HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Collection not found"})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("Restore failed: {}", e)
+        })),
+    }
+}
+
+// Callers check the user's role on the collection first.
+async fn get_revision(
+    collection_id: Uuid,
+    revision_id: Option<Uuid>,
+    data: &AppState,
+) -> Result<Option<CollectionRevision>, sqlx::Error> {
+    // No revision id means the latest one still to undo: undos themselves are skipped, as are
+    // revisions already undone, so undoing again steps further back.
+    sqlx::query_as!(
+        CollectionRevision,
+        r#"SELECT
+            r.id, r.collection_id, r.actor_id, u.name as "actor_name?", r.created_at,
+            r.added, r.removed, r.snapshot
+        FROM collection_revisions r
+        LEFT JOIN users u ON u.id = r.actor_id
+        WHERE
+            r.collection_id = $1
+        AND
+            CASE
+                WHEN $2::uuid IS NULL THEN
+                    r.reverts IS NULL
+                    AND NOT EXISTS (
+                        SELECT 1 FROM collection_revisions u WHERE u.reverts = r.id
+                    )
+                ELSE r.id = $2
+            END
+        ORDER BY r.created_at DESC
+        LIMIT 1
+        "#,
+        collection_id,
+        revision_id
+    )
+    .fetch_optional(&data.db)
+    .await
+}
+
+#[get("/collection/{id}/revisions")]
+async fn get_collection_revisions_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    query: web::Query<RevisionsQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    let member = match require_role(user_id, collection_id, CollectionRole::Viewer, &data).await {
+        Ok(role) => (role != CollectionRole::Owner).then_some(user_id),
+        Err(res) => return res,
+    };
+
+    let revisions = sqlx::query_as!(
+        CollectionRevision,
+        r#"SELECT
+            r.id, r.collection_id, r.actor_id, u.name as "actor_name?", r.created_at,
+            r.added, r.removed, r.snapshot
+        FROM collection_revisions r
+        LEFT JOIN users u ON u.id = r.actor_id
+        WHERE
+            r.collection_id = $1
+        ORDER BY r.created_at DESC
+        LIMIT $2
+        "#,
+        collection_id,
+        query
+            .limit
+            .unwrap_or(DEFAULT_REVISIONS_LIMIT)
+            .clamp(1, MAX_REVISIONS_LIMIT)
+    )
+    .fetch_all(&data.db)
+    .await;
+
+    match revisions {
+        Ok(revisions) => HttpResponse::Ok().json(RevisionsResponse {
+            status: "success".to_string(),
+            data: RevisionsData {
+                revisions: revisions
+                    .into_iter()
+                    .map(|rev| rev.personalized(member))
+                    .collect(),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Puts the collection back the way that revision left it. The restore is itself a new revision,
+// so it can be undone too.
+#[post("/collection/{id}/revisions/{revision_id}/restore")]
+async fn post_restore_collection_revision_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<(Uuid, Uuid)>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let actor_id = jwt_guard.user.id;
+    let (collection_id, revision_id) = path.into_inner();
+
+    let member = match require_role(actor_id, collection_id, CollectionRole::Editor, &data).await {
+        Ok(role) => (role != CollectionRole::Owner).then_some(actor_id),
+        Err(res) => return res,
+    };
+
+    // Only the restorer's own ratings, notes etc. go back to how they were; everyone else's stay.
+    let snapshot = match get_revision(collection_id, Some(revision_id), &data).await {
+        Ok(Some(rev)) => rev.personalized(member).snapshot,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(json!({"status": "fail", "message": "Revision not found"}));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    restored_response(
+        actor_id,
+        save_collection_entries(actor_id, collection_id, member, &snapshot, &data).await,
+    )
+}
+
+// Reverts just the latest change, keeping entries' notes etc. as they are now. Each undo steps one
+// further back. Changes that only touched entries' details (notes, ratings, order) can't be picked
+// apart like that, so those are refused; restoring a revision takes them back.
+#[post("/collection/{id}/undo")]
+async fn post_undo_collection_change_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let actor_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    if let Err(res) = require_role(actor_id, collection_id, CollectionRole::Editor, &data).await {
+        return res;
+    }
+
+    let latest = match get_revision(collection_id, None, &data).await {
+        Ok(Some(rev)) => rev,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(json!({"status": "fail", "message": "Nothing to undo"}));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    if !latest.adds_or_removes() {
+        return HttpResponse::Conflict().json(json!({
+            "status": "fail",
+            "message": "The last change only edited entries, so it can't be undone. Restore an earlier revision instead."
+        }));
+    }
+
+    restored_response(
+        actor_id,
+        undo_collection_revision(actor_id, collection_id, &latest, &data).await,
+    )
+}
+
+pub fn config(conf: &mut web::ServiceConfig) {
+    conf.service(get_collection_revisions_handler)
+        .service(post_restore_collection_revision_handler)
+        .service(post_undo_collection_change_handler);
+}
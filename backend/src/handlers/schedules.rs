@@ -0,0 +1,1135 @@
+use crate::ai::ai_schedule::{get_themed, resolve_picks, thumbed_down};
+use crate::calendar::render_calendar;
+use crate::db_helpers::{
+    can_view_schedule, get_channel_lineup, get_collection, get_recurrence, get_schedule,
+    get_series_progress, get_stream_urls, get_user_collections, save_entry_details, topped_up,
+};
+use crate::guide::{render_m3u, render_xmltv};
+use crate::handlers::members::require_role;
+use crate::tmdb::add_entry_details;
+use crate::{jwt_auth, AppState};
+use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use chrono::{Duration, NaiveDate, Utc};
+use common::model::channel::channel_slots;
+use common::model::collections::{Media, UserCollection};
+use common::model::members::CollectionRole;
+use common::model::playlist::{StreamEntry, StreamUrls, StreamUrlsData, StreamUrlsResponse};
+use common::model::progress::WatchedEpisodes;
+use common::model::schedule_generator::{
+    order_media, pack_items, schedule_items, window_instants, GenerateScheduleSchema,
+    GeneratedSchedule, GeneratedScheduleResponse, ScheduleItem, DEFAULT_MOVIE_RUNTIME,
+};
+use common::model::schedule_recurrence::{
+    NewRecurringScheduleSchema, Recurrence, RecurrenceData, RecurrenceResponse,
+};
+use common::model::schedule_reflow::{
+    find_conflicts, reflow, ConflictsData, ConflictsResponse, ReflowSchema, ReflowedSchedule,
+    ReflowedScheduleResponse,
+};
+use common::model::schedule_themes::{ThemedScheduleSchema, MAX_THEMED_SLOTS};
+use common::model::schedules::{
+    NewScheduleSchema, Schedule, ScheduleResponse, ScheduleSlots, SchedulesData, SchedulesResponse,
+    UpdateScheduleSchema,
+};
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// Enough for an EPG to show tonight and tomorrow.
+const CHANNEL_GUIDE_DAYS: i64 = 2;
+
+// About a year. Past that it's a lot of slots for a schedule nobody will get to the end of.
+const MAX_GENERATE_DAYS: i64 = 366;
+
+pub fn schedule_response(schedule: Result<Option<Schedule>, sqlx::Error>) -> HttpResponse {
+    match schedule {
+        Ok(Some(rec)) => HttpResponse::Ok().json(ScheduleResponse {
+            status: "success".to_string(),
+            data: rec,
+        }),
+        Ok(None) => HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Schedule not found"})),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Puts the slots in start order, refusing any that take no time at all, overlap, or run past
+// the end of their window. Conflicts come back with what they are, so they can be re-flowed.
+pub fn validate_slots(slots: &mut ScheduleSlots) -> Result<(), HttpResponse> {
+    if slots.slots.iter().any(|s| s.duration == 0) {
+        return Err(HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Every slot needs a duration"})));
+    }
+    slots.sort();
+
+    let conflicts = find_conflicts(slots);
+    match conflicts.first() {
+        Some(first) => Err(HttpResponse::Conflict().json(json!({
+            "status": "fail",
+            "message": first.describe(slots),
+            "conflicts": conflicts
+        }))),
+        None => Ok(()),
+    }
+}
+
+// The owner's schedule, or the response to send back instead. Locked ones can only be unlocked.
+pub async fn require_own_schedule(
+    owner_id: Uuid,
+    schedule_id: Uuid,
+    unlocking: bool,
+    data: &AppState,
+) -> Result<Schedule, HttpResponse> {
+    match get_schedule(schedule_id, data).await {
+        Ok(Some(schedule)) if schedule.owner_id != owner_id => Err(HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Schedule not found"}))),
+        Ok(Some(schedule)) if schedule.locked && !unlocking => Err(HttpResponse::Forbidden()
+            .json(json!({"status": "fail", "message": "Schedule is locked"}))),
+        Ok(Some(schedule)) => Ok(topped_up(schedule, data).await),
+        Ok(None) => Err(HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Schedule not found"}))),
+        Err(e) => Err(HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}))),
+    }
+}
+
+// A collection to schedule from, if the user can see it, with whatever runtimes and episodes TMDB
+// can add using their key.
+pub async fn load_source_collection(
+    user_id: Uuid,
+    collection_id: Uuid,
+    tmdb_key: Option<&str>,
+    data: &AppState,
+) -> Result<UserCollection, HttpResponse> {
+    require_role(user_id, collection_id, CollectionRole::Viewer, data).await?;
+
+    let mut col = match get_collection(collection_id, data).await {
+        Ok(Some(col)) => col,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound()
+                .json(json!({"status": "fail", "message": "Collection not found"})));
+        }
+        Err(e) => {
+            return Err(HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()})));
+        }
+    };
+
+    if let Some(key) = tmdb_key {
+        // Real runtimes when we can get them, the defaults when we can't.
+        if add_entry_details(key, &mut col.collection).await > 0 {
+            // Kept for next time where the user may edit; viewers just get them now.
+            if let Err(e) = save_entry_details(user_id, col.id, &col.collection, data).await {
+                warn!("Saving TMDB details for {} failed: {e}", col.id);
+            }
+        }
+    }
+
+    Ok(col)
+}
+
+#[get("/schedules")]
+async fn get_schedules_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let schedules = sqlx::query_as!(
+        Schedule,
+        r#"SELECT
+        id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+        source_collection
+        FROM schedules
+        WHERE owner_id = $1
+        ORDER BY created_at
+        "#,
+        jwt_guard.user.id
+    )
+    .fetch_all(&data.db)
+    .await;
+
+    let schedules = match schedules {
+        Ok(schedules) => schedules,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    // Recurring ones are topped up as they're looked at.
+    let mut laid_out = vec![];
+    for schedule in schedules {
+        laid_out.push(topped_up(schedule, &data).await);
+    }
+
+    HttpResponse::Ok().json(SchedulesResponse {
+        status: "success".to_string(),
+        data: SchedulesData {
+            schedules: laid_out,
+        },
+    })
+}
+
+#[get("/schedule/{id}")]
+async fn get_schedule_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let schedule = match get_schedule(path.into_inner(), &data).await {
+        Ok(Some(schedule)) => schedule,
+        other => return schedule_response(other),
+    };
+
+    match can_view_schedule(Some(jwt_guard.user.id), &schedule, &data).await {
+        Ok(true) => schedule_response(Ok(Some(topped_up(schedule, &data).await))),
+        // Same as not existing, so private schedules can't be probed for.
+        Ok(false) => schedule_response(Ok(None)),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+#[get("/schedule/{id}/conflicts")]
+async fn get_schedule_conflicts_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let schedule = match get_schedule(path.into_inner(), &data).await {
+        Ok(Some(schedule)) => schedule,
+        other => return schedule_response(other),
+    };
+
+    match can_view_schedule(Some(jwt_guard.user.id), &schedule, &data).await {
+        Ok(true) => {
+            let mut schedule = topped_up(schedule, &data).await;
+            schedule.schedule.sort();
+            HttpResponse::Ok().json(ConflictsResponse {
+                status: "success".to_string(),
+                data: ConflictsData {
+                    conflicts: find_conflicts(&schedule.schedule),
+                },
+            })
+        }
+        Ok(false) => schedule_response(Ok(None)),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Inserts, removes or delays a slot and moves the rest to fit, per the policy. A dry run says what
+// would happen without saving it.
+#[post("/schedule/{id}/reflow")]
+async fn post_schedule_reflow_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<ReflowSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+    let body = body.into_inner();
+
+    let mut schedule = match require_own_schedule(owner_id, path.into_inner(), false, &data).await {
+        Ok(schedule) => schedule,
+        Err(res) => return res,
+    };
+    if schedule.is_channel() {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Channels have no slots to re-flow"}));
+    }
+
+    let reflowed = match reflow(schedule.schedule.clone(), body.edit, body.policy) {
+        Ok(reflowed) => reflowed,
+        Err(message) => {
+            return HttpResponse::BadRequest().json(json!({"status": "fail", "message": message}));
+        }
+    };
+
+    if body.dry_run {
+        schedule.schedule = reflowed.slots;
+    } else {
+        let saved = sqlx::query_as!(
+            Schedule,
+            r#"
+            UPDATE
+                schedules
+            SET
+                schedule = $3
+            WHERE
+                owner_id = $1 AND id = $2
+            RETURNING
+                id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked,
+                tags, source_collection
+            "#,
+            owner_id,
+            schedule.id,
+            json!(reflowed.slots)
+        )
+        .fetch_one(&data.db)
+        .await;
+        schedule = match saved {
+            Ok(saved) => saved,
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(json!({"status": "error", "message": e.to_string()}));
+            }
+        };
+    }
+
+    let conflicts = find_conflicts(&schedule.schedule);
+    HttpResponse::Ok().json(ReflowedScheduleResponse {
+        status: "success".to_string(),
+        data: ReflowedSchedule {
+            schedule,
+            dropped: reflowed.dropped,
+            conflicts,
+        },
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedQuery {
+    token: Option<String>,
+}
+
+// The schedule behind a feed URL, if whoever is asking may see it. Calendar apps and IPTV players
+// can't log in, so subscriptions pass the user's calendar token instead.
+async fn feed_schedule(
+    jwt_guard: Option<jwt_auth::JwtMiddleware>,
+    schedule_id: Uuid,
+    token: Option<&str>,
+    data: &AppState,
+) -> Result<Schedule, HttpResponse> {
+    let viewer_id = match (jwt_guard, token) {
+        (Some(guard), _) => Some(guard.user.id),
+        (None, Some(token)) => sqlx::query_scalar!(
+            "SELECT user_id FROM calendar_tokens WHERE token = $1",
+            token
+        )
+        .fetch_optional(&data.db)
+        .await
+        .map_err(|e| {
+            HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}))
+        })?,
+        (None, None) => None,
+    };
+
+    let schedule = match get_schedule(schedule_id, data).await {
+        Ok(Some(schedule)) => schedule,
+        other => return Err(schedule_response(other)),
+    };
+
+    match can_view_schedule(viewer_id, &schedule, data).await {
+        Ok(true) => {
+            // Feeds go to calendar apps and anyone with the link, so no ratings or notes go along.
+            let mut schedule = topped_up(schedule, data).await;
+            schedule
+                .schedule
+                .slots
+                .iter_mut()
+                .for_each(|slot| slot.media.strip_personal());
+            Ok(schedule)
+        }
+        Ok(false) => Err(schedule_response(Ok(None))),
+        Err(e) => Err(HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}))),
+    }
+}
+
+fn feed_response(content_type: &str, filename: String, body: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header((
+            "Content-Disposition",
+            format!("inline; filename=\"{filename}\""),
+        ))
+        .body(body)
+}
+
+// The schedule as an iCalendar feed. It's rendered fresh each time, so subscribers see changes on
+// their next poll.
+#[get("/schedule/{id}/calendar.ics")]
+async fn get_schedule_calendar_handler(
+    jwt_guard: Option<jwt_auth::JwtMiddleware>,
+    path: web::Path<Uuid>,
+    query: web::Query<FeedQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match feed_schedule(jwt_guard, path.into_inner(), query.token.as_deref(), &data).await {
+        Ok(schedule) => feed_response(
+            "text/calendar; charset=utf-8",
+            format!("baing-{}.ics", schedule.id),
+            render_calendar(&schedule),
+        ),
+        Err(res) => res,
+    }
+}
+
+// The schedule as an XMLTV guide for media-center EPGs. Channels have no slots of their own, so
+// theirs is laid out from now for the next couple of days.
+#[get("/schedule/{id}/guide.xml")]
+async fn get_schedule_guide_handler(
+    jwt_guard: Option<jwt_auth::JwtMiddleware>,
+    path: web::Path<Uuid>,
+    query: web::Query<FeedQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let schedule =
+        match feed_schedule(jwt_guard, path.into_inner(), query.token.as_deref(), &data).await {
+            Ok(schedule) => schedule,
+            Err(res) => return res,
+        };
+
+    let slots = if schedule.is_channel() {
+        let lineup = match get_channel_lineup(&schedule, &data).await {
+            Ok(lineup) => lineup,
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(json!({"status": "error", "message": e.to_string()}));
+            }
+        };
+        let now = Utc::now();
+        channel_slots(
+            &lineup,
+            schedule.created_at.unwrap_or(now),
+            now,
+            now + Duration::days(CHANNEL_GUIDE_DAYS),
+        )
+    } else {
+        schedule.schedule.slots.clone()
+    };
+
+    feed_response(
+        "application/xml; charset=utf-8",
+        format!("baing-{}.xml", schedule.id),
+        render_xmltv(&schedule, &slots),
+    )
+}
+
+// The schedule as an M3U playlist, one entry per slot, or one loop of a channel.
+#[get("/schedule/{id}/playlist.m3u")]
+async fn get_schedule_playlist_handler(
+    jwt_guard: Option<jwt_auth::JwtMiddleware>,
+    path: web::Path<Uuid>,
+    query: web::Query<FeedQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let schedule =
+        match feed_schedule(jwt_guard, path.into_inner(), query.token.as_deref(), &data).await {
+            Ok(schedule) => schedule,
+            Err(res) => return res,
+        };
+
+    let items = if schedule.is_channel() {
+        match get_channel_lineup(&schedule, &data).await {
+            Ok(lineup) => lineup,
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(json!({"status": "error", "message": e.to_string()}));
+            }
+        }
+    } else {
+        schedule
+            .schedule
+            .slots
+            .iter()
+            .map(|s| ScheduleItem {
+                media: s.media.clone(),
+                duration: s.duration,
+                episode: s.episode.clone(),
+            })
+            .collect()
+    };
+
+    match get_stream_urls(schedule.id, &data).await {
+        Ok(urls) => feed_response(
+            "audio/x-mpegurl; charset=utf-8",
+            format!("baing-{}.m3u", schedule.id),
+            render_m3u(&schedule, &items, &urls),
+        ),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// What the schedule plays, once each, with the URL its playlist entry will have.
+async fn stream_urls_response(
+    schedule: &Schedule,
+    urls: StreamUrls,
+    data: &AppState,
+) -> HttpResponse {
+    let items = if schedule.is_channel() {
+        match get_channel_lineup(schedule, data).await {
+            Ok(lineup) => lineup.into_iter().map(|i| i.media).collect(),
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(json!({"status": "error", "message": e.to_string()}));
+            }
+        }
+    } else {
+        schedule
+            .schedule
+            .slots
+            .iter()
+            .map(|s| s.media.clone())
+            .collect::<Vec<Media>>()
+    };
+
+    let mut seen = HashSet::new();
+    let entries = items
+        .into_iter()
+        .filter(|m| seen.insert(m.identity()))
+        .map(|m| StreamEntry {
+            key: m.identity(),
+            title: m.to_string(),
+            url: urls.url_for(&m, None),
+        })
+        .collect();
+
+    HttpResponse::Ok().json(StreamUrlsResponse {
+        status: "success".to_string(),
+        data: StreamUrlsData { urls, entries },
+    })
+}
+
+#[get("/schedule/{id}/stream-urls")]
+async fn get_stream_urls_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    // Reading the settings doesn't change anything, so a locked schedule is fine.
+    let schedule =
+        match require_own_schedule(jwt_guard.user.id, path.into_inner(), true, &data).await {
+            Ok(schedule) => schedule,
+            Err(res) => return res,
+        };
+
+    match get_stream_urls(schedule.id, &data).await {
+        Ok(urls) => stream_urls_response(&schedule, urls, &data).await,
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Replaces where the schedule's playlist entries point. That's not what a lock protects, so locked
+// schedules can still have theirs changed.
+#[post("/schedule/{id}/stream-urls")]
+async fn post_stream_urls_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<StreamUrls>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let schedule =
+        match require_own_schedule(jwt_guard.user.id, path.into_inner(), true, &data).await {
+            Ok(schedule) => schedule,
+            Err(res) => return res,
+        };
+    let mut urls = body.into_inner();
+    urls.tidy();
+
+    let saved = sqlx::query_scalar!(
+        r#"
+        INSERT INTO
+            schedule_stream_urls (schedule_id, urls, updated_at)
+        VALUES
+            ($1, $2, NOW())
+        ON CONFLICT (schedule_id) DO UPDATE SET
+            urls = EXCLUDED.urls,
+            updated_at = NOW()
+        RETURNING
+            urls
+        "#,
+        schedule.id,
+        json!(urls)
+    )
+    .fetch_one(&data.db)
+    .await;
+
+    match saved {
+        Ok(urls) => stream_urls_response(&schedule, Some(urls).into(), &data).await,
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+#[post("/schedules")]
+async fn post_schedule_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<NewScheduleSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let mut body = body.into_inner();
+    if body.name.trim().is_empty() {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Schedules need a name"}));
+    }
+    if let Err(res) = validate_slots(&mut body.schedule) {
+        return res;
+    }
+
+    let schedule = sqlx::query_as!(
+        Schedule,
+        r#"
+        INSERT INTO
+            schedules (owner_id, name, active, sharing, schedule, locked, tags)
+        VALUES
+            ($1, $2, true, 'private', $3, false, $4)
+        RETURNING
+            id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+            source_collection
+        "#,
+        jwt_guard.user.id,
+        body.name.trim(),
+        json!(body.schedule),
+        json!(body.tags)
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    schedule_response(schedule)
+}
+
+// The checks every generated schedule shares.
+fn check_generate_request(name: &str, from: NaiveDate, to: NaiveDate) -> Result<(), HttpResponse> {
+    if name.trim().is_empty() {
+        return Err(HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Schedules need a name"})));
+    }
+    if to < from || (to - from).num_days() >= MAX_GENERATE_DAYS {
+        return Err(HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": format!("The date range must run forwards, for up to {MAX_GENERATE_DAYS} days")
+        })));
+    }
+    Ok(())
+}
+
+async fn insert_generated(
+    owner_id: Uuid,
+    name: &str,
+    slots: ScheduleSlots,
+    data: &AppState,
+) -> Result<Schedule, sqlx::Error> {
+    sqlx::query_as!(
+        Schedule,
+        r#"
+        INSERT INTO
+            schedules (owner_id, name, active, sharing, schedule, locked, tags)
+        VALUES
+            ($1, $2, true, 'private', $3, false, '[]')
+        RETURNING
+            id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+            source_collection
+        "#,
+        owner_id,
+        name.trim(),
+        json!(slots)
+    )
+    .fetch_one(&data.db)
+    .await
+}
+
+fn generated_response(
+    schedule: Result<Schedule, sqlx::Error>,
+    leftover: Vec<ScheduleItem>,
+    dropped: Vec<String>,
+) -> HttpResponse {
+    match schedule {
+        Ok(schedule) => HttpResponse::Ok().json(GeneratedScheduleResponse {
+            status: "success".to_string(),
+            data: GeneratedSchedule {
+                schedule,
+                unscheduled: leftover.iter().map(|item| item.title()).collect(),
+                dropped,
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Builds a new schedule out of the titles in `collections`, fitted into the viewing windows.
+#[post("/schedules/generate")]
+async fn post_generate_schedule_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<GenerateScheduleSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let tmdb_key = jwt_guard.user.tmdb_api_key.filter(|k| !k.is_empty());
+    let body = body.into_inner();
+
+    if body.collections.is_empty() || body.windows.is_empty() {
+        return HttpResponse::BadRequest().json(
+            json!({"status": "fail", "message": "Pick at least one collection and viewing window"}),
+        );
+    }
+    if let Err(res) = check_generate_request(&body.name, body.from, body.to) {
+        return res;
+    }
+
+    let mut sources = vec![];
+    for collection_id in &body.collections {
+        match load_source_collection(user_id, *collection_id, tmdb_key.as_deref(), &data).await {
+            Ok(col) => sources.push(col),
+            Err(res) => return res,
+        }
+    }
+
+    // Shows carry on from where the user is in them.
+    let progress: HashMap<String, WatchedEpisodes> = match get_series_progress(user_id, &data).await
+    {
+        Ok(progress) => progress
+            .into_iter()
+            .map(|p| (p.show_key, p.watched))
+            .collect(),
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let items: Vec<ScheduleItem> = order_media(&sources, body.ordering)
+        .iter()
+        .flat_map(|m| schedule_items(m, body.from, progress.get(&m.identity())))
+        .collect();
+    let windows = window_instants(&body.windows, body.from, body.to, &body.time_zone);
+    let (slots, leftover) = pack_items(items, &windows, body.gap);
+
+    let schedule = insert_generated(user_id, &body.name, slots, &data).await;
+    generated_response(schedule, leftover, vec![])
+}
+
+// Asks the AI for a lineup on the theme, looks the titles up on TMDB and packs them into the
+// windows in the order suggested, as a schedule to edit from there.
+#[post("/schedules/themed")]
+async fn post_themed_schedule_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<ThemedScheduleSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let tmdb_key = jwt_guard.user.tmdb_api_key.filter(|k| !k.is_empty());
+    let body = body.into_inner();
+
+    if body.theme.trim().is_empty() || body.windows.is_empty() {
+        return HttpResponse::BadRequest().json(
+            json!({"status": "fail", "message": "Give a theme and at least one viewing window"}),
+        );
+    }
+    if let Err(res) = check_generate_request(&body.name, body.from, body.to) {
+        return res;
+    }
+
+    let windows = window_instants(&body.windows, body.from, body.to, &body.time_zone);
+    let minutes: i64 = windows
+        .iter()
+        .map(|(start, end)| (*end - *start).num_minutes())
+        .sum();
+    if minutes <= 0 {
+        return HttpResponse::BadRequest().json(
+            json!({"status": "fail", "message": "The viewing windows leave no time to watch"}),
+        );
+    }
+    // Roughly one slot per film's worth of time, whatever mix of films and episodes comes back.
+    let count = ((minutes / DEFAULT_MOVIE_RUNTIME as i64) as usize).clamp(1, MAX_THEMED_SLOTS);
+    let described = format!(
+        "from {} to {}, over {} hours in total",
+        body.from,
+        body.to,
+        minutes / 60
+    );
+
+    // All collections, not just the special ones, so ratings and watch history count too.
+    let user_collections = match get_user_collections(user_id, &data).await {
+        Ok(collections) => collections,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+    let excluded = thumbed_down(&user_collections);
+
+    let lineup = match get_themed(
+        &data.api_keys,
+        user_collections,
+        body.theme.trim(),
+        count,
+        &described,
+    )
+    .await
+    {
+        Ok(lineup) => lineup,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": format!("Error: {e}")}));
+        }
+    };
+    debug!("{:#?}", &lineup);
+
+    let (items, dropped) =
+        resolve_picks(tmdb_key.as_deref(), lineup, body.theme.trim(), &excluded).await;
+    let (slots, leftover) = pack_items(items, &windows, body.gap);
+
+    let schedule = insert_generated(user_id, &body.name, slots, &data).await;
+    generated_response(schedule, leftover, dropped)
+}
+
+// The checks every set of recurrence rules gets: that they make sense, and that the user can see
+// every collection they draw on.
+async fn check_recurrence(
+    user_id: Uuid,
+    recurrence: &Recurrence,
+    data: &AppState,
+) -> Result<(), HttpResponse> {
+    if let Err(message) = recurrence.check() {
+        return Err(HttpResponse::BadRequest().json(json!({"status": "fail", "message": message})));
+    }
+    for collection_id in recurrence
+        .rules
+        .iter()
+        .filter_map(|r| r.source.collection())
+    {
+        require_role(user_id, collection_id, CollectionRole::Viewer, data).await?;
+    }
+    Ok(())
+}
+
+// A standing routine rather than a one-off plan. It starts with no slots; they're laid out from
+// the rules as the schedule is looked at, which the response already has been.
+#[post("/schedules/recurring")]
+async fn post_recurring_schedule_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<NewRecurringScheduleSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+    let body = body.into_inner();
+
+    if body.name.trim().is_empty() {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Schedules need a name"}));
+    }
+    if let Err(res) = check_recurrence(user_id, &body.recurrence, &data).await {
+        return res;
+    }
+
+    let mut tx = match data.db.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let schedule_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO
+            schedules (owner_id, name, active, sharing, schedule, locked, tags)
+        VALUES
+            ($1, $2, true, 'private', $3, false, '[]')
+        RETURNING
+            id
+        "#,
+        user_id,
+        body.name.trim(),
+        json!(ScheduleSlots::default())
+    )
+    .fetch_one(&mut *tx)
+    .await;
+    let schedule_id = match schedule_id {
+        Ok(id) => id,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let inserted = sqlx::query!(
+        r#"
+        INSERT INTO
+            schedule_recurrence (schedule_id, recurrence)
+        VALUES
+            ($1, $2)
+        "#,
+        schedule_id,
+        json!(body.recurrence)
+    )
+    .execute(&mut *tx)
+    .await;
+    if let Err(e) = inserted {
+        return HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}));
+    }
+
+    match get_schedule(schedule_id, &data).await {
+        Ok(Some(schedule)) => schedule_response(Ok(Some(topped_up(schedule, &data).await))),
+        other => schedule_response(other),
+    }
+}
+
+#[get("/schedule/{id}/recurrence")]
+async fn get_recurrence_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    // Reading the rules doesn't change anything, so a locked schedule is fine.
+    let schedule =
+        match require_own_schedule(jwt_guard.user.id, path.into_inner(), true, &data).await {
+            Ok(schedule) => schedule,
+            Err(res) => return res,
+        };
+
+    match get_recurrence(schedule.id, &data).await {
+        Ok(recurrence) => {
+            let (recurrence, materialized_until) = recurrence.unzip();
+            HttpResponse::Ok().json(RecurrenceResponse {
+                status: "success".to_string(),
+                data: RecurrenceData {
+                    recurrence,
+                    materialized_until,
+                },
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Sets a schedule's recurrence rules, making it recur if it didn't. Whatever has started stays;
+// everything still to come is laid out again from the new rules.
+#[post("/schedule/{id}/recurrence")]
+async fn post_recurrence_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<Recurrence>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+    let recurrence = body.into_inner();
+
+    let schedule = match require_own_schedule(owner_id, path.into_inner(), false, &data).await {
+        Ok(schedule) => schedule,
+        Err(res) => return res,
+    };
+    if schedule.is_channel() {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Channels can't recur"}));
+    }
+    if let Err(res) = check_recurrence(owner_id, &recurrence, &data).await {
+        return res;
+    }
+
+    let now = Utc::now();
+    let mut slots = schedule.schedule;
+    slots.slots.retain(|s| s.start < now);
+
+    let mut tx = match data.db.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let saved = sqlx::query!(
+        r#"
+        INSERT INTO
+            schedule_recurrence (schedule_id, recurrence, materialized_until, updated_at)
+        VALUES
+            ($1, $2, $3, NOW())
+        ON CONFLICT (schedule_id) DO UPDATE SET
+            recurrence = EXCLUDED.recurrence,
+            materialized_until = EXCLUDED.materialized_until,
+            updated_at = NOW()
+        "#,
+        schedule.id,
+        json!(recurrence),
+        now
+    )
+    .execute(&mut *tx)
+    .await;
+    if let Err(e) = saved {
+        return HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}));
+    }
+
+    let cleared = sqlx::query!(
+        "UPDATE schedules SET schedule = $2 WHERE id = $1",
+        schedule.id,
+        json!(slots)
+    )
+    .execute(&mut *tx)
+    .await;
+    if let Err(e) = cleared {
+        return HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}));
+    }
+
+    // Laid out straight away, so neither this response nor the first calendar poll comes up empty.
+    match get_schedule(schedule.id, &data).await {
+        Ok(Some(schedule)) => schedule_response(Ok(Some(topped_up(schedule, &data).await))),
+        other => schedule_response(other),
+    }
+}
+
+// Stops a schedule recurring. What's been laid out so far stays as ordinary slots.
+#[delete("/schedule/{id}/recurrence")]
+async fn delete_recurrence_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let schedule =
+        match require_own_schedule(jwt_guard.user.id, path.into_inner(), false, &data).await {
+            Ok(schedule) => schedule,
+            Err(res) => return res,
+        };
+
+    let deleted = sqlx::query!(
+        "DELETE FROM schedule_recurrence WHERE schedule_id = $1",
+        schedule.id
+    )
+    .execute(&data.db)
+    .await;
+
+    match deleted {
+        Ok(_) => schedule_response(Ok(Some(schedule))),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+#[patch("/schedule/{id}")]
+async fn patch_schedule_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateScheduleSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+    let schedule_id = path.into_inner();
+    let mut body = body.into_inner();
+
+    let unlocking = body.locked == Some(false);
+    let existing = match require_own_schedule(owner_id, schedule_id, unlocking, &data).await {
+        Ok(schedule) => schedule,
+        Err(res) => return res,
+    };
+    if body.name.as_ref().is_some_and(|n| n.trim().is_empty()) {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Schedules need a name"}));
+    }
+    if let Some(slots) = body.schedule.as_mut() {
+        // Slots sent back without their windows keep the ones they were generated for.
+        if slots.windows.is_empty() {
+            slots.windows = existing.schedule.windows;
+        }
+        if let Err(res) = validate_slots(slots) {
+            return res;
+        }
+    }
+
+    let schedule = sqlx::query_as!(
+        Schedule,
+        r#"
+        UPDATE
+            schedules
+        SET
+            name = COALESCE($3, name),
+            active = COALESCE($4, active),
+            sharing = COALESCE(CAST($5::text AS SHARE_TYPE), sharing),
+            schedule = COALESCE($6, schedule),
+            locked = COALESCE($7, locked),
+            tags = COALESCE($8, tags)
+        WHERE
+            owner_id = $1 AND id = $2
+        RETURNING
+            id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+            source_collection
+        "#,
+        owner_id,
+        schedule_id,
+        body.name.as_deref().map(str::trim),
+        body.active,
+        body.sharing.map(|s| s.to_string()),
+        body.schedule.map(|s| json!(s)),
+        body.locked,
+        body.tags.map(|t| json!(t))
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    schedule_response(schedule)
+}
+
+#[delete("/schedule/{id}")]
+async fn delete_schedule_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+    let schedule_id = path.into_inner();
+
+    if let Err(res) = require_own_schedule(owner_id, schedule_id, false, &data).await {
+        return res;
+    }
+
+    let schedule = sqlx::query_as!(
+        Schedule,
+        r#"
+        DELETE FROM schedules
+        WHERE
+            owner_id = $1 AND id = $2
+        RETURNING
+            id, owner_id, name, created_at, active, CAST(sharing as text), schedule, locked, tags,
+            source_collection
+        "#,
+        owner_id,
+        schedule_id
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    schedule_response(schedule)
+}
+
+pub fn config(conf: &mut web::ServiceConfig) {
+    conf.service(get_schedules_handler)
+        .service(get_schedule_handler)
+        .service(get_schedule_conflicts_handler)
+        .service(post_schedule_reflow_handler)
+        .service(get_schedule_calendar_handler)
+        .service(get_schedule_guide_handler)
+        .service(get_schedule_playlist_handler)
+        .service(get_stream_urls_handler)
+        .service(post_stream_urls_handler)
+        .service(post_schedule_handler)
+        .service(post_generate_schedule_handler)
+        .service(post_themed_schedule_handler)
+        .service(post_recurring_schedule_handler)
+        .service(get_recurrence_handler)
+        .service(post_recurrence_handler)
+        .service(delete_recurrence_handler)
+        .service(patch_schedule_handler)
+        .service(delete_schedule_handler);
+}
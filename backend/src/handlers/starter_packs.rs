@@ -0,0 +1,321 @@
+use crate::db_helpers::{edit_collection_entries, get_user_special_collections};
+use crate::{jwt_auth, AppState};
+use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use common::model::collections::{Media, UserCollectionData, UserCollectionResponse};
+use common::model::special::SpecialCollection;
+use common::model::starter_packs::{
+    ApplyStarterPackSchema, StarterPack, StarterPackResponse, StarterPackSchema, StarterPacksData,
+    StarterPacksResponse,
+};
+use common::model::user::User;
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+const STARTER_PACKS: &str = include_str!("../../data/starter_packs.json");
+
+// Adds any bundled pack that isn't in the database yet. Packs already there are left alone, so
+// admins' edits survive restarts, and ones they deleted stay deleted.
+pub async fn seed_starter_packs(db: &Pool<Postgres>) -> Result<u64, Box<dyn std::error::Error>> {
+    let packs: Vec<StarterPackSchema> = serde_json::from_str(STARTER_PACKS)?;
+    let mut seeded = 0;
+
+    for pack in packs {
+        seeded += sqlx::query!(
+            r#"INSERT INTO starter_packs (slug, name, description, entries)
+            SELECT $1::VARCHAR, $2::VARCHAR, $3::TEXT, $4::JSONB
+            WHERE NOT EXISTS (SELECT 1 FROM deleted_starter_packs WHERE slug = $1)
+            ON CONFLICT (slug) DO NOTHING
+            "#,
+            pack.slug,
+            pack.name,
+            pack.description,
+            json!(pack.entries)
+        )
+        .execute(db)
+        .await?
+        .rows_affected();
+    }
+
+    Ok(seeded)
+}
+
+fn require_admin(user: &User) -> Result<(), HttpResponse> {
+    match user.role.as_str() {
+        "admin" => Ok(()),
+        _ => Err(HttpResponse::Forbidden()
+            .json(json!({"status": "fail", "message": "Only admins can do that"}))),
+    }
+}
+
+fn validate_pack(pack: &StarterPackSchema) -> Result<(), HttpResponse> {
+    match pack.slug.trim().is_empty() || pack.name.trim().is_empty() {
+        true => Err(HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Starter packs need a slug and a name"}))),
+        false => Ok(()),
+    }
+}
+
+fn pack_response(pack: Result<Option<StarterPack>, sqlx::Error>) -> HttpResponse {
+    match pack {
+        Ok(Some(pack)) => HttpResponse::Ok().json(StarterPackResponse {
+            status: "success".to_string(),
+            data: pack,
+        }),
+        Ok(None) => HttpResponse::NotFound()
+            .json(json!({"status": "fail", "message": "Starter pack not found"})),
+        // Most likely a slug that's already taken.
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "A starter pack with that slug already exists"})),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+async fn get_starter_pack(id: Uuid, data: &AppState) -> Result<Option<StarterPack>, sqlx::Error> {
+    sqlx::query_as!(
+        StarterPack,
+        r#"SELECT id, slug, name, description, entries, created_at, updated_at
+        FROM starter_packs
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&data.db)
+    .await
+}
+
+#[get("/starter-packs")]
+async fn get_starter_packs_handler(
+    _jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let packs = sqlx::query_as!(
+        StarterPack,
+        r#"SELECT id, slug, name, description, entries, created_at, updated_at
+        FROM starter_packs
+        WHERE COALESCE(jsonb_array_length(entries->'entries'), 0) > 0
+        ORDER BY name
+        "#
+    )
+    .fetch_all(&data.db)
+    .await;
+
+    match packs {
+        Ok(packs) => HttpResponse::Ok().json(StarterPacksResponse {
+            status: "success".to_string(),
+            data: StarterPacksData { packs },
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Files the titles the user swiped on into their thumbs up and down collections, in one go.
+#[post("/starter-packs/{id}/apply")]
+async fn post_apply_starter_pack_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<ApplyStarterPackSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = jwt_guard.user.id;
+
+    let pack = match get_starter_pack(path.into_inner(), &data).await {
+        Ok(Some(pack)) => pack,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(json!({"status": "fail", "message": "Starter pack not found"}));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let specials = match get_user_special_collections(user_id, &data).await {
+        Ok(specials) => specials,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let ratings = body.into_inner();
+    let swipes = [
+        (SpecialCollection::ThumbsUp, ratings.thumbs_up),
+        (SpecialCollection::ThumbsDown, ratings.thumbs_down),
+    ];
+
+    let mut tx = match data.db.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let mut collections = vec![];
+    for (special, media) in swipes {
+        let media: Vec<Media> = media.into_iter().filter(|m| pack.contains(m)).collect();
+        if media.is_empty() {
+            continue;
+        }
+        let Some(uc) = specials.iter().find(|uc| uc.is_special(special)) else {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("Missing {} collection", special.name())
+            }));
+        };
+
+        match edit_collection_entries(user_id, uc.id, &mut tx, |entries| {
+            entries.add_entries(media);
+        })
+        .await
+        {
+            Ok(Some(updated)) => collections.push(updated.personalized_for(user_id)),
+            Ok(None) => {}
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(json!({"status": "error", "message": e.to_string()}));
+            }
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}));
+    }
+
+    HttpResponse::Ok().json(UserCollectionResponse {
+        status: "success".to_string(),
+        data: UserCollectionData { collections },
+    })
+}
+
+#[post("/admin/starter-packs")]
+async fn post_starter_pack_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<StarterPackSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(res) = require_admin(&jwt_guard.user) {
+        return res;
+    }
+
+    if let Err(res) = validate_pack(&body) {
+        return res;
+    }
+
+    let pack = sqlx::query_as!(
+        StarterPack,
+        r#"INSERT INTO starter_packs (slug, name, description, entries)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, slug, name, description, entries, created_at, updated_at
+        "#,
+        body.slug,
+        body.name,
+        body.description,
+        json!(body.entries)
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    pack_response(pack)
+}
+
+#[patch("/admin/starter-packs/{id}")]
+async fn patch_starter_pack_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<StarterPackSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(res) = require_admin(&jwt_guard.user) {
+        return res;
+    }
+
+    if let Err(res) = validate_pack(&body) {
+        return res;
+    }
+
+    let pack = sqlx::query_as!(
+        StarterPack,
+        r#"UPDATE starter_packs
+        SET slug = $2, name = $3, description = $4, entries = $5, updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, slug, name, description, entries, created_at, updated_at
+        "#,
+        path.into_inner(),
+        body.slug,
+        body.name,
+        body.description,
+        json!(body.entries)
+    )
+    .fetch_optional(&data.db)
+    .await;
+
+    pack_response(pack)
+}
+
+// Gone for good: the slug is remembered so seeding doesn't bring a bundled pack back.
+#[delete("/admin/starter-packs/{id}")]
+async fn delete_starter_pack_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(res) = require_admin(&jwt_guard.user) {
+        return res;
+    }
+
+    let mut tx = match data.db.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    };
+
+    let pack = sqlx::query_as!(
+        StarterPack,
+        r#"DELETE FROM starter_packs
+        WHERE id = $1
+        RETURNING id, slug, name, description, entries, created_at, updated_at
+        "#,
+        path.into_inner()
+    )
+    .fetch_optional(&mut *tx)
+    .await;
+
+    if let Ok(Some(deleted)) = &pack {
+        let remembered = sqlx::query!(
+            r#"INSERT INTO deleted_starter_packs (slug)
+            VALUES ($1)
+            ON CONFLICT (slug) DO NOTHING
+            "#,
+            deleted.slug
+        )
+        .execute(&mut *tx)
+        .await;
+        if let Err(e) = remembered {
+            return HttpResponse::InternalServerError()
+                .json(json!({"status": "error", "message": e.to_string()}));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()}));
+    }
+
+    pack_response(pack)
+}
+
+pub fn config(conf: &mut web::ServiceConfig) {
+    conf.service(get_starter_packs_handler)
+        .service(post_apply_starter_pack_handler)
+        .service(post_starter_pack_handler)
+        .service(patch_starter_pack_handler)
+        .service(delete_starter_pack_handler);
+}
@@ -0,0 +1,233 @@
+use crate::db_helpers::{
+    edit_collection_entries, get_user_collections, save_collection_rules, save_collection_tags,
+};
+use crate::handlers::members::require_role;
+use crate::{jwt_auth, AppState};
+use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use common::model::collections::{MediaCollection, UserCollection, UserCollectionPatchResponse};
+use common::model::members::CollectionRole;
+use common::model::rules::{CollectionRule, CollectionRules};
+use common::model::tags::{
+    autocomplete_tags, tag_usage, RenameTagSchema, TagAutocompleteResponse, TagUsage,
+    TaggedEntriesResponse, Tags, TagsData, TagsResponse, UpdateTagsSchema,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+const AUTOCOMPLETE_LIMIT: usize = 10;
+
+#[derive(Debug, Deserialize)]
+struct AutocompleteQuery {
+    q: Option<String>,
+    limit: Option<usize>,
+}
+
+fn tags_response(usage: Result<Vec<TagUsage>, Box<dyn std::error::Error>>) -> HttpResponse {
+    match usage {
+        Ok(tags) => HttpResponse::Ok().json(TagsResponse {
+            status: "success".to_string(),
+            data: TagsData { tags },
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Applies `edit` to the tags of every collection and entry tagged `tag`, and to the tags smart
+// collections' rules look for, all in one transaction.
+async fn edit_user_tags<F>(
+    owner_id: Uuid,
+    tag: &str,
+    data: &AppState,
+    edit: F,
+) -> Result<Vec<TagUsage>, Box<dyn std::error::Error>>
+where
+    F: Fn(&mut Tags) -> bool,
+{
+    let mut collections = get_user_collections(owner_id, data).await?;
+    let mut tx = data.db.begin().await?;
+
+    for col in collections.iter_mut() {
+        if edit(&mut col.tags) {
+            save_collection_tags(owner_id, col.id, &col.tags, &mut tx).await?;
+        }
+        if col.rules.edit_tags(&edit) {
+            save_collection_rules(owner_id, col.id, &col.rules, &mut tx).await?;
+        }
+        // Smart collections' entries belong to their source collections.
+        if col.is_smart() || !col.collection.has_tagged_entries(tag) {
+            continue;
+        }
+        let updated = edit_collection_entries(owner_id, col.id, &mut tx, |entries| {
+            for media in entries.entries.iter_mut() {
+                if media.entry_meta().is_some_and(|m| m.tags.contains(tag)) {
+                    edit(&mut media.entry_meta_mut().tags);
+                }
+            }
+        })
+        .await?;
+        if let Some(updated) = updated {
+            *col = updated;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(tag_usage(&collections))
+}
+
+#[get("/tags")]
+async fn get_tags_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let usage = get_user_collections(jwt_guard.user.id, &data)
+        .await
+        .map(|cols| tag_usage(&cols));
+
+    tags_response(usage)
+}
+
+#[get("/tags/autocomplete")]
+async fn get_tags_autocomplete_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    query: web::Query<AutocompleteQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let prefix = query.q.clone().unwrap_or_default();
+    let limit = query.limit.unwrap_or(AUTOCOMPLETE_LIMIT);
+
+    match get_user_collections(jwt_guard.user.id, &data).await {
+        Ok(cols) => HttpResponse::Ok().json(TagAutocompleteResponse {
+            status: "success".to_string(),
+            data: autocomplete_tags(&cols, &prefix, limit),
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+// Everything tagged `tag`, either directly or by being in a collection with that tag.
+#[get("/tags/{tag}/entries")]
+async fn get_tagged_entries_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let rules = CollectionRules {
+        rules: vec![CollectionRule::Tags {
+            tags: vec![path.into_inner()],
+        }],
+    };
+
+    let user_id = jwt_guard.user.id;
+    match get_user_collections(user_id, &data).await {
+        Ok(cols) => {
+            let cols: Vec<UserCollection> = cols
+                .into_iter()
+                .map(|col| col.personalized_for(user_id))
+                .collect();
+            HttpResponse::Ok().json(TaggedEntriesResponse {
+                status: "success".to_string(),
+                data: MediaCollection {
+                    entries: rules.evaluate(&cols),
+                },
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+#[patch("/collection/{id}/tags")]
+async fn patch_collection_tags_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateTagsSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let owner_id = jwt_guard.user.id;
+    let collection_id = path.into_inner();
+
+    // Collection tags are the owner's way of organising their lists, so only they set them.
+    if let Err(res) = require_role(owner_id, collection_id, CollectionRole::Owner, &data).await {
+        return res;
+    }
+
+    let collection = sqlx::query_as!(
+        UserCollection,
+        r#"
+        UPDATE
+            collections
+        SET
+            tags = $1
+        WHERE
+            owner_id = $2 AND id = $3
+        RETURNING
+            id, owner_id,name, created_at, active, collection, locked, tags, special, rules, ranked,
+            CAST(sharing as text)
+        "#,
+        json!(body.tags),
+        owner_id,
+        collection_id
+    )
+    .fetch_one(&data.db)
+    .await;
+
+    let json = match collection {
+        Ok(rec) => {
+            json!(UserCollectionPatchResponse {
+                status: "success".to_string(),
+                data: rec.personalized_for(owner_id)
+            })
+        }
+        Err(e) => {
+            json!({
+                "status": "error",
+                "message": format!("Update failed: {}", e)
+            })
+        }
+    };
+
+    HttpResponse::Ok().json(json)
+}
+
+#[post("/tags/rename")]
+async fn post_rename_tag_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    body: web::Json<RenameTagSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if Tags::normalize(&body.to).is_empty() {
+        return HttpResponse::BadRequest()
+            .json(json!({"status": "fail", "message": "Tags can't be blank"}));
+    }
+
+    tags_response(
+        edit_user_tags(jwt_guard.user.id, &body.from, &data, |tags| {
+            tags.rename(&body.from, &body.to)
+        })
+        .await,
+    )
+}
+
+#[delete("/tags/{tag}")]
+async fn delete_tag_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let tag = path.into_inner();
+
+    tags_response(edit_user_tags(jwt_guard.user.id, &tag, &data, |tags| tags.remove(&tag)).await)
+}
+
+pub fn config(conf: &mut web::ServiceConfig) {
+    conf.service(get_tags_handler)
+        .service(get_tags_autocomplete_handler)
+        .service(get_tagged_entries_handler)
+        .service(patch_collection_tags_handler)
+        .service(post_rename_tag_handler)
+        .service(delete_tag_handler);
+}
@@ -1,8 +1,24 @@
 use crate::response::FilteredUser;
 use crate::{jwt_auth, AppState};
-use actix_web::{get, patch, web, HttpResponse, Responder};
-use common::model::user::UpdateUserSchema;
+use actix_web::{get, patch, post, web, HttpResponse, Responder};
+use common::model::user::{CalendarTokenData, CalendarTokenResponse, UpdateUserSchema};
 use serde_json::json;
+use uuid::Uuid;
+
+fn new_calendar_token() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+fn calendar_token_response(token: Result<String, sqlx::Error>) -> HttpResponse {
+    match token {
+        Ok(token) => HttpResponse::Ok().json(CalendarTokenResponse {
+            status: "success".to_string(),
+            data: CalendarTokenData { token },
+        }),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
 
 #[get("/user/me")]
 async fn get_me_handler(jwt_guard: jwt_auth::JwtMiddleware) -> impl Responder {
@@ -51,6 +67,62 @@ async fn patch_me_handler(
     }
 }
 
+// The user's calendar token, made on first asking.
+#[get("/user/calendar-token")]
+async fn get_calendar_token_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let token = sqlx::query_scalar!(
+        r#"
+        INSERT INTO
+            calendar_tokens (user_id, token)
+        VALUES
+            ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET
+            token = calendar_tokens.token
+        RETURNING
+            token
+        "#,
+        jwt_guard.user.id,
+        new_calendar_token()
+    )
+    .fetch_one(&data.db)
+    .await;
+
+    calendar_token_response(token)
+}
+
+// Swaps the calendar token for a new one, for when a subscription URL got out.
+#[post("/user/calendar-token")]
+async fn post_calendar_token_handler(
+    jwt_guard: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let token = sqlx::query_scalar!(
+        r#"
+        INSERT INTO
+            calendar_tokens (user_id, token)
+        VALUES
+            ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET
+            token = EXCLUDED.token,
+            created_at = NOW()
+        RETURNING
+            token
+        "#,
+        jwt_guard.user.id,
+        new_calendar_token()
+    )
+    .fetch_one(&data.db)
+    .await;
+
+    calendar_token_response(token)
+}
+
 pub fn config(conf: &mut web::ServiceConfig) {
-    conf.service(get_me_handler).service(patch_me_handler);
+    conf.service(get_me_handler)
+        .service(patch_me_handler)
+        .service(get_calendar_token_handler)
+        .service(post_calendar_token_handler);
 }
@@ -1,9 +1,13 @@
 mod ai;
+mod calendar;
 mod config;
 mod db_helpers;
+mod export;
+mod guide;
 mod handlers;
 mod jwt_auth;
 mod response;
+mod tmdb;
 mod token;
 
 use actix_cors::Cors;
@@ -66,11 +70,16 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    match handlers::starter_packs::seed_starter_packs(&pool).await {
+        Ok(seeded) => println!("✅ Seeded {} starter packs", seeded),
+        Err(e) => println!("🔥 Failed to seed the starter packs: {}", e),
+    }
+
     println!("🚀 Server started successfully");
     HttpServer::new(move || {
         let cors = Cors::default()
             .allowed_origin(&config.client_origin)
-            .allowed_methods(vec!["GET", "POST", "PATCH"])
+            .allowed_methods(vec!["GET", "POST", "PATCH", "DELETE"])
             .allowed_headers(vec![
                 header::CONTENT_TYPE,
                 header::AUTHORIZATION,
@@ -93,6 +102,14 @@ async fn main() -> std::io::Result<()> {
                     .configure(handlers::auth::config)
                     .configure(handlers::user::config)
                     .configure(handlers::collections::config)
+                    .configure(handlers::friends::config)
+                    .configure(handlers::members::config)
+                    .configure(handlers::tags::config)
+                    .configure(handlers::revisions::config)
+                    .configure(handlers::starter_packs::config)
+                    .configure(handlers::schedules::config)
+                    .configure(handlers::progress::config)
+                    .configure(handlers::channels::config)
                     .configure(handlers::ai::config),
             )
             .wrap(cors)
@@ -0,0 +1,196 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use common::model::collections::{Media, MediaCollection};
+use common::model::core::{MovieDetails, TvEpisode, TvSeason, TvShowDetails};
+use common::model::tmdb::{
+    MovieDetailsResult, MovieSearch, TvDetailsResult, TvSearch, TvSeasonResult,
+};
+use log::warn;
+use serde::de::DeserializeOwned;
+use std::error;
+use std::sync::LazyLock;
+
+const API_BASE: &str = "https://api.themoviedb.org/3";
+// How long looked up seasons are trusted: a day for shows still airing, a month for the rest in
+// case they come back.
+const AIRING_SEASONS_TTL_DAYS: i64 = 1;
+const SEASONS_TTL_DAYS: i64 = 30;
+
+// One client for every call, so connections get reused.
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+async fn get_json<T: DeserializeOwned>(
+    key: &str,
+    api_call: &str,
+    params: &[(&str, &str)],
+) -> Result<T, Box<dyn error::Error>> {
+    let response = CLIENT
+        .get(format!("{API_BASE}{api_call}"))
+        .query(&[("api_key", key)])
+        .query(params)
+        .header("accept", "application/json")
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(response.json::<T>().await?)
+}
+
+// The best match for a title, trying its year first since remakes share names.
+pub async fn search_movie(
+    key: &str,
+    name: &str,
+    year: i32,
+) -> Result<Option<MovieDetails>, Box<dyn error::Error>> {
+    let year = year.to_string();
+    let mut params = vec![
+        ("query", name),
+        ("include_adult", "false"),
+        ("year", year.as_str()),
+    ];
+    let mut found: MovieSearch = get_json(key, "/search/movie", &params).await?;
+    if found.results.is_empty() {
+        params.pop();
+        found = get_json(key, "/search/movie", &params).await?;
+    }
+
+    Ok(found.results.into_iter().next().map(MovieDetails::from))
+}
+
+pub async fn search_tv_show(
+    key: &str,
+    name: &str,
+    year: i32,
+) -> Result<Option<TvShowDetails>, Box<dyn error::Error>> {
+    let year = year.to_string();
+    let mut params = vec![
+        ("query", name),
+        ("include_adult", "false"),
+        ("first_air_date_year", year.as_str()),
+    ];
+    let mut found: TvSearch = get_json(key, "/search/tv", &params).await?;
+    if found.results.is_empty() {
+        params.pop();
+        found = get_json(key, "/search/tv", &params).await?;
+    }
+
+    Ok(found.results.into_iter().next().map(TvShowDetails::from))
+}
+
+pub async fn get_movie_runtime(
+    key: &str,
+    tmdb_id: i64,
+) -> Result<Option<u32>, Box<dyn error::Error>> {
+    let movie: MovieDetailsResult = get_json(key, &format!("/movie/{tmdb_id}"), &[]).await?;
+
+    // TMDB says 0 when it doesn't know.
+    Ok(movie.runtime.filter(|r| *r > 0))
+}
+
+// Every season but the specials (season 0), with its episodes. Episodes without a runtime of
+// their own get the show's usual one, if TMDB has that.
+pub async fn get_tv_seasons(
+    key: &str,
+    tmdb_id: i64,
+) -> Result<Vec<TvSeason>, Box<dyn error::Error>> {
+    let show: TvDetailsResult = get_json(key, &format!("/tv/{tmdb_id}"), &[]).await?;
+    let usual_runtime = show.episode_run_time.first().copied().filter(|r| *r > 0);
+
+    let mut seasons = vec![];
+    for summary in show.seasons.iter().filter(|s| s.season_number > 0) {
+        let season: TvSeasonResult = get_json(
+            key,
+            &format!("/tv/{tmdb_id}/season/{}", summary.season_number),
+            &[],
+        )
+        .await?;
+
+        seasons.push(TvSeason {
+            season_number: season.season_number,
+            episodes: season
+                .episodes
+                .into_iter()
+                .map(|e| TvEpisode {
+                    episode_number: e.episode_number,
+                    name: e.name,
+                    runtime: e.runtime.filter(|r| *r > 0).or(usual_runtime),
+                    air_date: e.air_date.filter(|d| !d.is_empty()),
+                })
+                .collect(),
+        });
+    }
+
+    Ok(seasons)
+}
+
+// Looks the show's seasons up again, noting when.
+pub async fn add_tv_seasons(
+    key: &str,
+    details: &mut TvShowDetails,
+) -> Result<(), Box<dyn error::Error>> {
+    details.seasons = Some(get_tv_seasons(key, details.tmdb_id).await?);
+    details.seasons_fetched = Some(Utc::now());
+    Ok(())
+}
+
+// Whether the show's last known episode is still to come, or hasn't got a date yet.
+fn still_airing(details: &TvShowDetails, today: NaiveDate) -> bool {
+    details.episodes().last().is_some_and(|(_, e)| {
+        e.air_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .is_none_or(|aired| aired >= today)
+    })
+}
+
+// Whether this title's details need looking up: never fetched, or seasons gone stale. Titles TMDB
+// was never matched with have nothing to look up.
+pub fn needs_details(media: &Media, now: DateTime<Utc>) -> bool {
+    match media {
+        Media::Movie(m) => m.details.as_ref().is_some_and(|d| d.runtime.is_none()),
+        Media::TvShow(t) => t.details.as_ref().is_some_and(|d| {
+            let ttl = if still_airing(d, now.date_naive()) {
+                AIRING_SEASONS_TTL_DAYS
+            } else {
+                SEASONS_TTL_DAYS
+            };
+            d.seasons.is_none()
+                || d.seasons_fetched
+                    .is_none_or(|fetched| now - fetched > Duration::days(ttl))
+        }),
+        _ => false,
+    }
+}
+
+// Looks up runtimes, seasons and episodes for the entries that don't have them yet, or whose
+// seasons are stale. A lookup that fails is logged and skipped, keeping what the entry had.
+// Returns how many entries changed.
+pub async fn add_entry_details(key: &str, collection: &mut MediaCollection) -> usize {
+    let now = Utc::now();
+    let mut changed = 0;
+    for media in collection
+        .entries
+        .iter_mut()
+        .filter(|m| needs_details(m, now))
+    {
+        let looked_up = match media {
+            Media::Movie(m) => match m.details.as_mut() {
+                Some(details) => get_movie_runtime(key, details.tmdb_id)
+                    .await
+                    // Still nothing is recorded as 0, so we don't ask again every time.
+                    .map(|runtime| details.runtime = Some(runtime.unwrap_or(0))),
+                None => continue,
+            },
+            Media::TvShow(t) => match t.details.as_mut() {
+                Some(details) => add_tv_seasons(key, details).await,
+                None => continue,
+            },
+            _ => continue,
+        };
+        match looked_up {
+            Ok(()) => changed += 1,
+            Err(e) => warn!("TMDB lookup for {} failed: {e}", media.name()),
+        }
+    }
+
+    changed
+}
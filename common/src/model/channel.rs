@@ -0,0 +1,140 @@
+use crate::model::collections::UserCollection;
+use crate::model::schedule_generator::{media_items, order_media, ScheduleItem, ScheduleOrdering};
+use crate::model::schedules::ScheduleSlot;
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_UP_NEXT: usize = 5;
+pub const MAX_UP_NEXT: usize = 50;
+
+// What a channel plays on a loop: the collection in its own order, films at their runtime and
+// every aired episode of each show, in order.
+pub fn channel_lineup(source: &UserCollection, today: NaiveDate) -> Vec<ScheduleItem> {
+    order_media(std::slice::from_ref(source), ScheduleOrdering::Ranked)
+        .iter()
+        .flat_map(|m| media_items(m, today, None, usize::MAX))
+        .collect()
+}
+
+fn slot(item: &ScheduleItem, start: DateTime<Utc>) -> ScheduleSlot {
+    ScheduleSlot {
+        id: Uuid::new_v4(),
+        start,
+        media: item.media.clone(),
+        duration: item.duration,
+        episode: item.episode.clone(),
+        offset: 0,
+    }
+}
+
+// Which item of a lineup looping since `epoch` is on at `at`, and when it started.
+fn playing_at(
+    lineup: &[ScheduleItem],
+    epoch: DateTime<Utc>,
+    at: DateTime<Utc>,
+) -> Option<(usize, DateTime<Utc>)> {
+    let cycle: i64 = lineup.iter().map(|i| i.duration as i64 * 60).sum();
+    if cycle == 0 {
+        return None;
+    }
+
+    // Back to the start of the current time round, then along to whatever covers `at`.
+    let since_epoch = (at - epoch).num_seconds().rem_euclid(cycle);
+    let mut start = at - Duration::seconds(since_epoch);
+    for (i, item) in lineup.iter().enumerate() {
+        let end = start + Duration::minutes(item.duration as i64);
+        if at < end {
+            return Some((i, start));
+        }
+        start = end;
+    }
+    None
+}
+
+// What's on at `at` and what follows, for a lineup that has been looping since `epoch`.
+pub fn channel_now(
+    lineup: &[ScheduleItem],
+    epoch: DateTime<Utc>,
+    at: DateTime<Utc>,
+    up_next: usize,
+) -> ChannelNow {
+    let Some((current, start)) = playing_at(lineup, epoch, at) else {
+        return ChannelNow::default();
+    };
+
+    let now_playing = slot(&lineup[current], start);
+    let mut next_start = now_playing.end();
+    let next = lineup
+        .iter()
+        .cycle()
+        .skip(current + 1)
+        .take(up_next)
+        .map(|item| {
+            let next = slot(item, next_start);
+            next_start = next.end();
+            next
+        })
+        .collect();
+
+    ChannelNow {
+        elapsed: (at - start).num_seconds(),
+        now_playing: Some(now_playing),
+        up_next: next,
+    }
+}
+
+// Everything the channel plays from whatever is on at `from` until `until`, for guides.
+pub fn channel_slots(
+    lineup: &[ScheduleItem],
+    epoch: DateTime<Utc>,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<ScheduleSlot> {
+    let Some((current, mut start)) = playing_at(lineup, epoch, from) else {
+        return vec![];
+    };
+
+    lineup
+        .iter()
+        .cycle()
+        .skip(current)
+        .map_while(|item| {
+            (start < until).then(|| {
+                let next = slot(item, start);
+                start = next.end();
+                next
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct ChannelNow {
+    // `None` when the source collection has nothing to play.
+    pub now_playing: Option<ScheduleSlot>,
+    // Seconds into what's playing.
+    pub elapsed: i64,
+    pub up_next: Vec<ScheduleSlot>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NewChannelSchema {
+    pub name: String,
+    pub collection: Uuid,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ChannelGuide {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(flatten)]
+    pub now: ChannelNow,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChannelGuideResponse {
+    pub status: String,
+    pub data: ChannelGuide,
+}
@@ -1,7 +1,11 @@
-use crate::model::core::{Movie, OnlineContent, TvShow, YTChannel};
+use crate::model::core::{DiscoveryMeta, EntryMeta, Movie, OnlineContent, TvShow, YTChannel};
+use crate::model::rules::CollectionRules;
+use crate::model::special::SpecialCollection;
+use crate::model::tags::Tags;
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::mem;
 use uuid::Uuid;
@@ -18,12 +22,24 @@ use uuid::Uuid;
 // "tags": ["Thumbs Up"]
 // },
 
-// #[derive(Clone, Debug, PartialEq, PartialOrd, Deserialize, Serialize)]
-// pub enum ShareType {
-//     Private,
-//     Public,
-//     Friends,
-// }
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Deserialize, Serialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareType {
+    #[default]
+    Private,
+    Public,
+    Friends,
+}
+
+impl Display for ShareType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareType::Private => write!(f, "private"),
+            ShareType::Public => write!(f, "public"),
+            ShareType::Friends => write!(f, "friends"),
+        }
+    }
+}
 
 pub trait IsMedia {
     fn as_media(&self) -> Media;
@@ -37,6 +53,162 @@ pub enum Media {
     OnlineContent(OnlineContent),
 }
 
+impl Media {
+    pub fn name(&self) -> &str {
+        match self {
+            Media::Movie(m) => &m.name,
+            Media::TvShow(t) => &t.name,
+            Media::YTChannel(c) => &c.name,
+            Media::OnlineContent(c) => &c.name,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Media::Movie(_) => "movie",
+            Media::TvShow(_) => "tv",
+            Media::YTChannel(_) => "yt_channel",
+            Media::OnlineContent(_) => "online_content",
+        }
+    }
+
+    pub fn year(&self) -> Option<i32> {
+        match self {
+            Media::Movie(m) => Some(m.year),
+            Media::TvShow(t) => t.first_air_date.get(0..4).and_then(|y| y.parse().ok()),
+            _ => None,
+        }
+    }
+
+    pub fn tmdb_id(&self) -> Option<i64> {
+        match self {
+            Media::Movie(m) => m.details.as_ref().map(|d| d.tmdb_id),
+            Media::TvShow(t) => t.details.as_ref().map(|d| d.tmdb_id),
+            _ => None,
+        }
+    }
+
+    pub fn baing_meta(&self) -> Option<&DiscoveryMeta> {
+        match self {
+            Media::Movie(m) => m.baing_meta.as_ref(),
+            Media::TvShow(t) => t.baing_meta.as_ref(),
+            Media::YTChannel(c) => c.baing_meta.as_ref(),
+            Media::OnlineContent(c) => c.baing_meta.as_ref(),
+        }
+    }
+
+    pub fn entry_meta(&self) -> Option<&EntryMeta> {
+        match self {
+            Media::Movie(m) => m.entry_meta.as_ref(),
+            Media::TvShow(t) => t.entry_meta.as_ref(),
+            Media::YTChannel(c) => c.entry_meta.as_ref(),
+            Media::OnlineContent(c) => c.entry_meta.as_ref(),
+        }
+    }
+
+    fn entry_meta_mut_opt(&mut self) -> Option<&mut EntryMeta> {
+        match self {
+            Media::Movie(m) => m.entry_meta.as_mut(),
+            Media::TvShow(t) => t.entry_meta.as_mut(),
+            Media::YTChannel(c) => c.entry_meta.as_mut(),
+            Media::OnlineContent(c) => c.entry_meta.as_mut(),
+        }
+    }
+
+    // Leaves just `member`'s own take on the title (`None` for the owner's).
+    pub fn personalize(&mut self, member: Option<Uuid>) {
+        if let Some(meta) = self.entry_meta_mut_opt() {
+            meta.personalize(member);
+        }
+    }
+
+    // Leaves nobody's take on the title, for showing it to people outside the collection.
+    pub fn strip_personal(&mut self) {
+        if let Some(meta) = self.entry_meta_mut_opt() {
+            meta.strip_personal();
+        }
+    }
+
+    pub fn entry_meta_mut(&mut self) -> &mut EntryMeta {
+        let meta = match self {
+            Media::Movie(m) => &mut m.entry_meta,
+            Media::TvShow(t) => &mut t.entry_meta,
+            Media::YTChannel(c) => &mut c.entry_meta,
+            Media::OnlineContent(c) => &mut c.entry_meta,
+        };
+
+        meta.get_or_insert_with(EntryMeta::default)
+    }
+
+    pub fn genre_ids(&self) -> &[i64] {
+        match self {
+            Media::Movie(Movie {
+                details: Some(d), ..
+            }) => &d.genre_ids,
+            Media::TvShow(TvShow {
+                details: Some(d), ..
+            }) => &d.genre_ids,
+            _ => &[],
+        }
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        match self {
+            Media::Movie(m) => m.details.as_ref().map(|d| d.original_language.as_str()),
+            Media::TvShow(t) => Some(
+                t.details
+                    .as_ref()
+                    .map_or(t.language.as_str(), |d| d.original_language.as_str()),
+            ),
+            Media::YTChannel(c) => Some(c.language.as_str()),
+            Media::OnlineContent(c) => Some(c.language.as_str()),
+        }
+    }
+
+    pub fn vote_average(&self) -> Option<f64> {
+        match self {
+            Media::Movie(m) => m.details.as_ref().map(|d| d.vote_average),
+            Media::TvShow(t) => t.details.as_ref().map(|d| d.vote_average),
+            _ => None,
+        }
+    }
+
+    pub fn overview(&self) -> Option<&str> {
+        match self {
+            Media::Movie(m) => m.details.as_ref().map(|d| d.overview.as_str()),
+            Media::TvShow(t) => t.details.as_ref().map(|d| d.overview.as_str()),
+            Media::YTChannel(c) => Some(c.description.as_str()),
+            Media::OnlineContent(c) => Some(c.description.as_str()),
+        }
+        .filter(|o| !o.is_empty())
+    }
+
+    // Where to read more about it: TMDB's page for films and shows.
+    pub fn link(&self) -> Option<String> {
+        match self {
+            Media::Movie(_) => self
+                .tmdb_id()
+                .map(|id| format!("https://www.themoviedb.org/movie/{id}")),
+            Media::TvShow(_) => self
+                .tmdb_id()
+                .map(|id| format!("https://www.themoviedb.org/tv/{id}")),
+            Media::YTChannel(c) => {
+                Some(format!("https://www.youtube.com/channel/{}", c.channel_id))
+            }
+            Media::OnlineContent(c) => Some(c.url.clone()),
+        }
+    }
+
+    // The same title can live in several collections with different metadata attached, so
+    // compare on the TMDB id when we have one and fall back to the display string.
+    pub fn identity(&self) -> String {
+        match self.tmdb_id() {
+            Some(id) => format!("{}:{id}", self.kind()),
+            None => format!("{}:{self}", self.kind()),
+        }
+    }
+}
+
 impl IsMedia for Movie {
     fn as_media(&self) -> Media {
         Media::Movie(self.clone())
@@ -105,6 +277,73 @@ pub struct MediaCollection {
     pub entries: Vec<Media>,
 }
 
+impl MediaCollection {
+    // Pulls the entries with these identities out, keeping the rest in order.
+    pub fn take_entries(&mut self, identities: &HashSet<String>) -> Vec<Media> {
+        let (taken, kept) = self
+            .entries
+            .drain(..)
+            .partition(|m| identities.contains(&m.identity()));
+        self.entries = kept;
+        taken
+    }
+
+    // Moves an entry to `position` (0 is the top), clamped to the end. False if it isn't here.
+    pub fn move_entry(&mut self, identity: &str, position: usize) -> bool {
+        let Some(from) = self.entries.iter().position(|m| m.identity() == identity) else {
+            return false;
+        };
+        let media = self.entries.remove(from);
+        let to = position.min(self.entries.len());
+        self.entries.insert(to, media);
+        true
+    }
+
+    // Leaves each entry with just `member`'s own take on it (`None` for the owner's).
+    pub fn personalize(&mut self, member: Option<Uuid>) {
+        self.entries.iter_mut().for_each(|m| m.personalize(member));
+    }
+
+    // Leaves nobody's take on the entries.
+    pub fn strip_personal(&mut self) {
+        self.entries.iter_mut().for_each(Media::strip_personal);
+    }
+
+    // For entries sent back by `member` after `personalize`: their take goes back under their own
+    // key, and everyone else's is kept from `stored`.
+    pub fn keep_personal(&mut self, stored: &MediaCollection, member: Option<Uuid>) {
+        let stored: HashMap<String, &EntryMeta> = stored
+            .entries
+            .iter()
+            .filter_map(|m| Some((m.identity(), m.entry_meta()?)))
+            .collect();
+        for media in &mut self.entries {
+            let before = stored.get(&media.identity()).copied();
+            let meta = media.entry_meta_mut();
+            let mine = mem::take(&mut meta.personal);
+            meta.personal = before.map(|b| b.personal.clone()).unwrap_or_default();
+            meta.members = before.map(|b| b.members.clone()).unwrap_or_default();
+            *meta.personal_mut(member) = mine;
+        }
+    }
+
+    // Whether any of the entries is tagged `tag`.
+    pub fn has_tagged_entries(&self, tag: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|m| m.entry_meta().is_some_and(|meta| meta.tags.contains(tag)))
+    }
+
+    // Appends whatever isn't already in the collection. Returns how many went in.
+    pub fn add_entries(&mut self, entries: Vec<Media>) -> usize {
+        let mut present: HashSet<String> = self.entries.iter().map(|m| m.identity()).collect();
+        let before = self.entries.len();
+        self.entries
+            .extend(entries.into_iter().filter(|m| present.insert(m.identity())));
+        self.entries.len() - before
+    }
+}
+
 impl From<Option<Value>> for MediaCollection {
     fn from(value: Option<Value>) -> Self {
         if let Some(json_value) = value {
@@ -133,8 +372,127 @@ pub struct UserCollection {
     pub sharing: Option<String>,
     pub collection: MediaCollection,
     pub locked: bool,
-    pub tags: Value,
+    #[serde(default)]
+    pub tags: Tags,
     pub special: Option<String>,
+    #[serde(default)]
+    pub rules: CollectionRules,
+    // When set, the order of the entries is a ranking: first is best.
+    #[serde(default)]
+    pub ranked: bool,
+}
+
+impl UserCollection {
+    pub fn is_smart(&self) -> bool {
+        self.rules.is_smart()
+    }
+
+    pub fn special_kind(&self) -> Option<SpecialCollection> {
+        SpecialCollection::from_key(self.special.as_deref())
+    }
+
+    pub fn is_special(&self, special: SpecialCollection) -> bool {
+        self.special_kind() == Some(special)
+    }
+
+    // Where a title sits in a ranked collection, 1 being the top. Used as a priority elsewhere, so
+    // unranked collections have no opinion.
+    pub fn rank_of(&self, media: &Media) -> Option<usize> {
+        if !self.ranked {
+            return None;
+        }
+        let identity = media.identity();
+        self.collection
+            .entries
+            .iter()
+            .position(|m| m.identity() == identity)
+            .map(|i| i + 1)
+    }
+
+    // Tagged `tag` itself, or holding something that is.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag) || self.collection.has_tagged_entries(tag)
+    }
+
+    // The member key for `user_id`'s own take on the entries: `None` when they're the owner.
+    pub fn member_key(&self, user_id: Uuid) -> Option<Uuid> {
+        (user_id != self.owner_id).then_some(user_id)
+    }
+
+    // The collection as `user_id`, the owner or a member, should see it.
+    pub fn personalized_for(mut self, user_id: Uuid) -> Self {
+        let member = self.member_key(user_id);
+        self.collection.personalize(member);
+        self
+    }
+
+    pub fn share_type(&self) -> ShareType {
+        match self.sharing.as_deref() {
+            Some("public") => ShareType::Public,
+            Some("friends") => ShareType::Friends,
+            _ => ShareType::Private,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UpdateSharingSchema {
+    pub sharing: ShareType,
+}
+
+// `entry` is the `Media::identity()` of the title being updated. `added_at` is ignored.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UpdateEntryMetaSchema {
+    pub entry: String,
+    pub meta: EntryMeta,
+}
+
+// `entry` is a `Media::identity()`, `position` where it should end up, counting from 0.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MoveEntrySchema {
+    pub entry: String,
+    pub position: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UpdateRankedSchema {
+    pub ranked: bool,
+}
+
+// What to do with a selection of entries. Moves and copies skip titles the target already has.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkEntriesAction {
+    Move {
+        to: Uuid,
+    },
+    Copy {
+        to: Uuid,
+    },
+    Delete,
+    Tag {
+        #[serde(default)]
+        add: Tags,
+        #[serde(default)]
+        remove: Tags,
+    },
+    // `None` clears the rating.
+    Rate {
+        rating: Option<u8>,
+    },
+}
+
+// `entries` are `Media::identity()`s in the collection the request is made against.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BulkEntriesSchema {
+    pub entries: Vec<String>,
+    pub action: BulkEntriesAction,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NewSmartCollection {
+    pub name: String,
+    pub rules: CollectionRules,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -156,12 +514,12 @@ pub struct UserCollectionPatchResponse {
 
 pub fn extract_special_collection_to_entries(
     special_collection: &[UserCollection],
-    special_name: &str,
+    special: SpecialCollection,
     media_type: &Media,
 ) -> String {
     special_collection
         .iter()
-        .filter(|uc| uc.special.as_ref().is_some_and(|s| s == special_name))
+        .filter(|uc| uc.is_special(special))
         .map(|uc| {
             uc.collection
                 .entries
@@ -174,3 +532,21 @@ pub fn extract_special_collection_to_entries(
         .collect::<Vec<String>>()
         .join(" ")
 }
+
+// Everything the user has rated, watched or made notes on, across all their collections.
+// ".. Alien (1979) [rated 9/10, watched 2 times], Heat (1995) [currently watching] .."
+pub fn extract_rated_entries(collections: &[UserCollection], media_type: &Media) -> String {
+    let mut seen = HashSet::new();
+
+    collections
+        .iter()
+        .flat_map(|uc| uc.collection.entries.iter())
+        .filter(|media| mem::discriminant(*media) == mem::discriminant(media_type))
+        .filter_map(|media| {
+            let summary = media.entry_meta()?.taste_summary()?;
+            seen.insert(media.identity())
+                .then(|| format!("{media} [{summary}]"))
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
@@ -1,7 +1,11 @@
+use crate::model::tags::Tags;
 use crate::model::tmdb::{MovieSearchResult, TvSearchResult};
+use chrono::prelude::*;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct DiscoveryMeta {
@@ -10,12 +14,125 @@ pub struct DiscoveryMeta {
     pub streamers: String,
 }
 
+// Bookkeeping for a title once it has landed in one of the user's collections. Never part of
+// what we ask the AI for, so it's skipped in the schema.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct EntryMeta {
+    pub added_at: Option<DateTime<Utc>>,
+    // Who put it in the collection, which matters once others can edit it too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_by: Option<Uuid>,
+    // The owner's own take, stored inline as it was before collections had members.
+    #[serde(flatten)]
+    pub personal: PersonalMeta,
+    #[serde(default, skip_serializing_if = "Tags::is_empty")]
+    pub tags: Tags,
+    // Every other member's take, by user id. Only ever sent to the member it belongs to.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub members: HashMap<Uuid, PersonalMeta>,
+}
+
+// What one person makes of a title: whether they've seen it and what they thought. Each member of
+// a shared collection has their own.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PersonalMeta {
+    #[serde(default)]
+    pub watch_status: WatchStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watched_dates: Vec<NaiveDate>,
+    // 1-10, anything else is rejected by the API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub rewatch_count: u32,
+}
+
+impl EntryMeta {
+    pub const RATING_RANGE: std::ops::RangeInclusive<u8> = 1..=10;
+
+    // `member` is a member's user id, `None` for the owner.
+    pub fn personal_of(&self, member: Option<Uuid>) -> PersonalMeta {
+        match member {
+            Some(user_id) => self.members.get(&user_id).cloned().unwrap_or_default(),
+            None => self.personal.clone(),
+        }
+    }
+
+    pub fn personal_mut(&mut self, member: Option<Uuid>) -> &mut PersonalMeta {
+        match member {
+            Some(user_id) => self.members.entry(user_id).or_default(),
+            None => &mut self.personal,
+        }
+    }
+
+    // Leaves just what `member` should see: their own take up top, and nobody else's.
+    pub fn personalize(&mut self, member: Option<Uuid>) {
+        self.personal = self.personal_of(member);
+        self.members.clear();
+    }
+
+    pub fn strip_personal(&mut self) {
+        self.personal = PersonalMeta::default();
+        self.members.clear();
+    }
+
+    // The user's own take on a title, for the AI prompts, e.g. "rated 8/10, watched 3 times".
+    pub fn taste_summary(&self) -> Option<String> {
+        let personal = &self.personal;
+        let mut parts = vec![];
+
+        if let Some(rating) = personal.rating {
+            parts.push(format!("rated {rating}/10"));
+        }
+        match personal.watch_status {
+            WatchStatus::Watched if personal.rewatch_count > 0 => {
+                parts.push(format!("watched {} times", personal.rewatch_count + 1))
+            }
+            WatchStatus::Watched => parts.push("watched".to_string()),
+            WatchStatus::InProgress => parts.push("currently watching".to_string()),
+            WatchStatus::Unwatched => {}
+        }
+        if let Some(notes) = personal.notes.as_ref().filter(|n| !n.trim().is_empty()) {
+            parts.push(format!("notes: \"{}\"", notes.trim()));
+        }
+
+        match parts.is_empty() {
+            true => None,
+            false => Some(parts.join(", ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchStatus {
+    #[default]
+    Unwatched,
+    InProgress,
+    Watched,
+}
+
+impl Display for WatchStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchStatus::Unwatched => write!(f, "unwatched"),
+            WatchStatus::InProgress => write!(f, "in_progress"),
+            WatchStatus::Watched => write!(f, "watched"),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct Movie {
     pub name: String,
     pub year: i32,
     pub details: Option<MovieDetails>,
     pub baing_meta: Option<DiscoveryMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub entry_meta: Option<EntryMeta>,
 }
 
 impl Default for Movie {
@@ -25,6 +142,7 @@ impl Default for Movie {
             year: 0,
             details: None,
             baing_meta: None,
+            entry_meta: None,
         }
     }
 }
@@ -43,6 +161,10 @@ pub struct MovieDetails {
     pub title: String,
     pub vote_average: f64,
     pub vote_count: i64,
+    // Minutes, from TMDB's movie details. Search results don't carry it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub runtime: Option<u32>,
 }
 
 // Basic copy of the results value, because I prefer these somewhat uncoupled in the long run.
@@ -61,6 +183,7 @@ impl From<MovieSearchResult> for MovieDetails {
             title: value.title,
             vote_average: value.vote_average,
             vote_count: value.vote_count,
+            runtime: None,
         }
     }
 }
@@ -72,6 +195,9 @@ pub struct TvShow {
     pub language: String,
     pub details: Option<TvShowDetails>,
     pub baing_meta: Option<DiscoveryMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub entry_meta: Option<EntryMeta>,
 }
 
 impl Default for TvShow {
@@ -82,6 +208,7 @@ impl Default for TvShow {
             language: "".to_string(),
             details: None,
             baing_meta: None,
+            entry_meta: None,
         }
     }
 }
@@ -102,6 +229,40 @@ pub struct TvShowDetails {
     pub name: String,
     pub vote_average: f64,
     pub vote_count: i64,
+    // From TMDB's season details, specials left out. Search results don't carry them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub seasons: Option<Vec<TvSeason>>,
+    // When `seasons` was last looked up, so shows still airing get their new episodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub seasons_fetched: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TvSeason {
+    pub season_number: u32,
+    pub episodes: Vec<TvEpisode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TvEpisode {
+    pub episode_number: u32,
+    pub name: String,
+    // Minutes. Missing for episodes TMDB hasn't got a runtime for yet.
+    pub runtime: Option<u32>,
+    // "2008-01-20", missing until it's announced.
+    pub air_date: Option<String>,
+}
+
+impl TvShowDetails {
+    // Every episode in order, with its season number.
+    pub fn episodes(&self) -> impl Iterator<Item = (u32, &TvEpisode)> {
+        self.seasons
+            .iter()
+            .flatten()
+            .flat_map(|s| s.episodes.iter().map(move |e| (s.season_number, e)))
+    }
 }
 
 // Basic copy of the results value, because I prefer these somewhat uncoupled in the long run.
@@ -122,6 +283,8 @@ impl From<TvSearchResult> for TvShowDetails {
             name: value.name,
             vote_average: value.vote_average,
             vote_count: value.vote_count,
+            seasons: None,
+            seasons_fetched: None,
         }
     }
 }
@@ -134,6 +297,9 @@ pub struct YTChannel {
     pub language: String,
     pub details: Option<YTChannelDetails>,
     pub baing_meta: Option<DiscoveryMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub entry_meta: Option<EntryMeta>,
 }
 
 impl Default for YTChannel {
@@ -145,6 +311,7 @@ impl Default for YTChannel {
             language: "".to_string(),
             details: None,
             baing_meta: None,
+            entry_meta: None,
         }
     }
 }
@@ -169,6 +336,9 @@ pub struct OnlineContent {
     //pub details: Option<OnlineContentDetails>,
     //pub content_type: OnlineContentType,
     pub baing_meta: Option<DiscoveryMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub entry_meta: Option<EntryMeta>,
 }
 
 impl Default for OnlineContent {
@@ -183,6 +353,7 @@ impl Default for OnlineContent {
             //details: None,
             //content_type: OnlineContentType::Other,
             baing_meta: None,
+            entry_meta: None,
         }
     }
 }
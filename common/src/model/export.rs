@@ -0,0 +1,89 @@
+use crate::model::collections::{Media, UserCollection};
+use crate::model::core::WatchStatus;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Letterboxd,
+}
+
+// One flattened row per collection entry, shared by every export format.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ExportEntry {
+    pub collection: String,
+    pub special: Option<String>,
+    pub media_type: String,
+    pub title: String,
+    pub name: String,
+    pub year: Option<i32>,
+    pub tmdb_id: Option<i64>,
+    pub reason: Option<String>,
+    pub query: Option<String>,
+    pub streamers: Option<String>,
+    // The exporting user's own take on the title; collections should be personalized for them.
+    pub watch_status: WatchStatus,
+    pub watched_dates: Vec<NaiveDate>,
+    pub rating: Option<u8>,
+    pub notes: Option<String>,
+    pub rewatch_count: u32,
+}
+
+impl ExportEntry {
+    // The latest time it was watched, if ever.
+    pub fn last_watched(&self) -> Option<NaiveDate> {
+        self.watched_dates.iter().max().copied()
+    }
+
+    pub fn new(collection: &UserCollection, media: &Media) -> Self {
+        let meta = media.baing_meta();
+        let personal = media
+            .entry_meta()
+            .map(|m| m.personal.clone())
+            .unwrap_or_default();
+        ExportEntry {
+            collection: collection.name.to_owned(),
+            special: collection.special.to_owned(),
+            media_type: media.kind().to_string(),
+            title: media.to_string(),
+            name: media.name().to_string(),
+            year: media.year(),
+            tmdb_id: media.tmdb_id(),
+            reason: meta.map(|m| m.reason.to_owned()),
+            query: meta.map(|m| m.query.to_owned()),
+            streamers: meta.map(|m| m.streamers.to_owned()),
+            watch_status: personal.watch_status,
+            watched_dates: personal.watched_dates,
+            rating: personal.rating,
+            notes: personal.notes,
+            rewatch_count: personal.rewatch_count,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CollectionExport {
+    pub exported_at: DateTime<Utc>,
+    pub entries: Vec<ExportEntry>,
+}
+
+impl From<&[UserCollection]> for CollectionExport {
+    fn from(collections: &[UserCollection]) -> Self {
+        CollectionExport {
+            exported_at: Utc::now(),
+            entries: collections
+                .iter()
+                .flat_map(|uc| {
+                    uc.collection
+                        .entries
+                        .iter()
+                        .map(move |media| ExportEntry::new(uc, media))
+                })
+                .collect(),
+        }
+    }
+}
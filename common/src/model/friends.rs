@@ -0,0 +1,85 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FriendshipStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Blocked,
+}
+
+impl Display for FriendshipStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FriendshipStatus::Pending => write!(f, "pending"),
+            FriendshipStatus::Accepted => write!(f, "accepted"),
+            FriendshipStatus::Declined => write!(f, "declined"),
+            FriendshipStatus::Blocked => write!(f, "blocked"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Friendship {
+    pub id: Uuid,
+    pub requester_id: Uuid,
+    pub addressee_id: Uuid,
+    pub status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Friendship {
+    pub fn friendship_status(&self) -> FriendshipStatus {
+        match self.status.as_deref() {
+            Some("accepted") => FriendshipStatus::Accepted,
+            Some("declined") => FriendshipStatus::Declined,
+            Some("blocked") => FriendshipStatus::Blocked,
+            _ => FriendshipStatus::Pending,
+        }
+    }
+
+    pub fn other(&self, user_id: Uuid) -> Uuid {
+        match self.requester_id == user_id {
+            true => self.addressee_id,
+            false => self.requester_id,
+        }
+    }
+}
+
+// A friendship as seen from one side, with enough of the other user to show in a list.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Friend {
+    pub friendship_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub status: Option<String>,
+    pub incoming: Option<bool>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FriendRequestSchema {
+    pub email: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FriendsData {
+    pub friends: Vec<Friend>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FriendsResponse {
+    pub status: String,
+    pub data: FriendsData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FriendshipResponse {
+    pub status: String,
+    pub data: Friendship,
+}
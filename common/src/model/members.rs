@@ -0,0 +1,86 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+// What someone may do with a collection. Each role can do everything the ones before it can, so
+// they compare: `role >= CollectionRole::Editor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CollectionRole {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl CollectionRole {
+    pub fn from_name(name: Option<&str>) -> Option<CollectionRole> {
+        match name {
+            Some("viewer") => Some(CollectionRole::Viewer),
+            Some("editor") => Some(CollectionRole::Editor),
+            Some("owner") => Some(CollectionRole::Owner),
+            _ => None,
+        }
+    }
+
+    pub fn can_edit(&self) -> bool {
+        *self >= CollectionRole::Editor
+    }
+}
+
+impl Display for CollectionRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollectionRole::Viewer => write!(f, "viewer"),
+            CollectionRole::Editor => write!(f, "editor"),
+            CollectionRole::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+// Someone with access to a collection. The owner is listed too, with the "owner" role.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CollectionMember {
+    pub collection_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub role: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl CollectionMember {
+    pub fn member_role(&self) -> CollectionRole {
+        CollectionRole::from_name(self.role.as_deref()).unwrap_or(CollectionRole::Viewer)
+    }
+}
+
+// Who added an entry, by name, falling back to "someone" for people no longer on the collection.
+pub fn added_by_name(members: &[CollectionMember], user_id: Uuid) -> String {
+    members
+        .iter()
+        .find(|m| m.user_id == user_id)
+        .map(|m| m.name.to_owned())
+        .unwrap_or_else(|| "someone".to_string())
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct InviteMemberSchema {
+    pub email: String,
+    pub role: CollectionRole,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UpdateMemberSchema {
+    pub role: CollectionRole,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MembersData {
+    pub members: Vec<CollectionMember>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MembersResponse {
+    pub status: String,
+    pub data: MembersData,
+}
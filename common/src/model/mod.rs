@@ -1,5 +1,23 @@
+pub mod channel;
 pub mod collections;
 pub mod core;
 pub mod discovery;
+pub mod export;
+pub mod friends;
+pub mod members;
+pub mod playlist;
+pub mod progress;
+pub mod revisions;
+pub mod rules;
+pub mod schedule_generator;
+pub mod schedule_recurrence;
+pub mod schedule_reflow;
+pub mod schedule_themes;
+pub mod schedules;
+pub mod search;
+pub mod special;
+pub mod starter_packs;
+pub mod stats;
+pub mod tags;
 pub mod tmdb;
 pub mod user;
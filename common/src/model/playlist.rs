@@ -0,0 +1,98 @@
+use crate::model::collections::Media;
+use crate::model::schedules::EpisodeInfo;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+// Everything but the unreserved characters gets percent-encoded (RFC 3986 2.3).
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+// Where players should go for each entry when a schedule is exported as a playlist, say the
+// item on a local media server. Both the template and the per-entry URLs can use `{name}`,
+// `{title}`, `{year}`, `{tmdb_id}`, `{season}` and `{episode}`; whatever isn't known is left empty.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct StreamUrls {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    // Keyed by `Media::identity()`, and used instead of the template.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub entries: BTreeMap<String, String>,
+}
+
+impl StreamUrls {
+    fn fill(pattern: &str, media: &Media, episode: Option<&EpisodeInfo>) -> String {
+        let number = |n: Option<String>| n.unwrap_or_default();
+        pattern
+            .replace("{name}", &url_encode(media.name()))
+            .replace("{title}", &url_encode(&media.to_string()))
+            .replace("{year}", &number(media.year().map(|y| y.to_string())))
+            .replace(
+                "{tmdb_id}",
+                &number(media.tmdb_id().map(|id| id.to_string())),
+            )
+            .replace("{season}", &number(episode.map(|e| e.season.to_string())))
+            .replace("{episode}", &number(episode.map(|e| e.episode.to_string())))
+    }
+
+    // The entry's own URL, then the template, then wherever the media itself points.
+    pub fn url_for(&self, media: &Media, episode: Option<&EpisodeInfo>) -> Option<String> {
+        self.entries
+            .get(&media.identity())
+            .or(self.template.as_ref())
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| Self::fill(p.trim(), media, episode))
+            .or_else(|| media.link())
+    }
+
+    // Drops blank URLs so clearing a field removes it.
+    pub fn tidy(&mut self) {
+        self.template = self
+            .template
+            .take()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+        self.entries.retain(|_, url| !url.trim().is_empty());
+        for url in self.entries.values_mut() {
+            *url = url.trim().to_string();
+        }
+    }
+}
+
+impl From<Option<Value>> for StreamUrls {
+    fn from(value: Option<Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+// One thing the schedule plays, as the stream URL settings show it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StreamEntry {
+    pub key: String,
+    pub title: String,
+    // What the playlist will point at for it, if anything.
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StreamUrlsData {
+    pub urls: StreamUrls,
+    pub entries: Vec<StreamEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StreamUrlsResponse {
+    pub status: String,
+    pub data: StreamUrlsData,
+}
@@ -0,0 +1,253 @@
+use crate::model::core::{TvEpisode, TvShow, TvShowDetails};
+use crate::model::schedules::EpisodeInfo;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+// Season then episode, so they sort in viewing order.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EpisodeNumber {
+    pub season: u32,
+    pub episode: u32,
+}
+
+impl EpisodeNumber {
+    pub fn first() -> Self {
+        EpisodeNumber {
+            season: 1,
+            episode: 1,
+        }
+    }
+
+    // The next one in the same season. Only a guess at what comes next without TMDB's list.
+    pub fn following(self) -> Self {
+        EpisodeNumber {
+            season: self.season,
+            episode: self.episode + 1,
+        }
+    }
+}
+
+impl Display for EpisodeNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "S{:02}E{:02}", self.season, self.episode)
+    }
+}
+
+// What's stored in `series_progress.watched`, always sorted with no repeats.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct WatchedEpisodes {
+    pub episodes: Vec<EpisodeNumber>,
+}
+
+impl WatchedEpisodes {
+    pub fn contains(&self, episode: EpisodeNumber) -> bool {
+        self.episodes.binary_search(&episode).is_ok()
+    }
+
+    // The furthest one in, not necessarily the most recently marked.
+    pub fn last(&self) -> Option<EpisodeNumber> {
+        self.episodes.last().copied()
+    }
+
+    pub fn mark(&mut self, episodes: impl IntoIterator<Item = EpisodeNumber>, watched: bool) {
+        if watched {
+            self.episodes.extend(episodes);
+            self.episodes.sort();
+            self.episodes.dedup();
+        } else {
+            let mut unwatched: Vec<EpisodeNumber> = episodes.into_iter().collect();
+            unwatched.sort();
+            self.episodes
+                .retain(|e| unwatched.binary_search(e).is_err());
+        }
+    }
+}
+
+impl From<Option<Value>> for WatchedEpisodes {
+    fn from(value: Option<Value>) -> Self {
+        let mut watched: WatchedEpisodes = value
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        watched.episodes.sort();
+        watched.episodes.dedup();
+        watched
+    }
+}
+
+impl From<Option<Value>> for TvShow {
+    fn from(value: Option<Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+// The episodes after the furthest one watched, in order.
+pub fn remaining_episodes<'a>(
+    details: &'a TvShowDetails,
+    watched: &WatchedEpisodes,
+) -> impl Iterator<Item = (u32, &'a TvEpisode)> {
+    let last = watched.last();
+    details.episodes().filter(move |(season, e)| {
+        let number = EpisodeNumber {
+            season: *season,
+            episode: e.episode_number,
+        };
+        last.is_none_or(|last| number > last)
+    })
+}
+
+// The episode to watch next: the first one after the furthest watched, or the one after it when we
+// don't have the episode list. `None` once the whole show is watched.
+pub fn next_up(show: &TvShow, watched: &WatchedEpisodes) -> Option<EpisodeInfo> {
+    match show.details.as_ref().filter(|d| d.seasons.is_some()) {
+        Some(details) => remaining_episodes(details, watched)
+            .next()
+            .map(|(season, e)| EpisodeInfo {
+                season,
+                episode: e.episode_number,
+                name: Some(e.name.clone()).filter(|n| !n.is_empty()),
+            }),
+        None => {
+            let next = watched
+                .last()
+                .map_or(EpisodeNumber::first(), EpisodeNumber::following);
+            Some(EpisodeInfo {
+                season: next.season,
+                episode: next.episode,
+                name: None,
+            })
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SeriesProgress {
+    pub user_id: Uuid,
+    pub show_key: String,
+    pub show: TvShow,
+    pub watched: WatchedEpisodes,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl SeriesProgress {
+    pub fn next_up(&self) -> Option<EpisodeInfo> {
+        next_up(&self.show, &self.watched)
+    }
+}
+
+// The longest range that can be marked without the show's episode list to check it against.
+pub const MAX_UNLISTED_RANGE: u32 = 500;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "mark", rename_all = "snake_case")]
+pub enum WatchedMark {
+    Episode {
+        season: u32,
+        episode: u32,
+    },
+    Season {
+        season: u32,
+    },
+    // Both ends included.
+    Range {
+        from: EpisodeNumber,
+        to: EpisodeNumber,
+    },
+}
+
+impl WatchedMark {
+    // The episodes the mark covers. Whole seasons and ranges across seasons need the show's
+    // episode list from TMDB.
+    pub fn episodes(&self, show: &TvShow) -> Result<Vec<EpisodeNumber>, &'static str> {
+        let details = show.details.as_ref().filter(|d| d.seasons.is_some());
+        match (*self, details) {
+            (WatchedMark::Episode { season, episode }, _) => {
+                Ok(vec![EpisodeNumber { season, episode }])
+            }
+            (WatchedMark::Range { from, to }, _) if from > to => Err("The range must run forwards"),
+            (WatchedMark::Range { from, to }, None)
+                if from.season == to.season && to.episode - from.episode >= MAX_UNLISTED_RANGE =>
+            {
+                Err("Too many episodes to mark at once; look up the show's episodes first")
+            }
+            (WatchedMark::Range { from, to }, None) if from.season == to.season => {
+                Ok((from.episode..=to.episode)
+                    .map(|episode| EpisodeNumber {
+                        season: from.season,
+                        episode,
+                    })
+                    .collect())
+            }
+            (_, None) => Err("Look up the show's episodes first"),
+            (WatchedMark::Season { season }, Some(details)) => {
+                match listed_episodes(details, |n| n.season == season) {
+                    episodes if episodes.is_empty() => Err("No such season"),
+                    episodes => Ok(episodes),
+                }
+            }
+            (WatchedMark::Range { from, to }, Some(details)) => {
+                Ok(listed_episodes(details, |n| from <= n && n <= to))
+            }
+        }
+    }
+}
+
+fn listed_episodes(
+    details: &TvShowDetails,
+    keep: impl Fn(EpisodeNumber) -> bool,
+) -> Vec<EpisodeNumber> {
+    details
+        .episodes()
+        .map(|(season, e)| EpisodeNumber {
+            season,
+            episode: e.episode_number,
+        })
+        .filter(|n| keep(*n))
+        .collect()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MarkWatchedSchema {
+    pub show: TvShow,
+    pub mark: WatchedMark,
+    // Takes the mark off instead.
+    #[serde(default)]
+    pub unwatch: bool,
+}
+
+// A show's progress with what's next, worked out for the client.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ProgressSummary {
+    pub progress: SeriesProgress,
+    pub next_up: Option<EpisodeInfo>,
+}
+
+impl From<SeriesProgress> for ProgressSummary {
+    fn from(progress: SeriesProgress) -> Self {
+        ProgressSummary {
+            next_up: progress.next_up(),
+            progress,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ProgressData {
+    pub progress: Vec<ProgressSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProgressListResponse {
+    pub status: String,
+    pub data: ProgressData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProgressResponse {
+    pub status: String,
+    pub data: ProgressSummary,
+}
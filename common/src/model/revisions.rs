@@ -0,0 +1,99 @@
+use crate::model::collections::{Media, MediaCollection};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+// One change to a collection's entries. `added` and `removed` are the diff against the revision
+// before it, `snapshot` is the whole collection as it was left. Only the entries are kept: the
+// collection's own tags, sharing, rules and ranking aren't, so restoring leaves those alone.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CollectionRevision {
+    pub id: Uuid,
+    pub collection_id: Uuid,
+    pub actor_id: Uuid,
+    pub actor_name: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub added: MediaCollection,
+    pub removed: MediaCollection,
+    pub snapshot: MediaCollection,
+}
+
+impl CollectionRevision {
+    // "+2 / -1"
+    pub fn summary(&self) -> String {
+        format!(
+            "+{} / -{}",
+            self.added.entries.len(),
+            self.removed.entries.len()
+        )
+    }
+
+    // The revision as `member` (`None` for the owner) should see it, with only their own take on
+    // the entries.
+    pub fn personalized(mut self, member: Option<Uuid>) -> Self {
+        self.added.personalize(member);
+        self.removed.personalize(member);
+        self.snapshot.personalize(member);
+        self
+    }
+
+    // Whether undo has anything to take back. Revisions that only edited entries in place don't.
+    pub fn adds_or_removes(&self) -> bool {
+        !self.added.entries.is_empty() || !self.removed.entries.is_empty()
+    }
+
+    // Takes this revision's changes back out of `current`, leaving anything done since alone.
+    pub fn revert(&self, current: &MediaCollection) -> MediaCollection {
+        let added: HashSet<String> = self.added.entries.iter().map(|m| m.identity()).collect();
+        let mut entries: Vec<Media> = current
+            .entries
+            .iter()
+            .filter(|m| !added.contains(&m.identity()))
+            .cloned()
+            .collect();
+
+        let present: HashSet<String> = entries.iter().map(|m| m.identity()).collect();
+        entries.extend(
+            self.removed
+                .entries
+                .iter()
+                .filter(|m| !present.contains(&m.identity()))
+                .cloned(),
+        );
+
+        MediaCollection { entries }
+    }
+}
+
+// What went in and what came out between two versions of a collection, by `Media::identity()`.
+pub fn diff_entries(before: &MediaCollection, after: &MediaCollection) -> (Vec<Media>, Vec<Media>) {
+    let before_ids: HashSet<String> = before.entries.iter().map(|m| m.identity()).collect();
+    let after_ids: HashSet<String> = after.entries.iter().map(|m| m.identity()).collect();
+
+    let added = after
+        .entries
+        .iter()
+        .filter(|m| !before_ids.contains(&m.identity()))
+        .cloned()
+        .collect();
+    let removed = before
+        .entries
+        .iter()
+        .filter(|m| !after_ids.contains(&m.identity()))
+        .cloned()
+        .collect();
+
+    (added, removed)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RevisionsData {
+    pub revisions: Vec<CollectionRevision>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevisionsResponse {
+    pub status: String,
+    pub data: RevisionsData,
+}
@@ -0,0 +1,198 @@
+use crate::model::collections::{Media, UserCollection};
+use crate::model::special::SpecialCollection;
+use crate::model::tags::Tags;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::mem;
+use uuid::Uuid;
+
+// {
+//   "rules": [
+//     { "rule": "media_type", "kinds": ["movie"] },
+//     { "rule": "in_collection", "collection": { "special": "thumbsup" } },
+//     { "rule": "year_range", "from": 1980, "to": 1989 },
+//     { "rule": "not", "of": { "rule": "in_collection", "collection": { "special": "skipped" } } }
+//   ]
+// }
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionRef {
+    Id(Uuid),
+    Special(SpecialCollection),
+}
+
+impl CollectionRef {
+    fn matches(&self, uc: &UserCollection) -> bool {
+        match self {
+            CollectionRef::Id(id) => &uc.id == id,
+            CollectionRef::Special(special) => uc.is_special(*special),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum CollectionRule {
+    MediaType {
+        kinds: Vec<String>,
+    },
+    Genres {
+        genre_ids: Vec<i64>,
+    },
+    YearRange {
+        from: Option<i32>,
+        to: Option<i32>,
+    },
+    Language {
+        languages: Vec<String>,
+    },
+    VoteAverage {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    Tags {
+        tags: Vec<String>,
+    },
+    InCollection {
+        collection: CollectionRef,
+    },
+    AddedBetween {
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    },
+    Not {
+        of: Box<CollectionRule>,
+    },
+}
+
+fn in_range<T: PartialOrd>(value: Option<T>, from: &Option<T>, to: &Option<T>) -> bool {
+    match value {
+        Some(v) => from.as_ref().is_none_or(|f| &v >= f) && to.as_ref().is_none_or(|t| &v <= t),
+        None => from.is_none() && to.is_none(),
+    }
+}
+
+impl CollectionRule {
+    // Applies `edit` to the tags this rule, or the one it wraps, looks for. Returns whether
+    // anything changed.
+    fn edit_tags<F: Fn(&mut Tags) -> bool>(&mut self, edit: &F) -> bool {
+        match self {
+            CollectionRule::Tags { tags } => {
+                let mut edited = Tags::from(mem::take(tags));
+                let changed = edit(&mut edited);
+                *tags = edited.into();
+                changed
+            }
+            CollectionRule::Not { of } => of.edit_tags(edit),
+            _ => false,
+        }
+    }
+
+    // `containing` is every (non-smart) collection the entry was found in.
+    fn matches(&self, media: &Media, containing: &[&UserCollection]) -> bool {
+        match self {
+            CollectionRule::MediaType { kinds } => kinds.iter().any(|k| k == media.kind()),
+            CollectionRule::Genres { genre_ids } => {
+                media.genre_ids().iter().any(|g| genre_ids.contains(g))
+            }
+            CollectionRule::YearRange { from, to } => in_range(media.year(), from, to),
+            CollectionRule::Language { languages } => media.language().is_some_and(|lang| {
+                let lang = lang.to_lowercase();
+                languages
+                    .iter()
+                    .any(|l| lang.starts_with(&l.to_lowercase()))
+            }),
+            CollectionRule::VoteAverage { min, max } => in_range(media.vote_average(), min, max),
+            CollectionRule::Tags { tags } => {
+                let entry_tags = media.entry_meta().map(|m| &m.tags);
+                tags.iter().any(|t| {
+                    entry_tags.is_some_and(|et| et.contains(t))
+                        || containing.iter().any(|uc| uc.tags.contains(t))
+                })
+            }
+            CollectionRule::InCollection { collection } => {
+                containing.iter().any(|uc| collection.matches(uc))
+            }
+            CollectionRule::AddedBetween { from, to } => {
+                in_range(media.entry_meta().and_then(|m| m.added_at), from, to)
+            }
+            CollectionRule::Not { of } => !of.matches(media, containing),
+        }
+    }
+}
+
+// Rules are all AND'ed together; use `Not` to exclude. No rules means a plain, stored collection.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct CollectionRules {
+    pub rules: Vec<CollectionRule>,
+}
+
+impl From<Option<Value>> for CollectionRules {
+    fn from(value: Option<Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+impl CollectionRules {
+    pub fn is_smart(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    // For renaming or deleting a tag everywhere, so rules keep up.
+    pub fn edit_tags<F: Fn(&mut Tags) -> bool>(&mut self, edit: &F) -> bool {
+        let mut changed = false;
+        for rule in self.rules.iter_mut() {
+            changed |= rule.edit_tags(edit);
+        }
+        changed
+    }
+
+    pub fn evaluate(&self, sources: &[UserCollection]) -> Vec<Media> {
+        // Every distinct entry, first sighting first, with the collections it's in.
+        let mut order: Vec<(String, &Media)> = vec![];
+        let mut containing: HashMap<String, Vec<&UserCollection>> = HashMap::new();
+        for uc in sources.iter().filter(|uc| !uc.is_smart()) {
+            for media in &uc.collection.entries {
+                let identity = media.identity();
+                let cols = containing.entry(identity.clone()).or_default();
+                if cols.is_empty() {
+                    order.push((identity, media));
+                }
+                // An entry listed twice in one collection still counts it once.
+                if !cols.last().is_some_and(|c| std::ptr::eq(*c, uc)) {
+                    cols.push(uc);
+                }
+            }
+        }
+
+        order
+            .into_iter()
+            .filter(|(identity, media)| {
+                let cols = &containing[identity];
+                self.rules.iter().all(|r| r.matches(media, cols))
+            })
+            .map(|(_, media)| media.clone())
+            .collect()
+    }
+}
+
+// Fills in the entries of every smart collection from the rest of the user's collections.
+pub fn resolve_smart_collections(mut collections: Vec<UserCollection>) -> Vec<UserCollection> {
+    let resolved: Vec<(usize, Vec<Media>)> = collections
+        .iter()
+        .enumerate()
+        .filter(|(_, uc)| uc.is_smart())
+        .map(|(i, uc)| (i, uc.rules.evaluate(&collections)))
+        .collect();
+
+    for (i, entries) in resolved {
+        collections[i].collection.entries = entries;
+    }
+
+    collections
+}
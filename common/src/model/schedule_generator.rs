@@ -0,0 +1,342 @@
+use crate::model::collections::{Media, UserCollection};
+use crate::model::core::{TvEpisode, TvShow, TvShowDetails};
+use crate::model::progress::{remaining_episodes, EpisodeNumber, WatchedEpisodes};
+use crate::model::schedules::{EpisodeInfo, Schedule, ScheduleSlot, ScheduleSlots, TimeWindow};
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use uuid::Uuid;
+
+// Until TMDB tells us better, a film is two hours and an episode 45 minutes. Shows get their next
+// few episodes.
+pub const DEFAULT_MOVIE_RUNTIME: u32 = 120;
+pub const DEFAULT_EPISODE_RUNTIME: u32 = 45;
+pub const DEFAULT_EPISODES_PER_SHOW: u32 = 3;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleOrdering {
+    // Best first in ranked collections, the rest in collection order.
+    #[default]
+    Ranked,
+    Random,
+    // Oldest release first.
+    Chronological,
+    // One from each genre in turn, so a week isn't all horror.
+    GenreRotating,
+}
+
+// When the user watches on the given days, in their local time. An `end` at or before `start`
+// runs past midnight: 14:00 to 00:00 is the rest of the day.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ViewingWindow {
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GenerateScheduleSchema {
+    pub name: String,
+    pub collections: Vec<Uuid>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub windows: Vec<ViewingWindow>,
+    #[serde(default)]
+    pub ordering: ScheduleOrdering,
+    // The IANA zone the windows are in, as the browser sees it, so they keep their local times
+    // across daylight saving changes.
+    #[serde(default)]
+    pub time_zone: Tz,
+    // Minutes left free between slots.
+    #[serde(default)]
+    pub gap: u32,
+}
+
+// Something to put on the schedule: a film, or one episode of a show.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ScheduleItem {
+    pub media: Media,
+    pub duration: u32,
+    pub episode: Option<EpisodeInfo>,
+}
+
+impl ScheduleItem {
+    pub fn title(&self) -> String {
+        match &self.episode {
+            Some(episode) => format!("{} {episode}", self.media),
+            None => self.media.to_string(),
+        }
+    }
+}
+
+// The films and shows from `collections`, each once, in the order they should be watched.
+pub fn order_media(collections: &[UserCollection], ordering: ScheduleOrdering) -> Vec<Media> {
+    let mut seen = HashSet::new();
+    let mut media: Vec<Media> = collections
+        .iter()
+        .flat_map(|uc| uc.collection.entries.iter())
+        .filter(|m| matches!(m, Media::Movie(_) | Media::TvShow(_)))
+        .filter(|m| seen.insert(m.identity()))
+        .cloned()
+        .collect();
+
+    match ordering {
+        ScheduleOrdering::Ranked => {
+            // A title ranked in several collections goes by its best showing.
+            media.sort_by_key(|m| {
+                collections
+                    .iter()
+                    .filter_map(|uc| uc.rank_of(m))
+                    .min()
+                    .unwrap_or(usize::MAX)
+            });
+            media
+        }
+        ScheduleOrdering::Random => {
+            let mut keyed: Vec<(u64, Media)> =
+                media.into_iter().map(|m| (random_key(), m)).collect();
+            keyed.sort_by_key(|(key, _)| *key);
+            keyed.into_iter().map(|(_, m)| m).collect()
+        }
+        ScheduleOrdering::Chronological => {
+            media.sort_by_key(|m| m.year().unwrap_or(i32::MAX));
+            media
+        }
+        ScheduleOrdering::GenreRotating => rotate_genres(media),
+    }
+}
+
+fn random_key() -> u64 {
+    let mut buf = [0u8; 8];
+    getrandom::getrandom(&mut buf).expect("No source of randomness");
+    u64::from_le_bytes(buf)
+}
+
+// Groups by each title's first genre, in the order the genres turn up, then deals one from each
+// group in turn.
+fn rotate_genres(media: Vec<Media>) -> Vec<Media> {
+    let mut groups: Vec<(Option<i64>, VecDeque<Media>)> = vec![];
+    for m in media {
+        let genre = m.genre_ids().first().copied();
+        match groups.iter_mut().find(|(g, _)| *g == genre) {
+            Some((_, group)) => group.push_back(m),
+            None => groups.push((genre, VecDeque::from([m]))),
+        }
+    }
+
+    let mut out = vec![];
+    while groups.iter().any(|(_, group)| !group.is_empty()) {
+        for (_, group) in groups.iter_mut() {
+            if let Some(m) = group.pop_front() {
+                out.push(m);
+            }
+        }
+    }
+    out
+}
+
+// What a title becomes on the schedule: a film at its runtime, or a show's next few episodes that
+// have aired by `today`, carrying on from `watched`. Falls back on the defaults for anything TMDB
+// hasn't told us.
+pub fn schedule_items(
+    media: &Media,
+    today: NaiveDate,
+    watched: Option<&WatchedEpisodes>,
+) -> Vec<ScheduleItem> {
+    media_items(media, today, watched, DEFAULT_EPISODES_PER_SHOW as usize)
+}
+
+// As `schedule_items`, with up to `episodes` episodes of a show whose episode list we have.
+pub fn media_items(
+    media: &Media,
+    today: NaiveDate,
+    watched: Option<&WatchedEpisodes>,
+    episodes: usize,
+) -> Vec<ScheduleItem> {
+    let nothing_watched = WatchedEpisodes::default();
+    let watched = watched.unwrap_or(&nothing_watched);
+
+    match media {
+        Media::Movie(m) => vec![ScheduleItem {
+            media: media.clone(),
+            duration: m
+                .details
+                .as_ref()
+                .and_then(|d| d.runtime)
+                .filter(|r| *r > 0)
+                .unwrap_or(DEFAULT_MOVIE_RUNTIME),
+            episode: None,
+        }],
+        Media::TvShow(TvShow {
+            details:
+                Some(
+                    details @ TvShowDetails {
+                        seasons: Some(_), ..
+                    },
+                ),
+            ..
+        }) => remaining_episodes(details, watched)
+            .filter(|(_, e)| aired_by(e, today))
+            .take(episodes)
+            .map(|(season, e)| ScheduleItem {
+                media: media.clone(),
+                duration: e.runtime.unwrap_or(DEFAULT_EPISODE_RUNTIME),
+                episode: Some(EpisodeInfo {
+                    season,
+                    episode: e.episode_number,
+                    name: Some(e.name.clone()).filter(|n| !n.is_empty()),
+                }),
+            })
+            .collect(),
+        Media::TvShow(_) => {
+            let next = watched
+                .last()
+                .map_or(EpisodeNumber::first(), EpisodeNumber::following);
+            (0..DEFAULT_EPISODES_PER_SHOW)
+                .map(|i| ScheduleItem {
+                    media: media.clone(),
+                    duration: DEFAULT_EPISODE_RUNTIME,
+                    episode: Some(EpisodeInfo {
+                        season: next.season,
+                        episode: next.episode + i,
+                        name: None,
+                    }),
+                })
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+fn aired_by(episode: &TvEpisode, today: NaiveDate) -> bool {
+    episode
+        .air_date
+        .as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .is_some_and(|d| d <= today)
+}
+
+// A local time in `tz` as UTC: the earlier of a time that happens twice as the clocks go back, and
+// an hour on for one skipped as they go forward.
+pub fn local_to_utc<Z: TimeZone>(tz: &Z, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|d| d.with_timezone(&Utc))
+}
+
+// Every window between `from` and `to` (inclusive) as UTC start and end, in order, with any
+// overlapping ones merged. Each day's windows are placed by that day's offset in `tz`.
+pub fn window_instants<Z: TimeZone>(
+    windows: &[ViewingWindow],
+    from: NaiveDate,
+    to: NaiveDate,
+    tz: &Z,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let to_utc = |local: NaiveDateTime| local_to_utc(tz, local);
+
+    let mut instants = vec![];
+    for day in from.iter_days().take_while(|d| *d <= to) {
+        for window in windows.iter().filter(|w| w.days.contains(&day.weekday())) {
+            let end_day = match window.end <= window.start {
+                true => day.succ_opt().unwrap_or(day),
+                false => day,
+            };
+            if let (Some(start), Some(end)) = (
+                to_utc(day.and_time(window.start)),
+                to_utc(end_day.and_time(window.end)),
+            ) {
+                instants.push((start, end));
+            }
+        }
+    }
+    instants.sort();
+
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = vec![];
+    for (start, end) in instants {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+// Fills the windows with `items` in order, moving on to the next window when the next item
+// won't fit in what's left of this one. Returns the slots and whatever didn't make it.
+pub fn pack_items(
+    items: Vec<ScheduleItem>,
+    windows: &[(DateTime<Utc>, DateTime<Utc>)],
+    gap: u32,
+) -> (ScheduleSlots, Vec<ScheduleItem>) {
+    let longest = windows
+        .iter()
+        .map(|(start, end)| (*end - *start).num_minutes())
+        .max()
+        .unwrap_or(0);
+    // Anything longer than every window would hold the rest up forever.
+    let (mut queue, mut leftover): (VecDeque<ScheduleItem>, Vec<ScheduleItem>) =
+        (VecDeque::new(), vec![]);
+    for item in items {
+        match (item.duration as i64) <= longest {
+            true => queue.push_back(item),
+            false => leftover.push(item),
+        }
+    }
+
+    let mut slots = vec![];
+    for (start, end) in windows {
+        let mut cursor = *start;
+        while let Some(item) = queue.front() {
+            let finish = cursor + Duration::minutes(item.duration as i64);
+            if finish > *end {
+                break;
+            }
+            let item = queue.pop_front().expect("Checked above");
+            slots.push(ScheduleSlot {
+                id: Uuid::new_v4(),
+                start: cursor,
+                media: item.media,
+                duration: item.duration,
+                episode: item.episode,
+                offset: 0,
+            });
+            cursor = finish + Duration::minutes(gap as i64);
+        }
+        if queue.is_empty() {
+            break;
+        }
+    }
+
+    leftover.extend(queue);
+    let windows = windows
+        .iter()
+        .map(|(start, end)| TimeWindow {
+            start: *start,
+            end: *end,
+        })
+        .collect();
+    (ScheduleSlots { slots, windows }, leftover)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GeneratedSchedule {
+    pub schedule: Schedule,
+    // Titles that didn't fit in the windows, e.g. "Alien (1979)".
+    pub unscheduled: Vec<String>,
+    // Suggestions left out altogether, with why, e.g. "Alien (1979): thumbed down".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GeneratedScheduleResponse {
+    pub status: String,
+    pub data: GeneratedSchedule,
+}
@@ -0,0 +1,579 @@
+use crate::model::collections::{Media, UserCollection};
+use crate::model::progress::{EpisodeNumber, SeriesProgress, WatchedEpisodes};
+use crate::model::schedule_generator::{
+    local_to_utc, media_items, order_media, ScheduleItem, ScheduleOrdering,
+};
+use crate::model::schedules::{ScheduleSlot, ScheduleSlots};
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use uuid::Uuid;
+
+// Two weeks of slots ahead is enough to plan around, and little enough to redo when rules change.
+pub const DEFAULT_HORIZON_DAYS: u32 = 14;
+pub const MAX_HORIZON_DAYS: u32 = 90;
+pub const MAX_PER_OCCURRENCE: u32 = 10;
+// Topping up at most once a day keeps reads from writing every time.
+pub const TOP_UP_DAYS: i64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Frequency {
+    Daily,
+    #[default]
+    Weekly,
+}
+
+// The subset of an iCalendar RRULE (RFC 5545 3.3.10) that routines need: FREQ, INTERVAL, BYDAY,
+// BYHOUR, BYMINUTE and UNTIL, e.g. "FREQ=WEEKLY;BYDAY=TU,TH;BYHOUR=21;BYMINUTE=0". Times are in
+// the routine's local time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rhythm {
+    pub freq: Frequency,
+    // Every how many days or weeks.
+    pub interval: u32,
+    // Only on these days; every day for daily rules when empty.
+    pub days: Vec<Weekday>,
+    pub at: NaiveTime,
+    // The last day it happens on, if it stops.
+    pub until: Option<NaiveDate>,
+}
+
+const RRULE_DAYS: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Mon),
+    ("TU", Weekday::Tue),
+    ("WE", Weekday::Wed),
+    ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri),
+    ("SA", Weekday::Sat),
+    ("SU", Weekday::Sun),
+];
+
+impl Rhythm {
+    // Whether it happens on `day`, for a routine that started on `starts`.
+    pub fn happens_on(&self, day: NaiveDate, starts: NaiveDate) -> bool {
+        if day < starts || self.until.is_some_and(|until| day > until) {
+            return false;
+        }
+        if !self.days.is_empty() && !self.days.contains(&day.weekday()) {
+            return false;
+        }
+        let interval = self.interval.max(1) as i64;
+        match self.freq {
+            Frequency::Daily => (day - starts).num_days() % interval == 0,
+            // Counted in whole weeks from the Monday of the week it started.
+            Frequency::Weekly => {
+                let monday =
+                    |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+                ((monday(day) - monday(starts)).num_days() / 7) % interval == 0
+            }
+        }
+    }
+}
+
+impl Display for Rhythm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let freq = match self.freq {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+        };
+        write!(f, "FREQ={freq}")?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.days.is_empty() {
+            let days: Vec<&str> = RRULE_DAYS
+                .iter()
+                .filter(|(_, d)| self.days.contains(d))
+                .map(|(code, _)| *code)
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        write!(
+            f,
+            ";BYHOUR={};BYMINUTE={}",
+            self.at.hour(),
+            self.at.minute()
+        )?;
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Rhythm {
+    type Err = String;
+
+    fn from_str(rrule: &str) -> Result<Self, Self::Err> {
+        let rrule = rrule.trim();
+        let rrule = rrule.strip_prefix("RRULE:").unwrap_or(rrule);
+        let number = |key: &str, value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("{key} needs a number, not {value}"))
+        };
+
+        let mut freq = None;
+        let mut interval = 1;
+        let mut days = vec![];
+        let (mut hour, mut minute) = (None, 0);
+        let mut until = None;
+        for part in rrule.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Can't read {part}"))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        other => return Err(format!("Only daily and weekly rules, not {other}")),
+                    })
+                }
+                "INTERVAL" => interval = number(key, value)?.max(1),
+                "BYDAY" => {
+                    for code in value.split(',') {
+                        let day = RRULE_DAYS
+                            .iter()
+                            .find(|(c, _)| c.eq_ignore_ascii_case(code.trim()))
+                            .map(|(_, d)| *d)
+                            .ok_or_else(|| format!("{code} isn't a day"))?;
+                        if !days.contains(&day) {
+                            days.push(day);
+                        }
+                    }
+                }
+                "BYHOUR" => hour = Some(number(key, value)?),
+                "BYMINUTE" => minute = number(key, value)?,
+                // Just the date of a DATE or DATE-TIME.
+                "UNTIL" => {
+                    until = Some(
+                        NaiveDate::parse_from_str(value.get(..8).unwrap_or(value), "%Y%m%d")
+                            .map_err(|_| format!("UNTIL needs a date, not {value}"))?,
+                    )
+                }
+                other => return Err(format!("{other} isn't supported")),
+            }
+        }
+
+        let freq = freq.ok_or("Rules need a FREQ")?;
+        if freq == Frequency::Weekly && days.is_empty() {
+            return Err("Weekly rules need BYDAY".to_string());
+        }
+        let at = hour
+            .and_then(|hour| NaiveTime::from_hms_opt(hour, minute, 0))
+            .ok_or("Rules need a BYHOUR and BYMINUTE that make a time")?;
+        Ok(Rhythm {
+            freq,
+            interval,
+            days,
+            at,
+            until,
+        })
+    }
+}
+
+// Stored and sent as the RRULE text.
+impl Serialize for Rhythm {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rhythm {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+// What a rule puts on each time it comes round.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "from", rename_all = "snake_case")]
+pub enum RuleSource {
+    // The next films from the collection in its own order, each once.
+    Movies { collection: Uuid },
+    // The next episodes of the first show in the collection that has any left.
+    Episodes { collection: Uuid },
+    // The next episodes of whichever show the user marked something of most recently.
+    CurrentSeries,
+}
+
+impl RuleSource {
+    pub fn collection(&self) -> Option<Uuid> {
+        match self {
+            RuleSource::Movies { collection } | RuleSource::Episodes { collection } => {
+                Some(*collection)
+            }
+            RuleSource::CurrentSeries => None,
+        }
+    }
+}
+
+fn one() -> u32 {
+    1
+}
+
+// "Fridays 21:00, a film from the Watchlist" or "Tue/Thu, two episodes of the current series".
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub rrule: Rhythm,
+    pub source: RuleSource,
+    // How many films or episodes each time, back to back.
+    #[serde(default = "one")]
+    pub count: u32,
+}
+
+fn default_horizon() -> u32 {
+    DEFAULT_HORIZON_DAYS
+}
+
+// What's stored in `schedule_recurrence.recurrence`: a standing routine whose slots are laid out
+// as time goes on, `horizon_days` ahead.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Recurrence {
+    pub rules: Vec<RecurrenceRule>,
+    // Intervals count from here.
+    pub starts: NaiveDate,
+    // The IANA zone the rules' times are in, as the browser sees it, so "21:00" stays 21:00 across
+    // daylight saving changes.
+    #[serde(default)]
+    pub time_zone: Tz,
+    #[serde(default = "default_horizon")]
+    pub horizon_days: u32,
+}
+
+impl Recurrence {
+    pub fn check(&self) -> Result<(), String> {
+        if self.rules.is_empty() {
+            return Err("Routines need at least one rule".to_string());
+        }
+        if self
+            .rules
+            .iter()
+            .any(|r| r.count == 0 || r.count > MAX_PER_OCCURRENCE)
+        {
+            return Err(format!(
+                "Each rule can put on 1 to {MAX_PER_OCCURRENCE} things at a time"
+            ));
+        }
+        if self.horizon_days == 0 || self.horizon_days > MAX_HORIZON_DAYS {
+            return Err(format!(
+                "Routines can be laid out 1 to {MAX_HORIZON_DAYS} days ahead"
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Everything the rules may draw on: their collections by id, and the user's shows in progress,
+// most recently watched first.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSources {
+    pub collections: HashMap<Uuid, UserCollection>,
+    pub progress: Vec<SeriesProgress>,
+}
+
+// What's already on the schedule, so picks carry on from it rather than repeat it.
+struct Picked {
+    movies: HashSet<String>,
+    episodes: HashMap<String, EpisodeNumber>,
+}
+
+impl Picked {
+    fn add(&mut self, media: &Media, episode: Option<EpisodeNumber>) {
+        match episode {
+            Some(episode) => {
+                let last = self.episodes.entry(media.identity()).or_insert(episode);
+                *last = episode.max(*last);
+            }
+            None => {
+                self.movies.insert(media.identity());
+            }
+        }
+    }
+
+    // The show's next episode after anything scheduled or watched.
+    fn next_episode(
+        &self,
+        show: &Media,
+        watched: Option<&WatchedEpisodes>,
+        today: NaiveDate,
+    ) -> Option<ScheduleItem> {
+        let furthest = [
+            self.episodes.get(&show.identity()).copied(),
+            watched.and_then(|w| w.last()),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+        let after = WatchedEpisodes {
+            episodes: furthest.into_iter().collect(),
+        };
+        media_items(show, today, Some(&after), 1).into_iter().next()
+    }
+}
+
+fn episode_number(item: &ScheduleItem) -> Option<EpisodeNumber> {
+    item.episode.as_ref().map(|e| EpisodeNumber {
+        season: e.season,
+        episode: e.episode,
+    })
+}
+
+fn pick(
+    source: &RuleSource,
+    sources: &RuleSources,
+    picked: &Picked,
+    today: NaiveDate,
+) -> Result<ScheduleItem, String> {
+    let collection = |id: &Uuid| {
+        sources
+            .collections
+            .get(id)
+            .ok_or_else(|| "its collection is gone".to_string())
+    };
+    let watched = |show: &Media| {
+        sources
+            .progress
+            .iter()
+            .find(|p| p.show_key == show.identity())
+            .map(|p| &p.watched)
+    };
+
+    match source {
+        RuleSource::Movies { collection: id } => {
+            let col = collection(id)?;
+            order_media(std::slice::from_ref(col), ScheduleOrdering::Ranked)
+                .iter()
+                .filter(|m| matches!(m, Media::Movie(_)))
+                .filter(|m| !picked.movies.contains(&m.identity()))
+                .flat_map(|m| media_items(m, today, None, 1))
+                .next()
+                .ok_or_else(|| format!("no films left in {}", col.name))
+        }
+        RuleSource::Episodes { collection: id } => {
+            let col = collection(id)?;
+            order_media(std::slice::from_ref(col), ScheduleOrdering::Ranked)
+                .iter()
+                .filter(|m| matches!(m, Media::TvShow(_)))
+                .find_map(|show| picked.next_episode(show, watched(show), today))
+                .ok_or_else(|| format!("no episodes left in {}", col.name))
+        }
+        RuleSource::CurrentSeries => sources
+            .progress
+            .iter()
+            .find_map(|p| {
+                picked.next_episode(&Media::TvShow(p.show.clone()), Some(&p.watched), today)
+            })
+            .ok_or_else(|| "no series in progress".to_string()),
+    }
+}
+
+// Lays the rules out over [`from`, `until`), carrying on from what's already in `existing`. Things
+// due at the same time go back to back, in rule order. Returns the new slots and, for each time
+// something couldn't be put on, why.
+pub fn materialize(
+    recurrence: &Recurrence,
+    existing: &ScheduleSlots,
+    sources: &RuleSources,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> (Vec<ScheduleSlot>, Vec<String>) {
+    if recurrence.check().is_err() {
+        return (vec![], vec![]);
+    }
+    let tz = recurrence.time_zone;
+    let to_utc = |local: NaiveDateTime| local_to_utc(&tz, local);
+
+    let mut due: Vec<(DateTime<Utc>, &RecurrenceRule)> = vec![];
+    let first_day = from.with_timezone(&tz).date_naive();
+    let last_day = until.with_timezone(&tz).date_naive();
+    for day in first_day.iter_days().take_while(|d| *d <= last_day) {
+        for rule in &recurrence.rules {
+            if !rule.rrule.happens_on(day, recurrence.starts) {
+                continue;
+            }
+            match to_utc(day.and_time(rule.rrule.at)) {
+                Some(at) if from <= at && at < until => due.push((at, rule)),
+                _ => {}
+            }
+        }
+    }
+    // Stable, so rules due together keep their order.
+    due.sort_by_key(|(at, _)| *at);
+
+    let mut picked = Picked {
+        movies: HashSet::new(),
+        episodes: HashMap::new(),
+    };
+    for slot in &existing.slots {
+        picked.add(
+            &slot.media,
+            slot.episode.as_ref().map(|e| EpisodeNumber {
+                season: e.season,
+                episode: e.episode,
+            }),
+        );
+    }
+
+    // Anything due while something else is on waits for it to finish.
+    let mut taken: Vec<(DateTime<Utc>, DateTime<Utc>)> =
+        existing.slots.iter().map(|s| (s.start, s.end())).collect();
+    let mut slots = vec![];
+    let mut missed = vec![];
+    for (at, rule) in due {
+        let mut start = at;
+        while let Some(end) = taken
+            .iter()
+            .filter(|(s, e)| *s <= start && start < *e)
+            .map(|(_, e)| *e)
+            .max()
+        {
+            start = end;
+        }
+        for _ in 0..rule.count {
+            match pick(
+                &rule.source,
+                sources,
+                &picked,
+                at.with_timezone(&tz).date_naive(),
+            ) {
+                Ok(item) => {
+                    picked.add(&item.media, episode_number(&item));
+                    let slot = ScheduleSlot {
+                        id: Uuid::new_v4(),
+                        start,
+                        media: item.media,
+                        duration: item.duration,
+                        episode: item.episode,
+                        offset: 0,
+                    };
+                    taken.push((slot.start, slot.end()));
+                    start = slot.end();
+                    slots.push(slot);
+                }
+                Err(why) => {
+                    missed.push(format!(
+                        "{}: {why}",
+                        at.with_timezone(&tz).format("%a %e %b %H:%M")
+                    ));
+                    break;
+                }
+            }
+        }
+    }
+    (slots, missed)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NewRecurringScheduleSchema {
+    pub name: String,
+    pub recurrence: Recurrence,
+}
+
+// `recurrence` is `None` for schedules that don't recur.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RecurrenceData {
+    pub recurrence: Option<Recurrence>,
+    // How far ahead the slots have been laid out.
+    pub materialized_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecurrenceResponse {
+    pub status: String,
+    pub data: RecurrenceData,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints_rrules() {
+        let rhythm: Rhythm =
+            "RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;BYHOUR=21;BYMINUTE=30;UNTIL=20241231T235959Z"
+                .parse()
+                .unwrap();
+        assert_eq!(rhythm.freq, Frequency::Weekly);
+        assert_eq!(rhythm.interval, 2);
+        assert_eq!(rhythm.days, vec![Weekday::Tue, Weekday::Thu]);
+        assert_eq!(rhythm.at, NaiveTime::from_hms_opt(21, 30, 0).unwrap());
+        assert_eq!(rhythm.until, Some(day(2024, 12, 31)));
+        assert_eq!(
+            rhythm.to_string(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;BYHOUR=21;BYMINUTE=30;UNTIL=20241231"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_rrules() {
+        for rrule in [
+            "FREQ=DAILY;BYHOUR=25",
+            "FREQ=DAILY;BYHOUR=nine",
+            "FREQ=DAILY;BYHOUR=21;BYMINUTE=60",
+            "FREQ=DAILY",
+            "FREQ=WEEKLY;BYHOUR=21",
+            "FREQ=MONTHLY;BYHOUR=21",
+            "FREQ=WEEKLY;BYDAY=XX;BYHOUR=21",
+            "BYHOUR=21",
+        ] {
+            assert!(rrule.parse::<Rhythm>().is_err(), "{rrule} parsed");
+        }
+    }
+
+    #[test]
+    fn weekly_intervals_count_whole_weeks_from_the_start() {
+        let rhythm: Rhythm = "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;BYHOUR=21"
+            .parse()
+            .unwrap();
+        // A Wednesday, so the first week still counts.
+        let starts = day(2024, 11, 6);
+        let happens: Vec<NaiveDate> = starts
+            .iter_days()
+            .take(28)
+            .filter(|d| rhythm.happens_on(*d, starts))
+            .collect();
+        assert_eq!(
+            happens,
+            vec![
+                day(2024, 11, 7),
+                day(2024, 11, 19),
+                day(2024, 11, 21),
+                day(2024, 12, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_happens_after_until() {
+        let rhythm: Rhythm = "FREQ=DAILY;INTERVAL=3;BYHOUR=8;UNTIL=20241110"
+            .parse()
+            .unwrap();
+        let starts = day(2024, 11, 1);
+        let happens: Vec<NaiveDate> = starts
+            .iter_days()
+            .take(14)
+            .filter(|d| rhythm.happens_on(*d, starts))
+            .collect();
+        assert_eq!(
+            happens,
+            vec![
+                day(2024, 11, 1),
+                day(2024, 11, 4),
+                day(2024, 11, 7),
+                day(2024, 11, 10)
+            ]
+        );
+    }
+}
@@ -0,0 +1,405 @@
+use crate::model::schedules::{Schedule, ScheduleSlot, ScheduleSlots, TimeWindow};
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+// Splitting off less than this leaves a stub nobody wants to start watching; it moves instead.
+pub const MIN_SPLIT_MINUTES: u32 = 10;
+const RUN_GAP_MINUTES: i64 = 60;
+
+// Something wrong with the slot at `index`, in start order.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleConflict {
+    // Starts `minutes` before the slot before it has finished.
+    Overlap { index: usize, minutes: i64 },
+    // Runs `minutes` past the end of the window it starts in.
+    PastWindow { index: usize, minutes: i64 },
+}
+
+impl ScheduleConflict {
+    // "Heat (1995) overlaps Alien (1979) by 20 minutes"
+    pub fn describe(&self, slots: &ScheduleSlots) -> String {
+        let title = |index: usize| {
+            slots
+                .slots
+                .get(index)
+                .map(|s| s.title())
+                .unwrap_or_default()
+        };
+        match *self {
+            ScheduleConflict::Overlap { index, minutes } => format!(
+                "{} overlaps {} by {minutes} minutes",
+                title(index),
+                title(index.saturating_sub(1))
+            ),
+            ScheduleConflict::PastWindow { index, minutes } => {
+                format!("{} runs {minutes} minutes late", title(index))
+            }
+        }
+    }
+}
+
+// Overlapping slots, and slots running past the end of their window. Expects start order.
+pub fn find_conflicts(slots: &ScheduleSlots) -> Vec<ScheduleConflict> {
+    let mut conflicts = vec![];
+    for (index, slot) in slots.slots.iter().enumerate() {
+        if let Some(previous) = index.checked_sub(1).map(|i| &slots.slots[i]) {
+            let minutes = (previous.end() - slot.start).num_minutes();
+            if minutes > 0 {
+                conflicts.push(ScheduleConflict::Overlap { index, minutes });
+            }
+        }
+        if let Some(window) = slots.window_at(slot.start) {
+            let minutes = (slot.end() - window.end).num_minutes();
+            if minutes > 0 {
+                conflicts.push(ScheduleConflict::PastWindow { index, minutes });
+            }
+        }
+    }
+    conflicts
+}
+
+// What happens to slots that no longer fit after an edit.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReflowPolicy {
+    // Everything after moves along, into the next window when this one is full.
+    #[default]
+    Shift,
+    // Whatever no longer fits comes off the schedule.
+    Drop,
+    // Whatever runs past the end of its window carries on at the start of the next one.
+    Split,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ReflowEdit {
+    Insert { slot: Box<ScheduleSlot> },
+    // The slot at `index`, in start order.
+    Remove { index: usize },
+    // Whatever is on at `at`, or next if nothing is, starts `minutes` later. "We started 30
+    // minutes late."
+    Delay { at: DateTime<Utc>, minutes: u32 },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ReflowSchema {
+    pub edit: ReflowEdit,
+    #[serde(default)]
+    pub policy: ReflowPolicy,
+    // Work it out without saving, to see what would happen.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// The slots after an edit, and what came off them.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct Reflowed {
+    pub slots: ScheduleSlots,
+    pub dropped: Vec<ScheduleSlot>,
+}
+
+// The first window starting at or after `at`.
+fn next_window(windows: &[TimeWindow], at: DateTime<Utc>) -> Option<&TimeWindow> {
+    windows.iter().find(|w| w.start >= at)
+}
+
+// Applies the edit, then puts every slot back where it fits, keeping their order.
+pub fn reflow(
+    mut slots: ScheduleSlots,
+    edit: ReflowEdit,
+    policy: ReflowPolicy,
+) -> Result<Reflowed, &'static str> {
+    slots.sort();
+    match edit {
+        ReflowEdit::Insert { slot } => {
+            if slot.duration == 0 {
+                return Err("Every slot needs a duration");
+            }
+            // Ahead of anything starting at the same time, so that's what moves.
+            let index = slots.slots.partition_point(|s| s.start < slot.start);
+            slots.slots.insert(index, *slot);
+        }
+        ReflowEdit::Remove { index } => {
+            if index >= slots.slots.len() {
+                return Err("No such slot");
+            }
+            let removed = slots.slots.remove(index);
+            // Shifting pulls the rest of its window forward into the time it leaves, or without
+            // windows, whatever followed on within the hour.
+            if policy == ReflowPolicy::Shift {
+                let window = slots.window_at(removed.start).copied();
+                let pull = Duration::minutes(removed.duration as i64);
+                let mut free_from = removed.start;
+                let mut run_end = removed.end();
+                for slot in slots.slots.iter_mut().skip(index) {
+                    let same_run = match window {
+                        Some(window) => window.contains(slot.start),
+                        None => slot.start <= run_end + Duration::minutes(RUN_GAP_MINUTES),
+                    };
+                    if !same_run {
+                        break;
+                    }
+                    run_end = slot.end();
+                    slot.start = free_from.max(slot.start - pull);
+                    free_from = slot.end();
+                }
+            }
+        }
+        ReflowEdit::Delay { at, minutes } => {
+            let Some(slot) = slots.slots.iter_mut().find(|s| s.end() > at) else {
+                return Err("Nothing left on the schedule to delay");
+            };
+            slot.start += Duration::minutes(minutes as i64);
+        }
+    }
+
+    Ok(settle(slots, policy))
+}
+
+// Moves, splits or drops slots, in order, until nothing overlaps or runs past its window.
+fn settle(slots: ScheduleSlots, policy: ReflowPolicy) -> Reflowed {
+    let windows = slots.windows;
+    let mut queue: VecDeque<ScheduleSlot> = slots.slots.into();
+    let mut placed: Vec<ScheduleSlot> = vec![];
+    let mut dropped = vec![];
+
+    while let Some(mut slot) = queue.pop_front() {
+        let original_start = slot.start;
+        if let Some(previous_end) = placed.last().map(|s| s.end()) {
+            if slot.start < previous_end {
+                if policy == ReflowPolicy::Drop {
+                    dropped.push(slot);
+                    continue;
+                }
+                slot.start = previous_end;
+            }
+        }
+
+        if windows.is_empty() {
+            placed.push(slot);
+            continue;
+        }
+
+        let window = windows.iter().find(|w| w.contains(slot.start)).copied();
+        let window = match window {
+            Some(window) => window,
+            // Slots put outside the windows on purpose stay where they are; ones pushed out of
+            // theirs go on to the next.
+            None if slot.start == original_start => {
+                placed.push(slot);
+                continue;
+            }
+            None => match next_window(&windows, slot.start) {
+                Some(next) if policy != ReflowPolicy::Drop => {
+                    slot.start = next.start;
+                    *next
+                }
+                _ => {
+                    dropped.push(slot);
+                    continue;
+                }
+            },
+        };
+
+        if slot.end() <= window.end {
+            placed.push(slot);
+            continue;
+        }
+
+        let fits = (window.end - slot.start).num_minutes().max(0) as u32;
+        match policy {
+            ReflowPolicy::Drop => dropped.push(slot),
+            ReflowPolicy::Split if fits >= MIN_SPLIT_MINUTES => {
+                let mut rest = slot.clone();
+                rest.id = Uuid::new_v4();
+                rest.duration -= fits;
+                rest.offset += fits;
+                slot.duration = fits;
+                placed.push(slot);
+                match next_window(&windows, window.end) {
+                    Some(next) => {
+                        rest.start = next.start;
+                        queue.push_front(rest);
+                    }
+                    None => dropped.push(rest),
+                }
+            }
+            _ => match next_window(&windows, window.end) {
+                Some(next) => {
+                    slot.start = next.start;
+                    queue.push_front(slot);
+                }
+                None => dropped.push(slot),
+            },
+        }
+    }
+
+    Reflowed {
+        slots: ScheduleSlots {
+            slots: placed,
+            windows,
+        },
+        dropped,
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ReflowedSchedule {
+    pub schedule: Schedule,
+    pub dropped: Vec<ScheduleSlot>,
+    // Anything the policy couldn't sort out, like slots placed outside the windows on purpose.
+    pub conflicts: Vec<ScheduleConflict>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReflowedScheduleResponse {
+    pub status: String,
+    pub data: ReflowedSchedule,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ConflictsData {
+    pub conflicts: Vec<ScheduleConflict>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConflictsResponse {
+    pub status: String,
+    pub data: ConflictsData,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::collections::Media;
+    use crate::model::core::Movie;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 11, 1, hour, minute, 0).unwrap()
+    }
+
+    fn slot(name: &str, start: DateTime<Utc>, duration: u32) -> ScheduleSlot {
+        ScheduleSlot {
+            id: Uuid::new_v4(),
+            start,
+            media: Media::Movie(Movie {
+                name: name.to_string(),
+                ..Movie::default()
+            }),
+            duration,
+            episode: None,
+            offset: 0,
+        }
+    }
+
+    fn window(start: DateTime<Utc>, end: DateTime<Utc>) -> TimeWindow {
+        TimeWindow { start, end }
+    }
+
+    fn starts(reflowed: &Reflowed) -> Vec<DateTime<Utc>> {
+        reflowed.slots.slots.iter().map(|s| s.start).collect()
+    }
+
+    #[test]
+    fn shift_moves_overlaps_along() {
+        let slots = ScheduleSlots {
+            slots: vec![slot("A", at(20, 0), 60), slot("B", at(20, 30), 30)],
+            windows: vec![],
+        };
+        let reflowed = settle(slots, ReflowPolicy::Shift);
+        assert_eq!(starts(&reflowed), vec![at(20, 0), at(21, 0)]);
+        assert!(reflowed.dropped.is_empty());
+    }
+
+    #[test]
+    fn drop_takes_overlaps_off() {
+        let slots = ScheduleSlots {
+            slots: vec![slot("A", at(20, 0), 60), slot("B", at(20, 30), 30)],
+            windows: vec![],
+        };
+        let reflowed = settle(slots, ReflowPolicy::Drop);
+        assert_eq!(starts(&reflowed), vec![at(20, 0)]);
+        assert_eq!(reflowed.dropped.len(), 1);
+        assert_eq!(reflowed.dropped[0].media.name(), "B");
+    }
+
+    #[test]
+    fn split_carries_the_rest_into_the_next_window() {
+        let slots = ScheduleSlots {
+            slots: vec![slot("A", at(21, 30), 60)],
+            windows: vec![
+                window(at(20, 0), at(22, 0)),
+                window(at(23, 0), at(23, 0) + Duration::hours(2)),
+            ],
+        };
+        let reflowed = settle(slots, ReflowPolicy::Split);
+        let placed = &reflowed.slots.slots;
+        assert_eq!(placed.len(), 2);
+        assert_eq!(
+            (placed[0].start, placed[0].duration, placed[0].offset),
+            (at(21, 30), 30, 0)
+        );
+        assert_eq!(
+            (placed[1].start, placed[1].duration, placed[1].offset),
+            (at(23, 0), 30, 30)
+        );
+    }
+
+    #[test]
+    fn split_moves_slots_rather_than_leave_a_stub() {
+        let fits = MIN_SPLIT_MINUTES - 1;
+        let slots = ScheduleSlots {
+            slots: vec![slot("A", at(22, 0) - Duration::minutes(fits as i64), 60)],
+            windows: vec![
+                window(at(20, 0), at(22, 0)),
+                window(at(23, 0), at(23, 0) + Duration::hours(2)),
+            ],
+        };
+        let reflowed = settle(slots, ReflowPolicy::Split);
+        let placed = &reflowed.slots.slots;
+        assert_eq!(placed.len(), 1);
+        assert_eq!(
+            (placed[0].start, placed[0].duration, placed[0].offset),
+            (at(23, 0), 60, 0)
+        );
+    }
+
+    #[test]
+    fn slots_outside_the_windows_on_purpose_stay() {
+        let slots = ScheduleSlots {
+            slots: vec![slot("A", at(18, 0), 60), slot("B", at(20, 0), 60)],
+            windows: vec![window(at(20, 0), at(22, 0))],
+        };
+        let reflowed = settle(slots, ReflowPolicy::Shift);
+        assert_eq!(starts(&reflowed), vec![at(18, 0), at(20, 0)]);
+    }
+
+    #[test]
+    fn slots_pushed_out_of_their_window_go_to_the_next() {
+        let slots = ScheduleSlots {
+            slots: vec![slot("A", at(20, 0), 120), slot("B", at(21, 0), 30)],
+            windows: vec![
+                window(at(20, 0), at(22, 0)),
+                window(at(23, 0), at(23, 0) + Duration::hours(2)),
+            ],
+        };
+        let reflowed = settle(slots, ReflowPolicy::Shift);
+        assert_eq!(starts(&reflowed), vec![at(20, 0), at(23, 0)]);
+    }
+
+    #[test]
+    fn slots_with_no_window_left_are_dropped() {
+        let slots = ScheduleSlots {
+            slots: vec![slot("A", at(20, 0), 120), slot("B", at(21, 0), 30)],
+            windows: vec![window(at(20, 0), at(22, 0))],
+        };
+        let reflowed = settle(slots, ReflowPolicy::Shift);
+        assert_eq!(starts(&reflowed), vec![at(20, 0)]);
+        assert_eq!(reflowed.dropped.len(), 1);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::model::schedule_generator::ViewingWindow;
+use chrono::prelude::*;
+use chrono_tz::Tz;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Most slots we'll ask the AI to fill in one go.
+pub const MAX_THEMED_SLOTS: usize = 40;
+
+// "A week of 80s horror, escalating intensity", laid out over the given windows.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ThemedScheduleSchema {
+    pub name: String,
+    pub theme: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub windows: Vec<ViewingWindow>,
+    // The IANA zone the windows are in, as the browser sees it.
+    #[serde(default)]
+    pub time_zone: Tz,
+    // Minutes left free between slots.
+    #[serde(default)]
+    pub gap: u32,
+}
+
+// One slot as the AI picks it. Shows name the episode to play; without one they carry on from the
+// last episode of the same show in the lineup.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct ThemedPick {
+    // "movie" or "tv".
+    pub media_type: String,
+    pub name: String,
+    pub year: i32,
+    pub season: Option<u32>,
+    pub episode: Option<u32>,
+    pub rationale: String,
+}
+
+// What comes back from the AI, in the order it should play.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct ThemedLineup {
+    pub slots: Vec<ThemedPick>,
+}
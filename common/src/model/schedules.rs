@@ -0,0 +1,187 @@
+use crate::model::collections::{Media, ShareType};
+use crate::model::tags::Tags;
+use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+// Which episode of a show a slot is for.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct EpisodeInfo {
+    pub season: u32,
+    pub episode: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl std::fmt::Display for EpisodeInfo {
+    // "S01E04 Cat's in the Bag..."
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "S{:02}E{:02}", self.season, self.episode)?;
+        match &self.name {
+            Some(name) => write!(f, " {name}"),
+            None => Ok(()),
+        }
+    }
+}
+
+fn is_zero(minutes: &u32) -> bool {
+    *minutes == 0
+}
+
+// One thing on at one time. `duration` is in minutes.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ScheduleSlot {
+    // Kept as the slot is moved around, so calendar apps can follow it. Nil for slots saved before
+    // slots had ids.
+    #[serde(default)]
+    pub id: Uuid,
+    pub start: DateTime<Utc>,
+    pub media: Media,
+    pub duration: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode: Option<EpisodeInfo>,
+    // Minutes in that this slot picks up from, when something was split to fit the windows.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub offset: u32,
+}
+
+impl ScheduleSlot {
+    pub fn end(&self) -> DateTime<Utc> {
+        self.start + Duration::minutes(self.duration as i64)
+    }
+
+    // "Breaking Bad (2008-01-20) S01E04" or just the title for films, with where it picks up from
+    // if it's the rest of a split one.
+    pub fn title(&self) -> String {
+        let title = match &self.episode {
+            Some(episode) => format!("{} {episode}", self.media),
+            None => self.media.to_string(),
+        };
+        match self.offset {
+            0 => title,
+            offset => format!("{title} (from {offset}m)"),
+        }
+    }
+}
+
+// When the user said they'd be watching, for schedules that were generated.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct TimeWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+// What's stored in `schedules.schedule`, always kept in start order.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct ScheduleSlots {
+    pub slots: Vec<ScheduleSlot>,
+    // The viewing windows it was generated for, in order. Re-flowing keeps slots inside them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<TimeWindow>,
+}
+
+impl ScheduleSlots {
+    pub fn sort(&mut self) {
+        self.slots.sort_by_key(|s| s.start);
+    }
+
+    // Whatever is on at `at`, if anything.
+    pub fn on_at(&self, at: DateTime<Utc>) -> Option<&ScheduleSlot> {
+        self.slots.iter().find(|s| s.start <= at && at < s.end())
+    }
+
+    // The window a slot starting at `at` belongs to, if it's in one.
+    pub fn window_at(&self, at: DateTime<Utc>) -> Option<&TimeWindow> {
+        self.windows.iter().find(|w| w.contains(at))
+    }
+}
+
+impl From<Option<Value>> for ScheduleSlots {
+    fn from(value: Option<Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub active: bool,
+    pub sharing: Option<String>,
+    pub schedule: ScheduleSlots,
+    pub locked: bool,
+    #[serde(default)]
+    pub tags: Tags,
+    // Set for channels, which loop over this collection rather than having slots of their own.
+    #[serde(default)]
+    pub source_collection: Option<Uuid>,
+}
+
+impl Schedule {
+    pub fn is_channel(&self) -> bool {
+        self.source_collection.is_some()
+    }
+
+    pub fn share_type(&self) -> ShareType {
+        match self.sharing.as_deref() {
+            Some("public") => ShareType::Public,
+            Some("friends") => ShareType::Friends,
+            _ => ShareType::Private,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NewScheduleSchema {
+    pub name: String,
+    #[serde(default)]
+    pub schedule: ScheduleSlots,
+    #[serde(default)]
+    pub tags: Tags,
+}
+
+// Only what's set changes. A locked schedule can't be changed until it's unlocked.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct UpdateScheduleSchema {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sharing: Option<ShareType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<ScheduleSlots>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Tags>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SchedulesData {
+    pub schedules: Vec<Schedule>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SchedulesResponse {
+    pub status: String,
+    pub data: SchedulesData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScheduleResponse {
+    pub status: String,
+    pub data: Schedule,
+}
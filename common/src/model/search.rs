@@ -0,0 +1,57 @@
+use crate::model::collections::Media;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SearchHitCollection {
+    pub id: Uuid,
+    pub name: String,
+}
+
+// One title matching a search, with every collection it's in. The same title can sit in several
+// collections with different notes or tags, so `media` is the best matching copy.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub media: Media,
+    pub collections: Vec<SearchHitCollection>,
+    pub rank: f32,
+}
+
+// Folds (collection, entry, rank) matches into one hit per title, best first.
+pub fn group_search_hits(matches: Vec<(SearchHitCollection, Media, f32)>) -> Vec<SearchHit> {
+    let mut hits: Vec<SearchHit> = vec![];
+
+    for (collection, media, rank) in matches {
+        let identity = media.identity();
+        match hits.iter_mut().find(|h| h.media.identity() == identity) {
+            Some(hit) => {
+                if rank > hit.rank {
+                    hit.media = media;
+                    hit.rank = rank;
+                }
+                if !hit.collections.contains(&collection) {
+                    hit.collections.push(collection);
+                }
+            }
+            None => hits.push(SearchHit {
+                media,
+                collections: vec![collection],
+                rank,
+            }),
+        }
+    }
+
+    hits.sort_by(|a, b| b.rank.total_cmp(&a.rank));
+    hits
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SearchData {
+    pub results: Vec<SearchHit>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchResponse {
+    pub status: String,
+    pub data: SearchData,
+}
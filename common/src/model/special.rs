@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+// The collections every user has, which discovery and the AI prompts read and write. Stored in
+// `collections.special` by `key()`; the database only accepts these values, so adding one here
+// needs a migration widening that constraint. `ensure_special_collections` creates any a user is
+// missing when they log in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpecialCollection {
+    ThumbsUp,
+    ThumbsDown,
+    Skipped,
+    Watchlist,
+    Seen,
+}
+
+impl SpecialCollection {
+    pub const ALL: [SpecialCollection; 5] = [
+        SpecialCollection::ThumbsUp,
+        SpecialCollection::ThumbsDown,
+        SpecialCollection::Skipped,
+        SpecialCollection::Watchlist,
+        SpecialCollection::Seen,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            SpecialCollection::ThumbsUp => "thumbsup",
+            SpecialCollection::ThumbsDown => "thumbsdown",
+            SpecialCollection::Skipped => "skipped",
+            SpecialCollection::Watchlist => "watchlist",
+            SpecialCollection::Seen => "seen",
+        }
+    }
+
+    pub fn from_key(key: Option<&str>) -> Option<SpecialCollection> {
+        SpecialCollection::ALL
+            .into_iter()
+            .find(|special| Some(special.key()) == key)
+    }
+
+    // What the collection is called when it's created. Users can't rename them, they're locked.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpecialCollection::ThumbsUp => "👍 Thumbs Up",
+            SpecialCollection::ThumbsDown => "👎 Thumbs Down",
+            SpecialCollection::Skipped => "🤔 Skipped",
+            SpecialCollection::Watchlist => "📋 Watchlist",
+            SpecialCollection::Seen => "👁 Seen",
+        }
+    }
+
+    pub fn tag(&self) -> &'static str {
+        match self {
+            SpecialCollection::ThumbsUp => "thumbs up",
+            SpecialCollection::ThumbsDown => "thumbs down",
+            SpecialCollection::Skipped => "skipped",
+            SpecialCollection::Watchlist => "watchlist",
+            SpecialCollection::Seen => "seen",
+        }
+    }
+}
+
+impl Display for SpecialCollection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
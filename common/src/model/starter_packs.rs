@@ -0,0 +1,64 @@
+use crate::model::collections::{Media, MediaCollection};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// A curated handful of titles for new users to rate, so discovery has some taste to go on from
+// the start. `slug` is how the bundled packs are matched up with what's in the database.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StarterPack {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub entries: MediaCollection,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl StarterPack {
+    // Whether `media` is one of this pack's titles. By name rather than `identity()`, since the
+    // client fills in the TMDB details the bundled packs don't have.
+    pub fn contains(&self, media: &Media) -> bool {
+        self.entries
+            .entries
+            .iter()
+            .any(|m| m.kind() == media.kind() && m.name() == media.name())
+    }
+}
+
+// A pack as admins send it and as it's written in the bundled data file.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StarterPackSchema {
+    pub slug: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub entries: MediaCollection,
+}
+
+// How the user swiped through a pack. Anything they passed on is left out.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct ApplyStarterPackSchema {
+    #[serde(default)]
+    pub thumbs_up: Vec<Media>,
+    #[serde(default)]
+    pub thumbs_down: Vec<Media>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StarterPacksData {
+    pub packs: Vec<StarterPack>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StarterPacksResponse {
+    pub status: String,
+    pub data: StarterPacksData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StarterPackResponse {
+    pub status: String,
+    pub data: StarterPack,
+}
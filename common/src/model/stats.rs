@@ -0,0 +1,143 @@
+use crate::model::collections::{Media, UserCollection};
+use crate::model::special::SpecialCollection;
+use crate::model::tmdb::genre_name;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StatCount {
+    pub label: String,
+    pub count: usize,
+}
+
+// One month of activity, from the entries' `added_at`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TrendPoint {
+    // "2024-10"
+    pub period: String,
+    pub added: usize,
+    pub thumbs_up: usize,
+    pub thumbs_down: usize,
+}
+
+// The shape of a user's taste. The distributions cover every distinct title they've kept, which
+// leaves out thumbs down and skipped; those only count towards the up/down figures.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct CollectionStats {
+    pub total_entries: usize,
+    pub by_media_type: Vec<StatCount>,
+    pub genres: Vec<StatCount>,
+    pub decades: Vec<StatCount>,
+    pub languages: Vec<StatCount>,
+    pub average_vote: Option<f64>,
+    pub thumbs_up: usize,
+    pub thumbs_down: usize,
+    pub up_down_ratio: Option<f64>,
+    pub trends: Vec<TrendPoint>,
+}
+
+// Most common first, then alphabetically.
+fn ranked_counts(counts: HashMap<String, usize>) -> Vec<StatCount> {
+    let mut out: Vec<StatCount> = counts
+        .into_iter()
+        .map(|(label, count)| StatCount { label, count })
+        .collect();
+    out.sort_by(|a, b| b.count.cmp(&a.count).then(a.label.cmp(&b.label)));
+    out
+}
+
+pub fn collection_stats(collections: &[UserCollection]) -> CollectionStats {
+    // Smart collections only repeat entries from the others.
+    let stored: Vec<&UserCollection> = collections.iter().filter(|uc| !uc.is_smart()).collect();
+
+    let mut seen = HashSet::new();
+    let kept: Vec<&Media> = stored
+        .iter()
+        .filter(|uc| {
+            !uc.is_special(SpecialCollection::ThumbsDown)
+                && !uc.is_special(SpecialCollection::Skipped)
+        })
+        .flat_map(|uc| uc.collection.entries.iter())
+        .filter(|media| seen.insert(media.identity()))
+        .collect();
+
+    let mut by_media_type = HashMap::new();
+    let mut genres = HashMap::new();
+    let mut decades: BTreeMap<i32, usize> = BTreeMap::new();
+    let mut languages = HashMap::new();
+    let mut votes = vec![];
+
+    for media in &kept {
+        *by_media_type.entry(media.kind().to_string()).or_default() += 1;
+        for id in media.genre_ids() {
+            let genre = genre_name(*id).map_or_else(|| format!("Genre {id}"), String::from);
+            *genres.entry(genre).or_default() += 1;
+        }
+        if let Some(year) = media.year().filter(|y| *y > 0) {
+            *decades.entry(year - year % 10).or_default() += 1;
+        }
+        if let Some(lang) = media.language().filter(|l| !l.is_empty()) {
+            *languages.entry(lang.to_lowercase()).or_default() += 1;
+        }
+        if let Some(vote) = media.vote_average() {
+            votes.push(vote);
+        }
+    }
+
+    let count_special = |special: SpecialCollection| -> usize {
+        stored
+            .iter()
+            .filter(|uc| uc.is_special(special))
+            .map(|uc| uc.collection.entries.len())
+            .sum()
+    };
+    let thumbs_up = count_special(SpecialCollection::ThumbsUp);
+    let thumbs_down = count_special(SpecialCollection::ThumbsDown);
+
+    let mut trends: BTreeMap<String, TrendPoint> = BTreeMap::new();
+    for uc in &stored {
+        for media in &uc.collection.entries {
+            let Some(added_at) = media.entry_meta().and_then(|m| m.added_at) else {
+                continue;
+            };
+            let period = added_at.format("%Y-%m").to_string();
+            let point = trends.entry(period.clone()).or_insert_with(|| TrendPoint {
+                period,
+                added: 0,
+                thumbs_up: 0,
+                thumbs_down: 0,
+            });
+            point.added += 1;
+            if uc.is_special(SpecialCollection::ThumbsUp) {
+                point.thumbs_up += 1;
+            } else if uc.is_special(SpecialCollection::ThumbsDown) {
+                point.thumbs_down += 1;
+            }
+        }
+    }
+
+    CollectionStats {
+        total_entries: kept.len(),
+        by_media_type: ranked_counts(by_media_type),
+        genres: ranked_counts(genres),
+        decades: decades
+            .into_iter()
+            .map(|(decade, count)| StatCount {
+                label: format!("{decade}s"),
+                count,
+            })
+            .collect(),
+        languages: ranked_counts(languages),
+        average_vote: (!votes.is_empty()).then(|| votes.iter().sum::<f64>() / votes.len() as f64),
+        thumbs_up,
+        thumbs_down,
+        up_down_ratio: (thumbs_down > 0).then(|| thumbs_up as f64 / thumbs_down as f64),
+        trends: trends.into_values().collect(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatsResponse {
+    pub status: String,
+    pub data: CollectionStats,
+}
@@ -0,0 +1,231 @@
+use crate::model::collections::{Media, MediaCollection, UserCollection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::mem;
+
+// A normalised set of tags: trimmed, lowercase, single-spaced and without duplicates, so that
+// "Horror", " horror " and "HORROR" are all the same tag. Stored as a plain JSON array of strings.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(from = "Vec<String>", into = "Vec<String>")]
+pub struct Tags(Vec<String>);
+
+impl Tags {
+    pub fn normalize(tag: &str) -> String {
+        tag.split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .to_lowercase()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.contains(&Tags::normalize(tag))
+    }
+
+    pub fn insert(&mut self, tag: &str) -> bool {
+        let tag = Tags::normalize(tag);
+        if tag.is_empty() || self.0.contains(&tag) {
+            return false;
+        }
+        self.0.push(tag);
+        true
+    }
+
+    pub fn remove(&mut self, tag: &str) -> bool {
+        let tag = Tags::normalize(tag);
+        let before = self.0.len();
+        self.0.retain(|t| *t != tag);
+        before != self.0.len()
+    }
+
+    // Returns whether anything changed. Renaming onto an existing tag merges the two.
+    pub fn rename(&mut self, from: &str, to: &str) -> bool {
+        match self.remove(from) {
+            true => {
+                self.insert(to);
+                true
+            }
+            false => false,
+        }
+    }
+}
+
+impl From<Vec<String>> for Tags {
+    fn from(tags: Vec<String>) -> Self {
+        let mut out = Tags::default();
+        tags.iter().for_each(|t| {
+            out.insert(t);
+        });
+        out
+    }
+}
+
+impl From<Tags> for Vec<String> {
+    fn from(tags: Tags) -> Self {
+        tags.0
+    }
+}
+
+// The column is nullable and older rows may hold anything, so be forgiving.
+impl From<Option<Value>> for Tags {
+    fn from(value: Option<Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+            .map(Tags::from)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TagUsage {
+    pub tag: String,
+    pub collections: usize,
+    pub entries: usize,
+}
+
+impl TagUsage {
+    pub fn total(&self) -> usize {
+        self.collections + self.entries
+    }
+}
+
+// How often each tag is used on collections and on entries. Smart collections' entries are
+// copies of other collections' entries, so they aren't counted twice.
+pub fn tag_usage(collections: &[UserCollection]) -> Vec<TagUsage> {
+    let mut usage: BTreeMap<String, TagUsage> = BTreeMap::new();
+    let mut count = |tag: &String, on_collection: bool| {
+        let entry = usage.entry(tag.to_owned()).or_insert_with(|| TagUsage {
+            tag: tag.to_owned(),
+            collections: 0,
+            entries: 0,
+        });
+        match on_collection {
+            true => entry.collections += 1,
+            false => entry.entries += 1,
+        }
+    };
+
+    for uc in collections {
+        uc.tags.iter().for_each(|t| count(t, true));
+        if uc.is_smart() {
+            continue;
+        }
+        uc.collection
+            .entries
+            .iter()
+            .filter_map(|media| media.entry_meta())
+            .flat_map(|meta| meta.tags.iter())
+            .for_each(|t| count(t, false));
+    }
+
+    usage.into_values().collect()
+}
+
+// Tags starting with `prefix`, most used first, then anything else containing it.
+pub fn autocomplete_tags(
+    collections: &[UserCollection],
+    prefix: &str,
+    limit: usize,
+) -> Vec<String> {
+    let prefix = Tags::normalize(prefix);
+    let mut matches: Vec<TagUsage> = tag_usage(collections)
+        .into_iter()
+        .filter(|u| u.tag.contains(&prefix))
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.tag
+            .starts_with(&prefix)
+            .cmp(&a.tag.starts_with(&prefix))
+            .then(b.total().cmp(&a.total()))
+            .then(a.tag.cmp(&b.tag))
+    });
+
+    matches.into_iter().take(limit).map(|u| u.tag).collect()
+}
+
+// The user's own tags, for the AI prompts, ignoring the special collections which get their own
+// lines. "horror: Alien (1979), The Thing (1982); comfort watch: Paddington 2 (2017)"
+pub fn extract_tagged_entries(collections: &[UserCollection], media_type: &Media) -> String {
+    let tagged: Vec<&UserCollection> = collections
+        .iter()
+        .filter(|uc| uc.special.is_none() && !uc.is_smart())
+        .collect();
+    let mut by_tag: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+
+    for uc in &tagged {
+        for media in uc
+            .collection
+            .entries
+            .iter()
+            .filter(|media| mem::discriminant(*media) == mem::discriminant(media_type))
+        {
+            let identity = media.identity();
+            let mut tags = media
+                .entry_meta()
+                .map(|m| m.tags.clone())
+                .unwrap_or_default();
+            uc.tags.iter().for_each(|t| {
+                tags.insert(t);
+            });
+
+            for tag in tags.iter() {
+                if seen.insert((tag.to_owned(), identity.clone())) {
+                    by_tag
+                        .entry(tag.to_owned())
+                        .or_default()
+                        .push(media.to_string());
+                }
+            }
+        }
+    }
+
+    by_tag
+        .iter()
+        .map(|(tag, titles)| format!("{tag}: {}", titles.join(", ")))
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UpdateTagsSchema {
+    pub tags: Tags,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RenameTagSchema {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TagsData {
+    pub tags: Vec<TagUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TagsResponse {
+    pub status: String,
+    pub data: TagsData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TagAutocompleteResponse {
+    pub status: String,
+    pub data: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TaggedEntriesResponse {
+    pub status: String,
+    pub data: MediaCollection,
+}
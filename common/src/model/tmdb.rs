@@ -53,3 +53,75 @@ pub struct TvSearchResult {
     pub vote_count: i64,
     pub baing_meta: Option<DiscoveryMeta>,
 }
+
+// The parts of /movie/{id} we use.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MovieDetailsResult {
+    pub id: i64,
+    pub runtime: Option<u32>,
+}
+
+// The parts of /tv/{id} we use.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TvDetailsResult {
+    pub id: i64,
+    #[serde(default)]
+    pub episode_run_time: Vec<u32>,
+    #[serde(default)]
+    pub seasons: Vec<TvSeasonSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TvSeasonSummary {
+    pub season_number: u32,
+    pub episode_count: u32,
+}
+
+// The parts of /tv/{id}/season/{n} we use.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TvSeasonResult {
+    pub season_number: u32,
+    pub episodes: Vec<TvEpisodeResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TvEpisodeResult {
+    pub episode_number: u32,
+    pub name: String,
+    pub runtime: Option<u32>,
+    pub air_date: Option<String>,
+}
+
+// TMDB's genre ids, movies and TV together. The lists overlap and never disagree on a name.
+pub fn genre_name(id: i64) -> Option<&'static str> {
+    match id {
+        12 => Some("Adventure"),
+        14 => Some("Fantasy"),
+        16 => Some("Animation"),
+        18 => Some("Drama"),
+        27 => Some("Horror"),
+        28 => Some("Action"),
+        35 => Some("Comedy"),
+        36 => Some("History"),
+        37 => Some("Western"),
+        53 => Some("Thriller"),
+        80 => Some("Crime"),
+        99 => Some("Documentary"),
+        878 => Some("Science Fiction"),
+        9648 => Some("Mystery"),
+        10402 => Some("Music"),
+        10749 => Some("Romance"),
+        10751 => Some("Family"),
+        10752 => Some("War"),
+        10759 => Some("Action & Adventure"),
+        10762 => Some("Kids"),
+        10763 => Some("News"),
+        10764 => Some("Reality"),
+        10765 => Some("Sci-Fi & Fantasy"),
+        10766 => Some("Soap"),
+        10767 => Some("Talk"),
+        10768 => Some("War & Politics"),
+        10770 => Some("TV Movie"),
+        _ => None,
+    }
+}
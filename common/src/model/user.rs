@@ -41,6 +41,18 @@ pub struct UserUpdateData {
     pub tmdb_api_key: String,
 }
 
+// The secret that goes in the user's calendar subscription URLs.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CalendarTokenData {
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CalendarTokenResponse {
+    pub status: String,
+    pub data: CalendarTokenData,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UserLoginResponse {
     pub status: String,
@@ -1,13 +1,40 @@
 use crate::api::API_ROOT;
 use common::model::collections::{
-    UserCollection, UserCollectionPatchResponse, UserCollectionResponse,
+    BulkEntriesAction, BulkEntriesSchema, MoveEntrySchema, ShareType, UpdateEntryMetaSchema,
+    UpdateRankedSchema, UpdateSharingSchema, UserCollection, UserCollectionPatchResponse,
+    UserCollectionResponse,
 };
+use common::model::core::EntryMeta;
+use common::model::export::ExportFormat;
+use common::model::search::{SearchHit, SearchResponse};
+use common::model::stats::{CollectionStats, StatsResponse};
 use common::model::user::ErrorResponse;
 use gloo::console::console;
 use reqwasm::http;
+use uuid::Uuid;
 
-pub async fn api_get_user_collections() -> Result<Vec<UserCollection>, String> {
-    let response = match http::Request::get(&format!("{API_ROOT}/collections"))
+// Exports are plain downloads, so we hand back a link rather than fetching the body.
+pub fn api_collection_export_url(collection_id: Option<Uuid>, format: ExportFormat) -> String {
+    let format = serde_json::to_value(format)
+        .ok()
+        .and_then(|f| f.as_str().map(String::from))
+        .unwrap_or_default();
+
+    match collection_id {
+        Some(id) => format!("{API_ROOT}/collection/{id}/export?format={format}"),
+        None => format!("{API_ROOT}/collections/export?format={format}"),
+    }
+}
+
+pub async fn api_get_user_collections(tag: Option<String>) -> Result<Vec<UserCollection>, String> {
+    let url = match tag {
+        Some(tag) => format!(
+            "{API_ROOT}/collections?tag={}",
+            js_sys::encode_uri_component(&tag)
+        ),
+        None => format!("{API_ROOT}/collections"),
+    };
+    let response = match http::Request::get(&url)
         .credentials(http::RequestCredentials::Include)
         .send()
         .await
@@ -35,6 +62,39 @@ pub async fn api_get_user_collections() -> Result<Vec<UserCollection>, String> {
     }
 }
 
+pub async fn api_search_collections(q: &str) -> Result<Vec<SearchHit>, String> {
+    let url = format!(
+        "{API_ROOT}/collections/search?q={}",
+        js_sys::encode_uri_component(q)
+    );
+    let response = match http::Request::get(&url)
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<SearchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.results),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
 pub async fn api_patch_user_collection(
     user_collection: UserCollection,
 ) -> Result<UserCollection, String> {
@@ -72,3 +132,317 @@ pub async fn api_patch_user_collection(
         }
     }
 }
+
+pub async fn api_get_shared_collection(collection_id: &str) -> Result<UserCollection, String> {
+    let response =
+        match http::Request::get(&format!("{API_ROOT}/shared/collection/{collection_id}"))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_copy_collection(collection_id: Uuid) -> Result<UserCollection, String> {
+    let response = match http::Request::post(&format!("{API_ROOT}/collection/{collection_id}/copy"))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_patch_collection_sharing(
+    collection_id: Uuid,
+    sharing: ShareType,
+) -> Result<UserCollection, String> {
+    let body = serde_json::to_string(&UpdateSharingSchema { sharing })
+        .expect("Error Serializing Sharing into JSON payload");
+
+    let response =
+        match http::Request::patch(&format!("{API_ROOT}/collection/{collection_id}/sharing"))
+            .credentials(http::RequestCredentials::Include)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_patch_collection_entry_meta(
+    collection_id: Uuid,
+    entry: String,
+    meta: EntryMeta,
+) -> Result<UserCollection, String> {
+    let body = serde_json::to_string(&UpdateEntryMetaSchema { entry, meta })
+        .expect("Error Serializing Entry Meta into JSON payload");
+
+    let response =
+        match http::Request::patch(&format!("{API_ROOT}/collection/{collection_id}/entry"))
+            .credentials(http::RequestCredentials::Include)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+// Returns every collection the action touched: the one the entries came from, then any target.
+pub async fn api_bulk_collection_entries(
+    collection_id: Uuid,
+    entries: Vec<String>,
+    action: BulkEntriesAction,
+) -> Result<Vec<UserCollection>, String> {
+    let body = serde_json::to_string(&BulkEntriesSchema { entries, action })
+        .expect("Error Serializing Bulk Action into JSON payload");
+
+    let response = match http::Request::post(&format!(
+        "{API_ROOT}/collection/{collection_id}/entries/bulk"
+    ))
+    .credentials(http::RequestCredentials::Include)
+    .header("Content-Type", "application/json")
+    .body(body)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.collections),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_patch_collection_ranked(
+    collection_id: Uuid,
+    ranked: bool,
+) -> Result<UserCollection, String> {
+    let body = serde_json::to_string(&UpdateRankedSchema { ranked })
+        .expect("Error Serializing Ranked Flag into JSON payload");
+
+    let response =
+        match http::Request::patch(&format!("{API_ROOT}/collection/{collection_id}/ranked"))
+            .credentials(http::RequestCredentials::Include)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_post_collection_details(collection_id: Uuid) -> Result<UserCollection, String> {
+    let response =
+        match http::Request::post(&format!("{API_ROOT}/collection/{collection_id}/details"))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_move_collection_entry(
+    collection_id: Uuid,
+    entry: String,
+    position: usize,
+) -> Result<UserCollection, String> {
+    let body = serde_json::to_string(&MoveEntrySchema { entry, position })
+        .expect("Error Serializing Entry Move into JSON payload");
+
+    let response = match http::Request::post(&format!(
+        "{API_ROOT}/collection/{collection_id}/entries/move"
+    ))
+    .credentials(http::RequestCredentials::Include)
+    .header("Content-Type", "application/json")
+    .body(body)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_get_collection_stats() -> Result<CollectionStats, String> {
+    let response = match http::Request::get(&format!("{API_ROOT}/collections/stats"))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<StatsResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
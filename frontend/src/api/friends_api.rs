@@ -0,0 +1,166 @@
+use crate::api::API_ROOT;
+use common::model::collections::{UserCollection, UserCollectionResponse};
+use common::model::friends::{
+    Friend, FriendRequestSchema, FriendsResponse, Friendship, FriendshipResponse,
+};
+use common::model::user::ErrorResponse;
+use gloo::console::console;
+use reqwasm::http;
+use uuid::Uuid;
+
+async fn api_get_friends_list(path: &str) -> Result<Vec<Friend>, String> {
+    let response = match http::Request::get(&format!("{API_ROOT}{path}"))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<FriendsResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.friends),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_get_friends() -> Result<Vec<Friend>, String> {
+    api_get_friends_list("/friends").await
+}
+
+pub async fn api_get_friend_requests() -> Result<Vec<Friend>, String> {
+    api_get_friends_list("/friends/requests").await
+}
+
+pub async fn api_get_blocked() -> Result<Vec<Friend>, String> {
+    api_get_friends_list("/friends/blocked").await
+}
+
+async fn api_post_friendship(path: &str) -> Result<Friendship, String> {
+    api_friendship_request(
+        http::Request::post(&format!("{API_ROOT}{path}"))
+            .credentials(http::RequestCredentials::Include),
+    )
+    .await
+}
+
+async fn api_friendship_request(request: http::Request) -> Result<Friendship, String> {
+    let response = match request.send().await {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<FriendshipResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+// The server answers the same way whether or not anyone has the email, so there's nothing to
+// return beyond success.
+pub async fn api_send_friend_request(email: String) -> Result<(), String> {
+    let body = serde_json::to_string(&FriendRequestSchema { email })
+        .expect("Error Serializing Friend Request into JSON payload");
+
+    let response = match http::Request::post(&format!("{API_ROOT}/friends/requests"))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(body)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    Ok(())
+}
+
+pub async fn api_accept_friend_request(friendship_id: Uuid) -> Result<Friendship, String> {
+    api_post_friendship(&format!("/friends/requests/{friendship_id}/accept")).await
+}
+
+pub async fn api_decline_friend_request(friendship_id: Uuid) -> Result<Friendship, String> {
+    api_post_friendship(&format!("/friends/requests/{friendship_id}/decline")).await
+}
+
+pub async fn api_block_friend(friendship_id: Uuid) -> Result<Friendship, String> {
+    api_post_friendship(&format!("/friends/requests/{friendship_id}/block")).await
+}
+
+pub async fn api_unblock_friend(friendship_id: Uuid) -> Result<Friendship, String> {
+    api_post_friendship(&format!("/friends/requests/{friendship_id}/unblock")).await
+}
+
+pub async fn api_unfriend(friendship_id: Uuid) -> Result<Friendship, String> {
+    api_friendship_request(
+        http::Request::delete(&format!("{API_ROOT}/friends/{friendship_id}"))
+            .credentials(http::RequestCredentials::Include),
+    )
+    .await
+}
+
+pub async fn api_get_friend_collections(user_id: Uuid) -> Result<Vec<UserCollection>, String> {
+    let response =
+        match http::Request::get(&format!("{API_ROOT}/shared/user/{user_id}/collections"))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.collections),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
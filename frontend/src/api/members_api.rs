@@ -0,0 +1,116 @@
+use crate::api::API_ROOT;
+use common::model::members::{
+    CollectionMember, CollectionRole, InviteMemberSchema, MembersResponse, UpdateMemberSchema,
+};
+use common::model::user::ErrorResponse;
+use gloo::console::console;
+use reqwasm::http;
+use uuid::Uuid;
+
+// Members endpoints other than invites answer with the (updated) list of members.
+async fn api_members_request(request: http::Request) -> Result<Vec<CollectionMember>, String> {
+    let response = match request
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<MembersResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.members),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+// The current user's roles on other people's collections.
+pub async fn api_get_memberships() -> Result<Vec<CollectionMember>, String> {
+    api_members_request(http::Request::get(&format!(
+        "{API_ROOT}/collections/memberships"
+    )))
+    .await
+}
+
+pub async fn api_get_collection_members(
+    collection_id: Uuid,
+) -> Result<Vec<CollectionMember>, String> {
+    api_members_request(http::Request::get(&format!(
+        "{API_ROOT}/collection/{collection_id}/members"
+    )))
+    .await
+}
+
+// Answers the same whether or not anyone has the email, so there's nothing to return.
+pub async fn api_invite_collection_member(
+    collection_id: Uuid,
+    email: String,
+    role: CollectionRole,
+) -> Result<(), String> {
+    let body = serde_json::to_string(&InviteMemberSchema { email, role })
+        .expect("Error Serializing Invite into JSON payload");
+
+    let response =
+        match http::Request::post(&format!("{API_ROOT}/collection/{collection_id}/members"))
+            .header("Content-Type", "application/json")
+            .credentials(http::RequestCredentials::Include)
+            .body(body)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    Ok(())
+}
+
+pub async fn api_update_collection_member(
+    collection_id: Uuid,
+    user_id: Uuid,
+    role: CollectionRole,
+) -> Result<Vec<CollectionMember>, String> {
+    let body = serde_json::to_string(&UpdateMemberSchema { role })
+        .expect("Error Serializing Role into JSON payload");
+
+    api_members_request(
+        http::Request::patch(&format!(
+            "{API_ROOT}/collection/{collection_id}/members/{user_id}"
+        ))
+        .header("Content-Type", "application/json")
+        .body(body),
+    )
+    .await
+}
+
+pub async fn api_remove_collection_member(
+    collection_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<CollectionMember>, String> {
+    api_members_request(http::Request::delete(&format!(
+        "{API_ROOT}/collection/{collection_id}/members/{user_id}"
+    )))
+    .await
+}
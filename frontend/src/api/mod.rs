@@ -8,6 +8,13 @@ use std::error;
 
 pub mod collections_api;
 pub mod discovery_api;
+pub mod friends_api;
+pub mod members_api;
+pub mod progress_api;
+pub mod revisions_api;
+pub mod schedules_api;
+pub mod starter_packs_api;
+pub mod tags_api;
 pub mod tmdb_api;
 pub mod user_api;
 mod youtube_api;
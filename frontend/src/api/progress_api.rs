@@ -0,0 +1,70 @@
+use crate::api::API_ROOT;
+use common::model::progress::{
+    MarkWatchedSchema, ProgressListResponse, ProgressResponse, ProgressSummary,
+};
+use common::model::user::ErrorResponse;
+use gloo::console::console;
+use reqwasm::http;
+
+pub async fn api_get_progress() -> Result<Vec<ProgressSummary>, String> {
+    let response = match http::Request::get(&format!("{API_ROOT}/progress"))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<ProgressListResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.progress),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_mark_watched(mark: MarkWatchedSchema) -> Result<ProgressSummary, String> {
+    let body =
+        serde_json::to_string(&mark).expect("Error Serializing Watched Mark into JSON payload");
+
+    let response = match http::Request::post(&format!("{API_ROOT}/progress/watched"))
+        .credentials(http::RequestCredentials::Include)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<ProgressResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
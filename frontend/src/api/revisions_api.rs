@@ -0,0 +1,82 @@
+use crate::api::API_ROOT;
+use common::model::collections::{UserCollection, UserCollectionPatchResponse};
+use common::model::revisions::{CollectionRevision, RevisionsResponse};
+use common::model::user::ErrorResponse;
+use gloo::console::console;
+use reqwasm::http;
+use uuid::Uuid;
+
+pub async fn api_get_collection_revisions(
+    collection_id: Uuid,
+) -> Result<Vec<CollectionRevision>, String> {
+    let response =
+        match http::Request::get(&format!("{API_ROOT}/collection/{collection_id}/revisions"))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<RevisionsResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.revisions),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+async fn api_post_collection_restore(path: &str) -> Result<UserCollection, String> {
+    let response = match http::Request::post(&format!("{API_ROOT}{path}"))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_restore_collection_revision(
+    collection_id: Uuid,
+    revision_id: Uuid,
+) -> Result<UserCollection, String> {
+    api_post_collection_restore(&format!(
+        "/collection/{collection_id}/revisions/{revision_id}/restore"
+    ))
+    .await
+}
+
+pub async fn api_undo_collection_change(collection_id: Uuid) -> Result<UserCollection, String> {
+    api_post_collection_restore(&format!("/collection/{collection_id}/undo")).await
+}
@@ -0,0 +1,392 @@
+use crate::api::API_ROOT;
+use common::model::channel::{ChannelGuide, ChannelGuideResponse, NewChannelSchema};
+use common::model::playlist::{StreamUrls, StreamUrlsData, StreamUrlsResponse};
+use common::model::schedule_generator::{
+    GenerateScheduleSchema, GeneratedSchedule, GeneratedScheduleResponse,
+};
+use common::model::schedule_recurrence::{
+    NewRecurringScheduleSchema, Recurrence, RecurrenceData, RecurrenceResponse,
+};
+use common::model::schedule_reflow::{ReflowSchema, ReflowedSchedule, ReflowedScheduleResponse};
+use common::model::schedule_themes::ThemedScheduleSchema;
+use common::model::schedules::{
+    NewScheduleSchema, Schedule, ScheduleResponse, SchedulesResponse, UpdateScheduleSchema,
+};
+use common::model::user::ErrorResponse;
+use gloo::console::console;
+use reqwasm::http;
+use uuid::Uuid;
+
+// With a calendar token the schedule's feeds work without logging in, for subscribing.
+fn schedule_feed_url(schedule_id: Uuid, file: &str, token: Option<&str>) -> String {
+    match token {
+        Some(token) => format!("{API_ROOT}/schedule/{schedule_id}/{file}?token={token}"),
+        None => format!("{API_ROOT}/schedule/{schedule_id}/{file}"),
+    }
+}
+
+pub fn api_schedule_calendar_url(schedule_id: Uuid, token: Option<&str>) -> String {
+    schedule_feed_url(schedule_id, "calendar.ics", token)
+}
+
+pub fn api_schedule_guide_url(schedule_id: Uuid, token: Option<&str>) -> String {
+    schedule_feed_url(schedule_id, "guide.xml", token)
+}
+
+pub fn api_schedule_playlist_url(schedule_id: Uuid, token: Option<&str>) -> String {
+    schedule_feed_url(schedule_id, "playlist.m3u", token)
+}
+
+pub async fn api_get_schedules() -> Result<Vec<Schedule>, String> {
+    let response = match http::Request::get(&format!("{API_ROOT}/schedules"))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<SchedulesResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.schedules),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+// Every single-schedule endpoint answers with the schedule as it now is.
+async fn api_schedule_request(request: http::Request) -> Result<Schedule, String> {
+    let response = match request
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<ScheduleResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_get_schedule(schedule_id: Uuid) -> Result<Schedule, String> {
+    api_schedule_request(http::Request::get(&format!(
+        "{API_ROOT}/schedule/{schedule_id}"
+    )))
+    .await
+}
+
+pub async fn api_create_schedule(new_schedule: NewScheduleSchema) -> Result<Schedule, String> {
+    let body =
+        serde_json::to_string(&new_schedule).expect("Error Serializing Schedule into JSON payload");
+
+    api_schedule_request(
+        http::Request::post(&format!("{API_ROOT}/schedules"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
+
+pub async fn api_update_schedule(
+    schedule_id: Uuid,
+    update: UpdateScheduleSchema,
+) -> Result<Schedule, String> {
+    let body =
+        serde_json::to_string(&update).expect("Error Serializing Schedule into JSON payload");
+
+    api_schedule_request(
+        http::Request::patch(&format!("{API_ROOT}/schedule/{schedule_id}"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
+
+pub async fn api_delete_schedule(schedule_id: Uuid) -> Result<Schedule, String> {
+    api_schedule_request(http::Request::delete(&format!(
+        "{API_ROOT}/schedule/{schedule_id}"
+    )))
+    .await
+}
+
+async fn api_generated_schedule_request(
+    request: http::Request,
+) -> Result<GeneratedSchedule, String> {
+    let response = match request
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<GeneratedScheduleResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_generate_schedule(
+    generate: GenerateScheduleSchema,
+) -> Result<GeneratedSchedule, String> {
+    let body = serde_json::to_string(&generate)
+        .expect("Error Serializing Schedule Options into JSON payload");
+
+    api_generated_schedule_request(
+        http::Request::post(&format!("{API_ROOT}/schedules/generate"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
+
+pub async fn api_generate_themed_schedule(
+    themed: ThemedScheduleSchema,
+) -> Result<GeneratedSchedule, String> {
+    let body =
+        serde_json::to_string(&themed).expect("Error Serializing Schedule Theme into JSON payload");
+
+    api_generated_schedule_request(
+        http::Request::post(&format!("{API_ROOT}/schedules/themed"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
+
+pub async fn api_create_channel(new_channel: NewChannelSchema) -> Result<Schedule, String> {
+    let body =
+        serde_json::to_string(&new_channel).expect("Error Serializing Channel into JSON payload");
+
+    api_schedule_request(
+        http::Request::post(&format!("{API_ROOT}/channels"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
+
+pub async fn api_create_recurring_schedule(
+    new_schedule: NewRecurringScheduleSchema,
+) -> Result<Schedule, String> {
+    let body =
+        serde_json::to_string(&new_schedule).expect("Error Serializing Schedule into JSON payload");
+
+    api_schedule_request(
+        http::Request::post(&format!("{API_ROOT}/schedules/recurring"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
+
+pub async fn api_get_recurrence(schedule_id: Uuid) -> Result<RecurrenceData, String> {
+    let response =
+        match http::Request::get(&format!("{API_ROOT}/schedule/{schedule_id}/recurrence"))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<RecurrenceResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_save_recurrence(
+    schedule_id: Uuid,
+    recurrence: Recurrence,
+) -> Result<Schedule, String> {
+    let body =
+        serde_json::to_string(&recurrence).expect("Error Serializing Recurrence into JSON payload");
+
+    api_schedule_request(
+        http::Request::post(&format!("{API_ROOT}/schedule/{schedule_id}/recurrence"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
+
+pub async fn api_stop_recurrence(schedule_id: Uuid) -> Result<Schedule, String> {
+    api_schedule_request(http::Request::delete(&format!(
+        "{API_ROOT}/schedule/{schedule_id}/recurrence"
+    )))
+    .await
+}
+
+pub async fn api_get_channel_now(channel_id: Uuid, next: usize) -> Result<ChannelGuide, String> {
+    let response =
+        match http::Request::get(&format!("{API_ROOT}/channel/{channel_id}/now?next={next}"))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<ChannelGuideResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_reflow_schedule(
+    schedule_id: Uuid,
+    reflow: ReflowSchema,
+) -> Result<ReflowedSchedule, String> {
+    let body = serde_json::to_string(&reflow).expect("Error Serializing Reflow into JSON payload");
+
+    let response = match http::Request::post(&format!("{API_ROOT}/schedule/{schedule_id}/reflow"))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(body)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<ReflowedScheduleResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+async fn api_stream_urls_request(request: http::Request) -> Result<StreamUrlsData, String> {
+    let response = match request
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<StreamUrlsResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_get_stream_urls(schedule_id: Uuid) -> Result<StreamUrlsData, String> {
+    api_stream_urls_request(http::Request::get(&format!(
+        "{API_ROOT}/schedule/{schedule_id}/stream-urls"
+    )))
+    .await
+}
+
+pub async fn api_save_stream_urls(
+    schedule_id: Uuid,
+    urls: StreamUrls,
+) -> Result<StreamUrlsData, String> {
+    let body =
+        serde_json::to_string(&urls).expect("Error Serializing Stream URLs into JSON payload");
+
+    api_stream_urls_request(
+        http::Request::post(&format!("{API_ROOT}/schedule/{schedule_id}/stream-urls"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
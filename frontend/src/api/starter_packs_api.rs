@@ -0,0 +1,78 @@
+use crate::api::API_ROOT;
+use common::model::collections::{Media, UserCollection, UserCollectionResponse};
+use common::model::starter_packs::{ApplyStarterPackSchema, StarterPack, StarterPacksResponse};
+use common::model::user::ErrorResponse;
+use gloo::console::console;
+use reqwasm::http;
+use uuid::Uuid;
+
+pub async fn api_get_starter_packs() -> Result<Vec<StarterPack>, String> {
+    let response = match http::Request::get(&format!("{API_ROOT}/starter-packs"))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<StarterPacksResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.packs),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+// Returns the special collections that changed.
+pub async fn api_apply_starter_pack(
+    pack_id: Uuid,
+    thumbs_up: Vec<Media>,
+    thumbs_down: Vec<Media>,
+) -> Result<Vec<UserCollection>, String> {
+    let body = serde_json::to_string(&ApplyStarterPackSchema {
+        thumbs_up,
+        thumbs_down,
+    })
+    .expect("Error Serializing Starter Pack Ratings into JSON payload");
+
+    let response = match http::Request::post(&format!("{API_ROOT}/starter-packs/{pack_id}/apply"))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(body)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.collections),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::api::API_ROOT;
+use common::model::collections::{Media, UserCollection, UserCollectionPatchResponse};
+use common::model::tags::{
+    RenameTagSchema, TagAutocompleteResponse, TagUsage, TaggedEntriesResponse, Tags, TagsResponse,
+    UpdateTagsSchema,
+};
+use common::model::user::ErrorResponse;
+use gloo::console::console;
+use js_sys::encode_uri_component;
+use reqwasm::http;
+use uuid::Uuid;
+
+async fn api_tag_usage(request: http::Request) -> Result<Vec<TagUsage>, String> {
+    let response = match request
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<TagsResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.tags),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_get_tags() -> Result<Vec<TagUsage>, String> {
+    api_tag_usage(http::Request::get(&format!("{API_ROOT}/tags"))).await
+}
+
+pub async fn api_rename_tag(from: String, to: String) -> Result<Vec<TagUsage>, String> {
+    let body = serde_json::to_string(&RenameTagSchema { from, to })
+        .expect("Error Serializing Tag Rename into JSON payload");
+
+    api_tag_usage(
+        http::Request::post(&format!("{API_ROOT}/tags/rename"))
+            .header("Content-Type", "application/json")
+            .body(body),
+    )
+    .await
+}
+
+pub async fn api_delete_tag(tag: &str) -> Result<Vec<TagUsage>, String> {
+    api_tag_usage(http::Request::delete(&format!(
+        "{API_ROOT}/tags/{}",
+        encode_uri_component(tag)
+    )))
+    .await
+}
+
+pub async fn api_autocomplete_tags(prefix: &str) -> Result<Vec<String>, String> {
+    let response = match http::Request::get(&format!(
+        "{API_ROOT}/tags/autocomplete?q={}",
+        encode_uri_component(prefix)
+    ))
+    .credentials(http::RequestCredentials::Include)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<TagAutocompleteResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_get_tagged_entries(tag: &str) -> Result<Vec<Media>, String> {
+    let response = match http::Request::get(&format!(
+        "{API_ROOT}/tags/{}/entries",
+        encode_uri_component(tag)
+    ))
+    .credentials(http::RequestCredentials::Include)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<TaggedEntriesResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.entries),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
+
+pub async fn api_patch_collection_tags(
+    collection_id: Uuid,
+    tags: Tags,
+) -> Result<UserCollection, String> {
+    let body = serde_json::to_string(&UpdateTagsSchema { tags })
+        .expect("Error Serializing Tags into JSON payload");
+
+    let response =
+        match http::Request::patch(&format!("{API_ROOT}/collection/{collection_id}/tags"))
+            .credentials(http::RequestCredentials::Include)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<UserCollectionPatchResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
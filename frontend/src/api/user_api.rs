@@ -1,6 +1,7 @@
 use crate::api::API_ROOT;
 use common::model::user::{
-    ErrorResponse, User, UserLoginResponse, UserResponse, UserUpdateData, UserUpdateResponse,
+    CalendarTokenResponse, ErrorResponse, User, UserLoginResponse, UserResponse, UserUpdateData,
+    UserUpdateResponse,
 };
 use gloo::console::console;
 use reqwasm::http;
@@ -140,3 +141,38 @@ pub async fn api_logout_user() -> Result<(), String> {
 
     Ok(())
 }
+
+// The secret for calendar subscription URLs. `rotate` replaces it, cutting off the old URLs.
+pub async fn api_calendar_token(rotate: bool) -> Result<String, String> {
+    let url = format!("{API_ROOT}/user/calendar-token");
+    let request = match rotate {
+        true => http::Request::post(&url),
+        false => http::Request::get(&url),
+    };
+    let response = match request
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        return if let Ok(error_response) = error_response {
+            Err(error_response.message)
+        } else {
+            Err(format!("API error: {}", response.status()))
+        };
+    }
+
+    let res_json = response.json::<CalendarTokenResponse>().await;
+    match res_json {
+        Ok(res) => Ok(res.data.token),
+        Err(e) => {
+            console!(format!("Error Parsing Response JSON: {e:?}"));
+            Err("Failed to parse response.".to_string())
+        }
+    }
+}
@@ -2,7 +2,8 @@ use gloo::timers::callback::Timeout;
 use yew::prelude::*;
 use yewdux::prelude::use_store;
 
-use crate::store::{set_hide_alert, Store};
+use crate::api::revisions_api::api_undo_collection_change;
+use crate::store::{set_hide_alert, set_show_alert, set_store_collection, Store};
 
 #[derive(Debug, PartialEq, Properties)]
 pub struct Props {
@@ -36,10 +37,32 @@ pub fn alert_component(props: &Props) -> Html {
         },
     );
 
+    let undo_collection_id = store.alert_input.undo_collection_id;
+    let on_undo = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let dispatch = dispatch.clone();
+            if let Some(collection_id) = undo_collection_id {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_undo_collection_change(collection_id).await {
+                        Ok(collection) => {
+                            set_show_alert(format!("Undone in '{}'", collection.name), &dispatch);
+                            set_store_collection(collection, &dispatch);
+                        }
+                        Err(e) => set_show_alert(e, &dispatch),
+                    }
+                });
+            }
+        })
+    };
+
     html! {
     <div id="baingerToast" class={format!("toast toast-top toast-end {}", if show_alert { "" } else { "hidden" })}>
         <p class="alert alert-info">
             <span>{props.message.clone()}</span>
+            if undo_collection_id.is_some() {
+                <button class="btn btn-sm" onclick={on_undo}>{"Undo"}</button>
+            }
         </p>
     </div>
     }
@@ -0,0 +1,174 @@
+use crate::api::members_api::{
+    api_get_collection_members, api_invite_collection_member, api_remove_collection_member,
+    api_update_collection_member,
+};
+use crate::store::{set_show_alert, Store};
+use crate::ui_helpers::get_value_from_input_event;
+use common::model::members::{CollectionMember, CollectionRole};
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::functional::use_store;
+
+fn select_role(event: Event) -> CollectionRole {
+    let value = event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .map(|s| s.value())
+        .unwrap_or_default();
+
+    CollectionRole::from_name(Some(&value)).unwrap_or(CollectionRole::Viewer)
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct CollectionMembersProps {
+    pub collection_id: Uuid,
+    pub members: Vec<CollectionMember>,
+    // Only the owner manages members, everyone else just sees them (and can leave).
+    pub role: CollectionRole,
+    pub on_change: Callback<Vec<CollectionMember>>,
+}
+
+// Who else can see or edit a collection.
+#[function_component(CollectionMembers)]
+pub fn collection_members(props: &CollectionMembersProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let me = store.auth_user.as_ref().map(|u| u.id);
+    let email = use_state(String::new);
+    let invite_role = use_state(|| CollectionRole::Editor);
+    let is_owner = props.role == CollectionRole::Owner;
+
+    let on_email = {
+        let email = email.clone();
+        Callback::from(move |event: InputEvent| email.set(get_value_from_input_event(event)))
+    };
+
+    let on_invite_role = {
+        let invite_role = invite_role.clone();
+        Callback::from(move |event: Event| invite_role.set(select_role(event)))
+    };
+
+    let on_invite = {
+        let email = email.clone();
+        let invite_role = invite_role.clone();
+        let collection_id = props.collection_id;
+        let on_change = props.on_change.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            if email.trim().is_empty() {
+                return;
+            }
+            let (address, role) = (email.trim().to_string(), *invite_role);
+            let email = email.clone();
+            let on_change = on_change.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = api_invite_collection_member(collection_id, address, role).await {
+                    set_show_alert(e, &dispatch);
+                    return;
+                }
+                email.set(String::new());
+                set_show_alert("Invite sent".to_string(), &dispatch);
+                match api_get_collection_members(collection_id).await {
+                    Ok(members) => on_change.emit(members),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_member_role = |user_id: Uuid| {
+        let collection_id = props.collection_id;
+        let on_change = props.on_change.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |event: Event| {
+            let role = select_role(event);
+            let on_change = on_change.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_update_collection_member(collection_id, user_id, role).await {
+                    Ok(members) => on_change.emit(members),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_remove = |user_id: Uuid| {
+        let collection_id = props.collection_id;
+        let on_change = props.on_change.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let on_change = on_change.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_remove_collection_member(collection_id, user_id).await {
+                    Ok(members) => on_change.emit(members),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let role_options = |selected: CollectionRole| {
+        [CollectionRole::Viewer, CollectionRole::Editor]
+            .iter()
+            .map(|r| {
+                html! {
+                    <option value={r.to_string()} selected={*r == selected}>{r.to_string()}</option>
+                }
+            })
+            .collect::<Html>()
+    };
+
+    html! {
+        <details class="text-xs pt-2 text-left">
+            <summary class="cursor-pointer">{format!("Members ({})", props.members.len())}</summary>
+            <div class="flex flex-col gap-1 pt-1">
+                {
+                    props.members.iter().map(|m| {
+                        let role = m.member_role();
+                        html!{
+                            <div class="flex justify-between items-center gap-2">
+                                <span>{m.name.clone()}</span>
+                                if role == CollectionRole::Owner {
+                                    <span class="badge badge-xs">{"owner"}</span>
+                                } else if is_owner {
+                                    <span class="flex gap-1 items-center">
+                                        <select class="select select-bordered select-xs" onchange={on_member_role(m.user_id)}>
+                                            { role_options(role) }
+                                        </select>
+                                        <button class="btn btn-xs btn-ghost" onclick={on_remove(m.user_id)}>{"✕"}</button>
+                                    </span>
+                                } else if Some(m.user_id) == me {
+                                    <span class="flex gap-1 items-center">
+                                        <span class="badge badge-xs">{role.to_string()}</span>
+                                        <button class="btn btn-xs btn-ghost" onclick={on_remove(m.user_id)}>{"Leave"}</button>
+                                    </span>
+                                } else {
+                                    <span class="badge badge-xs">{role.to_string()}</span>
+                                }
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+                if is_owner {
+                    <div class="join pt-1">
+                        <input
+                            class="input input-bordered input-xs join-item grow"
+                            type="email"
+                            placeholder="Invite by email..."
+                            value={(*email).clone()}
+                            oninput={on_email}
+                        />
+                        <select class="select select-bordered select-xs join-item" onchange={on_invite_role}>
+                            { role_options(*invite_role) }
+                        </select>
+                        <button class="btn btn-xs join-item" onclick={on_invite}>{"Invite"}</button>
+                    </div>
+                }
+            </div>
+        </details>
+    }
+}
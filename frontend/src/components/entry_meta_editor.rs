@@ -0,0 +1,227 @@
+use crate::components::tag_input::TagInput;
+use crate::ui_helpers::get_value_from_input_event;
+use chrono::NaiveDate;
+use common::model::core::{EntryMeta, WatchStatus};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+fn select_value(event: Event) -> String {
+    event
+        .target()
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .map(|s| s.value())
+        .unwrap_or_default()
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct EntryMetaEditorProps {
+    // Unique per card, for the tag suggestions.
+    pub id: AttrValue,
+    pub meta: EntryMeta,
+    pub on_save: Callback<EntryMeta>,
+}
+
+// The user's own bits for a title in a collection: watch status, dates, rating, notes.
+#[function_component(EntryMetaEditor)]
+pub fn entry_meta_editor(props: &EntryMetaEditorProps) -> Html {
+    let draft = use_state(|| props.meta.clone());
+    let new_date: UseStateHandle<Option<NaiveDate>> = use_state(|| None);
+
+    {
+        let draft = draft.clone();
+        use_effect_with(props.meta.clone(), move |meta| {
+            draft.set(meta.clone());
+            || ()
+        });
+    }
+
+    let on_status = {
+        let draft = draft.clone();
+        Callback::from(move |event: Event| {
+            let mut meta = (*draft).clone();
+            meta.personal.watch_status = match select_value(event).as_str() {
+                "in_progress" => WatchStatus::InProgress,
+                "watched" => WatchStatus::Watched,
+                _ => WatchStatus::Unwatched,
+            };
+            draft.set(meta);
+        })
+    };
+
+    let on_rating = {
+        let draft = draft.clone();
+        Callback::from(move |event: Event| {
+            let mut meta = (*draft).clone();
+            meta.personal.rating = select_value(event).parse::<u8>().ok();
+            draft.set(meta);
+        })
+    };
+
+    let on_rewatch = {
+        let draft = draft.clone();
+        Callback::from(move |event: InputEvent| {
+            let mut meta = (*draft).clone();
+            meta.personal.rewatch_count = get_value_from_input_event(event).parse().unwrap_or(0);
+            draft.set(meta);
+        })
+    };
+
+    let on_notes = {
+        let draft = draft.clone();
+        Callback::from(move |event: InputEvent| {
+            let notes = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlTextAreaElement>().ok())
+                .map(|t| t.value())
+                .unwrap_or_default();
+            let mut meta = (*draft).clone();
+            meta.personal.notes = (!notes.trim().is_empty()).then_some(notes);
+            draft.set(meta);
+        })
+    };
+
+    let on_new_date = {
+        let new_date = new_date.clone();
+        Callback::from(move |event: InputEvent| {
+            new_date.set(
+                NaiveDate::parse_from_str(&get_value_from_input_event(event), "%Y-%m-%d").ok(),
+            );
+        })
+    };
+
+    // Logging a date means it's been watched; a second date is a rewatch.
+    let on_add_date = {
+        let draft = draft.clone();
+        let new_date = new_date.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(date) = *new_date {
+                let mut meta = (*draft).clone();
+                if !meta.personal.watched_dates.contains(&date) {
+                    meta.personal.watched_dates.push(date);
+                    meta.personal.watched_dates.sort();
+                    meta.personal.rewatch_count = meta
+                        .personal
+                        .rewatch_count
+                        .max(meta.personal.watched_dates.len() as u32 - 1);
+                }
+                meta.personal.watch_status = WatchStatus::Watched;
+                draft.set(meta);
+            }
+        })
+    };
+
+    let on_remove_date = |date: NaiveDate| {
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut meta = (*draft).clone();
+            meta.personal.watched_dates.retain(|d| *d != date);
+            draft.set(meta);
+        })
+    };
+
+    let on_add_tag = {
+        let draft = draft.clone();
+        Callback::from(move |tag: String| {
+            let mut meta = (*draft).clone();
+            if meta.tags.insert(&tag) {
+                draft.set(meta);
+            }
+        })
+    };
+
+    let on_remove_tag = |tag: String| {
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut meta = (*draft).clone();
+            meta.tags.remove(&tag);
+            draft.set(meta);
+        })
+    };
+
+    let on_save = {
+        let draft = draft.clone();
+        let on_save = props.on_save.clone();
+        Callback::from(move |_: MouseEvent| on_save.emit((*draft).clone()))
+    };
+
+    html! {
+        <details class="collapse collapse-arrow bg-base-300 text-left">
+            <summary class="collapse-title text-sm">{"My notes"}</summary>
+            <div class="collapse-content flex flex-col gap-2 text-sm">
+                <div class="flex gap-2">
+                    <select class="select select-bordered select-xs grow" onchange={on_status}>
+                        {
+                            [WatchStatus::Unwatched, WatchStatus::InProgress, WatchStatus::Watched]
+                            .iter()
+                            .map(|ws| html!{
+                                <option value={ws.to_string()} selected={*ws == draft.personal.watch_status}>
+                                    {ws.to_string().replace('_', " ")}
+                                </option>
+                            })
+                            .collect::<Html>()
+                        }
+                    </select>
+                    <select class="select select-bordered select-xs" onchange={on_rating}>
+                        <option value="" selected={draft.personal.rating.is_none()}>{"No rating"}</option>
+                        {
+                            EntryMeta::RATING_RANGE
+                            .map(|r| html!{
+                                <option value={r.to_string()} selected={draft.personal.rating == Some(r)}>
+                                    {format!("{r}/10")}
+                                </option>
+                            })
+                            .collect::<Html>()
+                        }
+                    </select>
+                </div>
+                <div class="flex gap-2 items-center">
+                    <input type="date" class="input input-bordered input-xs grow" oninput={on_new_date} />
+                    <button class="btn btn-xs" onclick={on_add_date} disabled={new_date.is_none()}>
+                        {"Watched on"}
+                    </button>
+                </div>
+                <div class="flex flex-wrap gap-1">
+                    {
+                        draft.personal.watched_dates.iter().map(|date| html!{
+                            <span class="badge badge-outline gap-1">
+                                {date.to_string()}
+                                <a class="cursor-pointer" onclick={on_remove_date(*date)}>{"✕"}</a>
+                            </span>
+                        }).collect::<Html>()
+                    }
+                </div>
+                <label class="flex gap-2 items-center">
+                    {"Rewatches"}
+                    <input
+                        type="number"
+                        min="0"
+                        class="input input-bordered input-xs w-16"
+                        value={draft.personal.rewatch_count.to_string()}
+                        oninput={on_rewatch}
+                    />
+                </label>
+                <div class="flex flex-wrap gap-1 items-center">
+                    {
+                        draft.tags.iter().map(|tag| html!{
+                            <span class="badge badge-secondary gap-1">
+                                {tag.clone()}
+                                <a class="cursor-pointer" onclick={on_remove_tag(tag.clone())}>{"✕"}</a>
+                            </span>
+                        }).collect::<Html>()
+                    }
+                    <TagInput id={format!("{}_tags", props.id)} on_add={on_add_tag} />
+                </div>
+                <textarea
+                    class="textarea textarea-bordered textarea-xs"
+                    placeholder="Notes"
+                    value={draft.personal.notes.clone().unwrap_or_default()}
+                    oninput={on_notes}
+                />
+                <button class="btn btn-xs btn-primary" onclick={on_save} disabled={*draft == props.meta}>
+                    {"Save"}
+                </button>
+            </div>
+        </details>
+    }
+}
@@ -73,6 +73,21 @@ pub fn header_component() -> Html {
                                         {"Profile"}
                                     </Link<Route>>
                                 </li>
+                                <li>
+                                    <Link<Route> to={Route::FriendsPage} classes="text-ct-dark-600">
+                                        {"Friends"}
+                                    </Link<Route>>
+                                </li>
+                                <li>
+                                    <Link<Route> to={Route::StatsPage} classes="text-ct-dark-600">
+                                        {"Stats"}
+                                    </Link<Route>>
+                                </li>
+                                <li>
+                                    <Link<Route> to={Route::StarterPacksPage} classes="text-ct-dark-600">
+                                        {"Starter Packs"}
+                                    </Link<Route>>
+                                </li>
                                 <li onclick={&handle_logout}>
                                     <a>{"Logout"}</a>
                                 </li>
@@ -83,10 +98,10 @@ pub fn header_component() -> Html {
                     <div class="">
                         <Link<Route> to={Route::CollectionsPage} classes="text-ct-dark-600">{"Collections"}</Link<Route>>
                     </div>
-                    // <div class="divider divider-horizontal"></div>
-                    // <div class="">
-                    //     <Link<Route> to={Route::SchedulesPage} classes="text-ct-dark-600">{"Schedules"}</Link<Route>>
-                    // </div>
+                    <div class="divider divider-horizontal"></div>
+                    <div class="">
+                        <Link<Route> to={Route::SchedulesPage} classes="text-ct-dark-600">{"Schedules"}</Link<Route>>
+                    </div>
                 </div>
             } else {
                 <div class="">
@@ -1,8 +1,11 @@
+use crate::components::entry_meta_editor::EntryMetaEditor;
 use crate::components::figures::{PlayButton, RoboHead};
 use common::model::collections::{IsMedia, Media};
-use common::model::core::{DiscoveryMeta, Movie, OnlineContent, TvShow, TvShowDetails, YTChannel};
+use common::model::core::{
+    DiscoveryMeta, EntryMeta, Movie, OnlineContent, TvShow, TvShowDetails, YTChannel,
+};
 use serde_json::map::Entry::Vacant;
-use yew::{classes, function_component, html, Children, Classes, Html, Properties};
+use yew::{classes, function_component, html, Callback, Children, Classes, Html, Properties};
 
 struct CardData {
     pub title: String,
@@ -18,7 +21,15 @@ impl From<Movie> for CardData {
     fn from(movie: Movie) -> Self {
         CardData {
             title: movie.name,
-            subtitle: format!("({})", movie.year),
+            subtitle: match movie
+                .details
+                .as_ref()
+                .and_then(|d| d.runtime)
+                .filter(|r| *r > 0)
+            {
+                Some(runtime) => format!("({}) · {runtime}m", movie.year),
+                None => format!("({})", movie.year),
+            },
             description: movie.details.as_ref().map(|m| m.overview.to_owned()),
             link: None,
             fig_path: match movie.details {
@@ -37,7 +48,15 @@ impl From<TvShow> for CardData {
     fn from(tv_show: TvShow) -> Self {
         CardData {
             title: tv_show.name,
-            subtitle: format!("({})", tv_show.first_air_date),
+            subtitle: match tv_show.details.as_ref().and_then(|d| d.seasons.as_ref()) {
+                Some(seasons) => format!(
+                    "({}) · {} seasons, {} episodes",
+                    tv_show.first_air_date,
+                    seasons.len(),
+                    seasons.iter().map(|s| s.episodes.len()).sum::<usize>()
+                ),
+                None => format!("({})", tv_show.first_air_date),
+            },
             description: tv_show.details.as_ref().map(|t| t.overview.to_owned()),
             link: None,
             fig_path: match tv_show.details {
@@ -110,6 +129,9 @@ pub struct MediaCardProps {
     pub class: Classes,
     #[prop_or(Children::default())]
     pub children: Children,
+    // When set, the card shows an editor for the entry's watch status, rating and notes.
+    #[prop_or_default]
+    pub on_entry_meta_change: Option<Callback<EntryMeta>>,
 }
 
 #[function_component(MediaCard)]
@@ -169,6 +191,16 @@ pub fn media_card(props: &MediaCardProps) -> Html {
                         }
                     </p>
                 </div>
+                if let Some(summary) = details.entry_meta().and_then(|m| m.taste_summary()) {
+                    <div class="text-xs italic">{summary}</div>
+                }
+                if let Some(on_save) = props.on_entry_meta_change.clone() {
+                    <EntryMetaEditor
+                        id={details.identity()}
+                        meta={details.entry_meta().cloned().unwrap_or_default()}
+                        {on_save}
+                    />
+                }
                 { for props.children.iter() }
             </div>
         </div>
@@ -1,8 +1,16 @@
 pub mod alert;
+pub mod collection_members;
+pub mod entry_meta_editor;
 pub mod figures;
 pub mod form_input;
 pub mod header;
 pub mod loading_button;
 pub mod media_card;
 pub mod media_selector;
+pub mod new_channel;
+pub mod routine_editor;
+pub mod schedule_generator;
+pub mod series_progress;
 pub mod spinner;
+pub mod stream_urls;
+pub mod tag_input;
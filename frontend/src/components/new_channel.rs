@@ -0,0 +1,97 @@
+use crate::api::collections_api::api_get_user_collections;
+use crate::api::schedules_api::api_create_channel;
+use crate::store::{set_show_alert, Store};
+use crate::ui_helpers::get_value_from_input_by_id;
+use common::model::channel::NewChannelSchema;
+use common::model::collections::UserCollection;
+use common::model::schedules::Schedule;
+use gloo::console::console;
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct NewChannelProps {
+    pub on_created: Callback<Schedule>,
+}
+
+// Makes a channel that plays one of the user's collections round and round.
+#[function_component(NewChannel)]
+pub fn new_channel(props: &NewChannelProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let collections: UseStateHandle<Vec<UserCollection>> =
+        use_state(|| store.collections.clone().unwrap_or_default());
+    let source: UseStateHandle<Option<Uuid>> = use_state(|| None);
+
+    {
+        let collections = collections.clone();
+        use_effect_with((), move |_| {
+            if collections.is_empty() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_get_user_collections(None).await {
+                        Ok(cols) => collections.set(cols),
+                        Err(e) => console!(format!("Error getting user collections: {e}")),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_source = {
+        let source = source.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .unwrap()
+                .value();
+            source.set(Uuid::parse_str(&value).ok());
+        })
+    };
+
+    let on_create = {
+        let source = source.clone();
+        let dispatch = dispatch.clone();
+        let on_created = props.on_created.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(collection) = *source else {
+                return;
+            };
+            let new_channel = NewChannelSchema {
+                name: get_value_from_input_by_id("#channel_name").unwrap_or_default(),
+                collection,
+            };
+            let dispatch = dispatch.clone();
+            let on_created = on_created.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_create_channel(new_channel).await {
+                    Ok(channel) => on_created.emit(channel),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <details class="collapse collapse-arrow bg-base-200">
+            <summary class="collapse-title font-medium">{"New channel"}</summary>
+            <div class="collapse-content flex flex-col gap-2">
+                <input id="channel_name" class="input input-bordered input-sm" placeholder="Channel name" />
+                <select class="select select-bordered select-sm" onchange={on_source}>
+                    <option value="" selected={source.is_none()}>{"Plays the collection..."}</option>
+                    {
+                        collections.iter().map(|c| html!{
+                            <option value={c.id.to_string()} selected={*source == Some(c.id)}>{c.name.clone()}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <button class="btn btn-sm btn-primary" onclick={on_create} disabled={source.is_none()}>
+                    {"Create channel"}
+                </button>
+            </div>
+        </details>
+    }
+}
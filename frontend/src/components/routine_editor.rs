@@ -0,0 +1,377 @@
+use crate::api::collections_api::api_get_user_collections;
+use crate::api::schedules_api::{
+    api_create_recurring_schedule, api_get_recurrence, api_save_recurrence, api_stop_recurrence,
+};
+use crate::store::{set_show_alert, Store};
+use crate::ui_helpers::{
+    browser_time_zone, get_value_from_input_by_id, get_value_from_input_event,
+};
+use chrono::prelude::*;
+use common::model::collections::UserCollection;
+use common::model::schedule_recurrence::{
+    Frequency, NewRecurringScheduleSchema, Recurrence, RecurrenceData, RecurrenceRule, Rhythm,
+    RuleSource, DEFAULT_HORIZON_DAYS, MAX_PER_OCCURRENCE,
+};
+use common::model::schedules::Schedule;
+use gloo::console::console;
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+const WEEK: [(Weekday, &str); 7] = [
+    (Weekday::Mon, "Mo"),
+    (Weekday::Tue, "Tu"),
+    (Weekday::Wed, "We"),
+    (Weekday::Thu, "Th"),
+    (Weekday::Fri, "Fr"),
+    (Weekday::Sat, "Sa"),
+    (Weekday::Sun, "Su"),
+];
+
+// Fridays at nine, the next episode of whatever the user is watching.
+fn new_rule() -> RecurrenceRule {
+    RecurrenceRule {
+        rrule: Rhythm {
+            freq: Frequency::Weekly,
+            interval: 1,
+            days: vec![Weekday::Fri],
+            at: NaiveTime::from_hms_opt(21, 0, 0).expect("A real time"),
+            until: None,
+        },
+        source: RuleSource::CurrentSeries,
+        count: 1,
+    }
+}
+
+fn source_value(source: &RuleSource) -> String {
+    match source {
+        RuleSource::Movies { collection } => format!("movies:{collection}"),
+        RuleSource::Episodes { collection } => format!("episodes:{collection}"),
+        RuleSource::CurrentSeries => "current_series".to_string(),
+    }
+}
+
+fn source_from_value(value: &str) -> RuleSource {
+    let collection = |id: &str| Uuid::parse_str(id).ok();
+    match value.split_once(':') {
+        Some(("movies", id)) => collection(id).map(|collection| RuleSource::Movies { collection }),
+        Some(("episodes", id)) => {
+            collection(id).map(|collection| RuleSource::Episodes { collection })
+        }
+        _ => None,
+    }
+    .unwrap_or(RuleSource::CurrentSeries)
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct RoutineEditorProps {
+    // The schedule whose routine to edit, or none to make a new recurring schedule.
+    pub schedule_id: Option<Uuid>,
+    pub on_saved: Callback<Schedule>,
+}
+
+// The rules a schedule repeats by, e.g. a film from the Watchlist every Friday at nine, or two
+// episodes of the current series on Tuesdays and Thursdays. The server lays the slots out ahead.
+#[function_component(RoutineEditor)]
+pub fn routine_editor(props: &RoutineEditorProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let collections: UseStateHandle<Vec<UserCollection>> =
+        use_state(|| store.collections.clone().unwrap_or_default());
+    let saved: UseStateHandle<Option<RecurrenceData>> = use_state(|| None);
+    let rules: UseStateHandle<Vec<RecurrenceRule>> = use_state(|| vec![new_rule()]);
+
+    {
+        let collections = collections.clone();
+        use_effect_with((), move |_| {
+            if collections.is_empty() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_get_user_collections(None).await {
+                        Ok(cols) => collections.set(cols),
+                        Err(e) => console!(format!("Error getting user collections: {e}")),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    {
+        let saved = saved.clone();
+        let rules = rules.clone();
+        let dispatch = dispatch.clone();
+        use_effect_with(props.schedule_id, move |schedule_id| {
+            saved.set(None);
+            rules.set(vec![new_rule()]);
+            if let Some(schedule_id) = *schedule_id {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_get_recurrence(schedule_id).await {
+                        Ok(data) => {
+                            if let Some(recurrence) = &data.recurrence {
+                                rules.set(recurrence.rules.clone());
+                            }
+                            saved.set(Some(data));
+                        }
+                        Err(e) => set_show_alert(e, &dispatch),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let edit_rule = |index: usize, edit: fn(&mut RecurrenceRule, String)| {
+        let rules = rules.clone();
+        move |value: String| {
+            let mut next = (*rules).clone();
+            if let Some(rule) = next.get_mut(index) {
+                edit(rule, value);
+            }
+            rules.set(next);
+        }
+    };
+
+    let on_day = |index: usize, day: Weekday| {
+        let rules = rules.clone();
+        Callback::from(move |_: Event| {
+            let mut next = (*rules).clone();
+            if let Some(rule) = next.get_mut(index) {
+                let days = &mut rule.rrule.days;
+                match days.iter().position(|d| *d == day) {
+                    Some(i) => {
+                        days.remove(i);
+                    }
+                    None => days.push(day),
+                }
+            }
+            rules.set(next);
+        })
+    };
+
+    let on_time = |index: usize| {
+        let edit = edit_rule(index, |rule, value| {
+            if let Ok(at) = NaiveTime::parse_from_str(&value, "%H:%M") {
+                rule.rrule.at = at;
+            }
+        });
+        Callback::from(move |event: InputEvent| edit(get_value_from_input_event(event)))
+    };
+
+    let on_count = |index: usize| {
+        let edit = edit_rule(index, |rule, value| {
+            rule.count = value.parse().unwrap_or(1).clamp(1, MAX_PER_OCCURRENCE);
+        });
+        Callback::from(move |event: InputEvent| edit(get_value_from_input_event(event)))
+    };
+
+    let on_source = |index: usize| {
+        let edit = edit_rule(index, |rule, value| rule.source = source_from_value(&value));
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .unwrap()
+                .value();
+            edit(value);
+        })
+    };
+
+    let on_remove = |index: usize| {
+        let rules = rules.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*rules).clone();
+            next.remove(index);
+            rules.set(next);
+        })
+    };
+
+    let on_add = {
+        let rules = rules.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*rules).clone();
+            next.push(new_rule());
+            rules.set(next);
+        })
+    };
+
+    let on_save = {
+        let rules = rules.clone();
+        let saved = saved.clone();
+        let dispatch = dispatch.clone();
+        let on_saved = props.on_saved.clone();
+        let schedule_id = props.schedule_id;
+        Callback::from(move |_: MouseEvent| {
+            // Changing the rules keeps the routine's start, so every other week stays in step.
+            let current = (*saved).as_ref().and_then(|s| s.recurrence.clone());
+            let recurrence = Recurrence {
+                rules: (*rules).clone(),
+                starts: current
+                    .as_ref()
+                    .map_or(Local::now().date_naive(), |r| r.starts),
+                time_zone: browser_time_zone()
+                    .and_then(|tz| tz.parse().ok())
+                    .unwrap_or_default(),
+                horizon_days: current.map_or(DEFAULT_HORIZON_DAYS, |r| r.horizon_days),
+            };
+            let name = get_value_from_input_by_id("#routine_name").unwrap_or_default();
+            let saved = saved.clone();
+            let dispatch = dispatch.clone();
+            let on_saved = on_saved.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match schedule_id {
+                    Some(schedule_id) => api_save_recurrence(schedule_id, recurrence.clone()).await,
+                    None => {
+                        api_create_recurring_schedule(NewRecurringScheduleSchema {
+                            name,
+                            recurrence: recurrence.clone(),
+                        })
+                        .await
+                    }
+                };
+                match result {
+                    Ok(schedule) => {
+                        if schedule_id.is_some() {
+                            saved.set(Some(RecurrenceData {
+                                recurrence: Some(recurrence),
+                                materialized_until: None,
+                            }));
+                        }
+                        on_saved.emit(schedule);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_stop = {
+        let saved = saved.clone();
+        let rules = rules.clone();
+        let on_saved = props.on_saved.clone();
+        let schedule_id = props.schedule_id;
+        Callback::from(move |_: MouseEvent| {
+            let Some(schedule_id) = schedule_id else {
+                return;
+            };
+            let saved = saved.clone();
+            let rules = rules.clone();
+            let dispatch = dispatch.clone();
+            let on_saved = on_saved.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_stop_recurrence(schedule_id).await {
+                    Ok(schedule) => {
+                        saved.set(Some(RecurrenceData {
+                            recurrence: None,
+                            materialized_until: None,
+                        }));
+                        rules.set(vec![new_rule()]);
+                        on_saved.emit(schedule);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let recurring = (*saved).as_ref().is_some_and(|s| s.recurrence.is_some());
+    // Editing an existing schedule waits until we know what its rules are.
+    let loading = props.schedule_id.is_some() && saved.is_none();
+    let title = match (props.schedule_id, recurring) {
+        (None, _) => "New routine",
+        (Some(_), true) => "Repeats",
+        (Some(_), false) => "Make this repeat",
+    };
+
+    html! {
+        <details class="collapse collapse-arrow bg-base-200">
+            <summary class="collapse-title font-medium">{title}</summary>
+            <div class="collapse-content flex flex-col gap-2">
+                if props.schedule_id.is_none() {
+                    <input id="routine_name" class="input input-bordered input-sm" placeholder="Routine name" />
+                }
+                if let Some(until) = (*saved).as_ref().and_then(|s| s.materialized_until) {
+                    <p class="text-xs">
+                        {format!("Laid out until {}", until.with_timezone(&Local).format("%a %e %b %H:%M"))}
+                    </p>
+                }
+                {
+                    rules.iter().enumerate().map(|(index, rule)| html!{
+                        <div class="flex flex-col gap-1 border-b border-base-300 pb-2">
+                            <div class="flex flex-wrap gap-1 items-center">
+                                {
+                                    WEEK.iter().map(|(day, label)| html!{
+                                        <label class="label cursor-pointer gap-1">
+                                            <input
+                                                type="checkbox"
+                                                class="checkbox checkbox-xs"
+                                                checked={rule.rrule.days.contains(day)}
+                                                onchange={on_day(index, *day)}
+                                            />
+                                            <span class="label-text">{*label}</span>
+                                        </label>
+                                    }).collect::<Html>()
+                                }
+                                <input
+                                    type="time"
+                                    class="input input-bordered input-sm"
+                                    value={rule.rrule.at.format("%H:%M").to_string()}
+                                    oninput={on_time(index)}
+                                />
+                            </div>
+                            <div class="flex flex-wrap gap-1 items-center">
+                                <input
+                                    type="number"
+                                    min="1"
+                                    max={MAX_PER_OCCURRENCE.to_string()}
+                                    class="input input-bordered input-sm w-16"
+                                    value={rule.count.to_string()}
+                                    oninput={on_count(index)}
+                                />
+                                <select class="select select-bordered select-sm grow" onchange={on_source(index)}>
+                                    <option value="current_series" selected={rule.source == RuleSource::CurrentSeries}>
+                                        {"of the current series"}
+                                    </option>
+                                    {
+                                        collections.iter().map(|c| {
+                                            let movies = RuleSource::Movies { collection: c.id };
+                                            let episodes = RuleSource::Episodes { collection: c.id };
+                                            html!{
+                                                <>
+                                                    <option value={source_value(&movies)} selected={rule.source == movies}>
+                                                        {format!("films from {}", c.name)}
+                                                    </option>
+                                                    <option value={source_value(&episodes)} selected={rule.source == episodes}>
+                                                        {format!("episodes from {}", c.name)}
+                                                    </option>
+                                                </>
+                                            }
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                                <button class="btn btn-xs btn-ghost" title="Remove this rule" onclick={on_remove(index)}>
+                                    {"✕"}
+                                </button>
+                            </div>
+                            <code class="text-xs">{rule.rrule.to_string()}</code>
+                        </div>
+                    }).collect::<Html>()
+                }
+                <div class="flex gap-2">
+                    <button class="btn btn-sm" onclick={on_add}>{"Add rule"}</button>
+                    <button
+                        class="btn btn-sm btn-primary"
+                        onclick={on_save}
+                        disabled={loading || rules.is_empty() || rules.iter().any(|r| r.rrule.days.is_empty())}>
+                        {if props.schedule_id.is_none() { "Create routine" } else { "Save rules" }}
+                    </button>
+                    if recurring {
+                        <button class="btn btn-sm btn-outline btn-error" onclick={on_stop}>
+                            {"Stop repeating"}
+                        </button>
+                    }
+                </div>
+            </div>
+        </details>
+    }
+}
@@ -0,0 +1,251 @@
+use crate::api::collections_api::api_get_user_collections;
+use crate::api::schedules_api::{api_generate_schedule, api_generate_themed_schedule};
+use crate::store::{set_page_loading, set_show_alert, Store};
+use crate::ui_helpers::{
+    browser_time_zone, get_value_from_input_by_id, get_value_from_input_event,
+};
+use chrono::prelude::*;
+use common::model::collections::UserCollection;
+use common::model::schedule_generator::{
+    GenerateScheduleSchema, GeneratedSchedule, ScheduleOrdering, ViewingWindow,
+};
+use common::model::schedule_themes::ThemedScheduleSchema;
+use common::model::schedules::Schedule;
+use gloo::console::console;
+use std::collections::HashSet;
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+const WEEKNIGHTS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+const WEEKENDS: [Weekday; 2] = [Weekday::Sat, Weekday::Sun];
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct ScheduleGeneratorProps {
+    pub on_generated: Callback<Schedule>,
+}
+
+// A window from a pair of time inputs, or none if either is left blank.
+fn window_from_inputs(days: &[Weekday], start_id: &str, end_id: &str) -> Option<ViewingWindow> {
+    let time = |id: &str| {
+        get_value_from_input_by_id(id).and_then(|v| NaiveTime::parse_from_str(&v, "%H:%M").ok())
+    };
+    Some(ViewingWindow {
+        days: days.to_vec(),
+        start: time(start_id)?,
+        end: time(end_id)?,
+    })
+}
+
+// Tells the user about anything that didn't make it onto the schedule.
+fn report_left_out(generated: &GeneratedSchedule) -> Option<String> {
+    let mut notes = vec![];
+    if !generated.unscheduled.is_empty() {
+        notes.push(format!(
+            "{} didn't fit: {}",
+            generated.unscheduled.len(),
+            generated.unscheduled.join(", ")
+        ));
+    }
+    if !generated.dropped.is_empty() {
+        notes.push(format!("Left out: {}", generated.dropped.join(", ")));
+    }
+    (!notes.is_empty()).then(|| notes.join(". "))
+}
+
+// Fills a new schedule from some collections: what to watch, when, and in which order. Or, given
+// a theme, from whatever bAIng suggests for it.
+#[function_component(ScheduleGenerator)]
+pub fn schedule_generator(props: &ScheduleGeneratorProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let collections: UseStateHandle<Vec<UserCollection>> =
+        use_state(|| store.collections.clone().unwrap_or_default());
+    let selected = use_state(HashSet::<Uuid>::new);
+    let ordering = use_state(ScheduleOrdering::default);
+    let theme = use_state(String::new);
+
+    {
+        let collections = collections.clone();
+        use_effect_with((), move |_| {
+            if collections.is_empty() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_get_user_collections(None).await {
+                        Ok(cols) => collections.set(cols),
+                        Err(e) => console!(format!("Error getting user collections: {e}")),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_toggle = |id: Uuid| {
+        let selected = selected.clone();
+        Callback::from(move |_: Event| {
+            let mut next = (*selected).clone();
+            if !next.remove(&id) {
+                next.insert(id);
+            }
+            selected.set(next);
+        })
+    };
+
+    let on_ordering = {
+        let ordering = ordering.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .unwrap()
+                .value();
+            ordering.set(match value.as_str() {
+                "random" => ScheduleOrdering::Random,
+                "chronological" => ScheduleOrdering::Chronological,
+                "genre_rotating" => ScheduleOrdering::GenreRotating,
+                _ => ScheduleOrdering::Ranked,
+            });
+        })
+    };
+
+    let on_theme = {
+        let theme = theme.clone();
+        Callback::from(move |event: InputEvent| theme.set(get_value_from_input_event(event)))
+    };
+
+    let on_generate = {
+        let selected = selected.clone();
+        let theme = theme.clone();
+        let ordering = ordering.clone();
+        let dispatch = dispatch.clone();
+        let on_generated = props.on_generated.clone();
+        Callback::from(move |_: MouseEvent| {
+            let date = |id: &str| {
+                get_value_from_input_by_id(id)
+                    .and_then(|v| NaiveDate::parse_from_str(&v, "%Y-%m-%d").ok())
+            };
+            let (Some(from), Some(to)) = (date("#generate_from"), date("#generate_to")) else {
+                set_show_alert("Pick the dates to schedule".to_string(), &dispatch);
+                return;
+            };
+            let windows: Vec<ViewingWindow> = [
+                window_from_inputs(&WEEKNIGHTS, "#weeknight_start", "#weeknight_end"),
+                window_from_inputs(&WEEKENDS, "#weekend_start", "#weekend_end"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+            let name = get_value_from_input_by_id("#generate_name").unwrap_or_default();
+            let time_zone = browser_time_zone()
+                .and_then(|tz| tz.parse().ok())
+                .unwrap_or_default();
+            let gap = get_value_from_input_by_id("#generate_gap")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+            let themed = (!theme.trim().is_empty()).then(|| ThemedScheduleSchema {
+                name: name.clone(),
+                theme: theme.trim().to_string(),
+                from,
+                to,
+                windows: windows.clone(),
+                time_zone,
+                gap,
+            });
+            let generate = GenerateScheduleSchema {
+                name,
+                collections: selected.iter().copied().collect(),
+                from,
+                to,
+                windows,
+                ordering: *ordering,
+                time_zone,
+                gap,
+            };
+
+            let dispatch = dispatch.clone();
+            let on_generated = on_generated.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                set_page_loading(true, &dispatch);
+                let response = match themed {
+                    Some(themed) => api_generate_themed_schedule(themed).await,
+                    None => api_generate_schedule(generate).await,
+                };
+                set_page_loading(false, &dispatch);
+                match response {
+                    Ok(generated) => {
+                        if let Some(note) = report_left_out(&generated) {
+                            set_show_alert(note, &dispatch);
+                        }
+                        on_generated.emit(generated.schedule);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let today = Local::now().date_naive();
+
+    html! {
+        <details class="collapse collapse-arrow bg-base-200">
+            <summary class="collapse-title font-medium">{"Generate from collections or a theme"}</summary>
+            <div class="collapse-content flex flex-col gap-2">
+                <input id="generate_name" class="input input-bordered input-sm" placeholder="Schedule name" />
+                <div class="flex flex-wrap gap-2">
+                    {
+                        collections.iter().map(|c| html!{
+                            <label class="label cursor-pointer gap-1">
+                                <input
+                                    type="checkbox"
+                                    class="checkbox checkbox-xs"
+                                    checked={selected.contains(&c.id)}
+                                    onchange={on_toggle(c.id)}
+                                />
+                                <span class="label-text">{c.name.clone()}</span>
+                            </label>
+                        }).collect::<Html>()
+                    }
+                </div>
+                <input
+                    class="input input-bordered input-sm"
+                    placeholder="Or a theme, e.g. a week of 80s horror, escalating intensity"
+                    value={(*theme).clone()}
+                    oninput={on_theme}
+                />
+                <div class="grid grid-cols-3 gap-2 items-center text-sm">
+                    <span>{"From / to"}</span>
+                    <input id="generate_from" type="date" class="input input-bordered input-sm" value={today.to_string()} />
+                    <input id="generate_to" type="date" class="input input-bordered input-sm" value={(today + chrono::Duration::days(6)).to_string()} />
+                    <span>{"Weeknights"}</span>
+                    <input id="weeknight_start" type="time" class="input input-bordered input-sm" value="20:00" />
+                    <input id="weeknight_end" type="time" class="input input-bordered input-sm" value="23:00" />
+                    <span>{"Weekends"}</span>
+                    <input id="weekend_start" type="time" class="input input-bordered input-sm" value="14:00" />
+                    <input id="weekend_end" type="time" class="input input-bordered input-sm" value="00:00" />
+                    <span>{"Minutes between"}</span>
+                    <input id="generate_gap" type="number" min="0" class="input input-bordered input-sm" value="0" />
+                    <select class="select select-bordered select-sm" onchange={on_ordering} disabled={!theme.trim().is_empty()}>
+                        <option value="ranked" selected=true>{"Ranked"}</option>
+                        <option value="random">{"Random"}</option>
+                        <option value="chronological">{"Chronological"}</option>
+                        <option value="genre_rotating">{"Rotate genres"}</option>
+                    </select>
+                </div>
+                <button
+                    class="btn btn-sm btn-primary"
+                    onclick={on_generate}
+                    disabled={store.page_loading || (selected.is_empty() && theme.trim().is_empty())}>
+                    {if theme.trim().is_empty() { "Generate" } else { "Ask bAIng" }}
+                </button>
+            </div>
+        </details>
+    }
+}
@@ -0,0 +1,81 @@
+use crate::api::progress_api::api_mark_watched;
+use crate::store::{set_show_alert, Store};
+use common::model::core::TvShow;
+use common::model::progress::{
+    next_up, MarkWatchedSchema, ProgressSummary, WatchedEpisodes, WatchedMark,
+};
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SeriesProgressProps {
+    pub show: TvShow,
+    // `None` until something of the show has been marked.
+    pub progress: Option<ProgressSummary>,
+    pub on_change: Callback<ProgressSummary>,
+}
+
+// Where the user is in a show, with buttons to tick off the next episode or the rest of its season.
+#[function_component(SeriesProgressControls)]
+pub fn series_progress_controls(props: &SeriesProgressProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+
+    let (next, last) = match &props.progress {
+        Some(p) => (p.next_up.clone(), p.progress.watched.last()),
+        None => (next_up(&props.show, &WatchedEpisodes::default()), None),
+    };
+
+    let on_mark = |mark: WatchedMark, unwatch: bool| {
+        let show = props.show.clone();
+        let on_change = props.on_change.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let schema = MarkWatchedSchema {
+                show: show.clone(),
+                mark,
+                unwatch,
+            };
+            let on_change = on_change.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_mark_watched(schema).await {
+                    Ok(progress) => on_change.emit(progress),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="flex flex-wrap gap-2 items-center text-xs">
+            {
+                match &next {
+                    Some(episode) => format!("Next up: {episode}"),
+                    None => "Watched it all".to_string(),
+                }
+            }
+            if let Some(episode) = &next {
+                <button
+                    class="btn btn-xs btn-ghost"
+                    onclick={on_mark(WatchedMark::Episode { season: episode.season, episode: episode.episode }, false)}>
+                    {"✓ Watched"}
+                </button>
+                if props.show.details.as_ref().is_some_and(|d| d.seasons.is_some()) {
+                    <button
+                        class="btn btn-xs btn-ghost"
+                        onclick={on_mark(WatchedMark::Season { season: episode.season }, false)}>
+                        {format!("✓ Season {}", episode.season)}
+                    </button>
+                }
+            }
+            if let Some(last) = last {
+                <button
+                    class="btn btn-xs btn-ghost"
+                    title={format!("Unmark {last}")}
+                    onclick={on_mark(WatchedMark::Episode { season: last.season, episode: last.episode }, true)}>
+                    {"Undo"}
+                </button>
+            }
+        </div>
+    }
+}
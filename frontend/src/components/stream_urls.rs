@@ -0,0 +1,118 @@
+use crate::api::schedules_api::{api_get_stream_urls, api_save_stream_urls};
+use crate::store::{set_show_alert, Store};
+use crate::ui_helpers::get_value_from_input_event;
+use common::model::playlist::{StreamUrls, StreamUrlsData};
+use uuid::Uuid;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct StreamUrlsEditorProps {
+    pub schedule_id: Uuid,
+}
+
+// Where each entry of the schedule's M3U playlist points: one template for everything, say a
+// search on the local media server, and a URL of its own for anything the template gets wrong.
+#[function_component(StreamUrlsEditor)]
+pub fn stream_urls_editor(props: &StreamUrlsEditorProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let saved: UseStateHandle<Option<StreamUrlsData>> = use_state(|| None);
+    let urls: UseStateHandle<StreamUrls> = use_state(StreamUrls::default);
+
+    {
+        let saved = saved.clone();
+        let urls = urls.clone();
+        let dispatch = dispatch.clone();
+        use_effect_with(props.schedule_id, move |schedule_id| {
+            let schedule_id = *schedule_id;
+            saved.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_get_stream_urls(schedule_id).await {
+                    Ok(data) => {
+                        urls.set(data.urls.clone());
+                        saved.set(Some(data));
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_template = {
+        let urls = urls.clone();
+        Callback::from(move |event: InputEvent| {
+            let mut next = (*urls).clone();
+            next.template = Some(get_value_from_input_event(event));
+            urls.set(next);
+        })
+    };
+
+    let on_entry = |key: String| {
+        let urls = urls.clone();
+        Callback::from(move |event: InputEvent| {
+            let mut next = (*urls).clone();
+            next.entries
+                .insert(key.clone(), get_value_from_input_event(event));
+            urls.set(next);
+        })
+    };
+
+    let on_save = {
+        let saved = saved.clone();
+        let urls = urls.clone();
+        let schedule_id = props.schedule_id;
+        Callback::from(move |_: MouseEvent| {
+            let saved = saved.clone();
+            let urls = urls.clone();
+            let dispatch = dispatch.clone();
+            let to_save = (*urls).clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_save_stream_urls(schedule_id, to_save).await {
+                    Ok(data) => {
+                        urls.set(data.urls.clone());
+                        saved.set(Some(data));
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <details class="collapse collapse-arrow bg-base-200">
+            <summary class="collapse-title font-medium">{"Playlist URLs"}</summary>
+            <div class="collapse-content flex flex-col gap-2">
+                <p class="text-xs">
+                    {"Use {name}, {title}, {year}, {tmdb_id}, {season} and {episode} to fill in each entry. "}
+                    {"Anything left blank points at its TMDB or YouTube page."}
+                </p>
+                <input
+                    class="input input-bordered input-sm"
+                    placeholder="http://media-server.local/search?q={name}"
+                    value={urls.template.clone().unwrap_or_default()}
+                    oninput={on_template}
+                />
+                {
+                    match &*saved {
+                        Some(data) => data.entries.iter().map(|entry| html!{
+                            <label class="flex flex-col gap-1 text-sm">
+                                {entry.title.clone()}
+                                <input
+                                    class="input input-bordered input-xs"
+                                    placeholder={entry.url.clone().unwrap_or_default()}
+                                    value={urls.entries.get(&entry.key).cloned().unwrap_or_default()}
+                                    oninput={on_entry(entry.key.clone())}
+                                />
+                            </label>
+                        }).collect::<Html>(),
+                        None => html!{<span class="loading loading-spinner loading-sm"></span>},
+                    }
+                }
+                <button class="btn btn-sm btn-primary" onclick={on_save} disabled={saved.is_none()}>
+                    {"Save"}
+                </button>
+            </div>
+        </details>
+    }
+}
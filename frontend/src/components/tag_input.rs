@@ -0,0 +1,78 @@
+use crate::api::tags_api::api_autocomplete_tags;
+use crate::ui_helpers::get_value_from_input_event;
+use common::model::tags::Tags;
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct TagInputProps {
+    // Needed to tie the input to its own list of suggestions.
+    pub id: AttrValue,
+    pub on_add: Callback<String>,
+    #[prop_or(AttrValue::from("Add a tag..."))]
+    pub placeholder: AttrValue,
+}
+
+// A text input that suggests the user's existing tags as they type.
+#[function_component(TagInput)]
+pub fn tag_input(props: &TagInputProps) -> Html {
+    let value = use_state(String::new);
+    let suggestions = use_state(Vec::<String>::new);
+    let list_id = format!("{}_suggestions", props.id);
+
+    let on_input = {
+        let value = value.clone();
+        let suggestions = suggestions.clone();
+        Callback::from(move |event: InputEvent| {
+            let input = get_value_from_input_event(event);
+            value.set(input.clone());
+            let suggestions = suggestions.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(tags) = api_autocomplete_tags(&input).await {
+                    suggestions.set(tags);
+                }
+            });
+        })
+    };
+
+    let add = {
+        let value = value.clone();
+        let on_add = props.on_add.clone();
+        move || {
+            let tag = Tags::normalize(&value);
+            if !tag.is_empty() {
+                on_add.emit(tag);
+            }
+            value.set(String::new());
+        }
+    };
+
+    let on_keypress = {
+        let add = add.clone();
+        Callback::from(move |event: KeyboardEvent| {
+            if event.key() == "Enter" {
+                event.prevent_default();
+                add();
+            }
+        })
+    };
+
+    let on_click = Callback::from(move |_: MouseEvent| add());
+
+    html! {
+        <div class="join">
+            <input
+                id={props.id.clone()}
+                class="input input-bordered input-xs join-item grow"
+                list={list_id.clone()}
+                placeholder={props.placeholder.clone()}
+                value={(*value).clone()}
+                oninput={on_input}
+                onkeypress={on_keypress}
+            />
+            <datalist id={list_id}>
+                { suggestions.iter().map(|t| html!{<option value={t.clone()} />}).collect::<Html>() }
+            </datalist>
+            <button class="btn btn-xs join-item" onclick={on_click}>{"+"}</button>
+        </div>
+    }
+}
@@ -0,0 +1,120 @@
+use crate::api::schedules_api::api_get_channel_now;
+use crate::components::media_card::MediaCard;
+use crate::components::spinner::Spinner;
+use chrono::prelude::*;
+use common::model::channel::ChannelGuide;
+use gloo::console::console;
+use gloo::timers::callback::Interval;
+use uuid::Uuid;
+use yew::prelude::*;
+
+// Often enough that the guide moves on soon after the next thing starts.
+const REFRESH_MS: u32 = 30_000;
+const UP_NEXT: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct ChannelPageProps {
+    pub id: String,
+}
+
+// The channel guide: what's on now, how far in it is, and what comes after.
+#[function_component(ChannelPage)]
+pub fn channel_page(props: &ChannelPageProps) -> Html {
+    let guide: UseStateHandle<Option<ChannelGuide>> = use_state(|| None);
+    let error = use_state(|| None::<String>);
+
+    {
+        let guide = guide.clone();
+        let error = error.clone();
+        use_effect_with(props.id.clone(), move |id| {
+            let refresh = {
+                let id = Uuid::parse_str(id).ok();
+                move || {
+                    let guide = guide.clone();
+                    let error = error.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let Some(id) = id else {
+                            error.set(Some("Channel not found".to_string()));
+                            return;
+                        };
+                        match api_get_channel_now(id, UP_NEXT).await {
+                            Ok(now) => guide.set(Some(now)),
+                            Err(e) => {
+                                console!(format!("Error getting channel: {e}"));
+                                error.set(Some(e));
+                            }
+                        }
+                    });
+                }
+            };
+            refresh();
+            let interval = Interval::new(REFRESH_MS, refresh);
+            move || drop(interval)
+        });
+    }
+
+    let body = match (&*guide, &*error) {
+        (Some(guide), _) => html! {
+            <>
+                <h2 class="text-2xl font-bold pb-2 pt-2">{format!("📺 {}", guide.name)}</h2>
+                {
+                    match &guide.now.now_playing {
+                        Some(slot) => html!{
+                            <div class="flex flex-col gap-2">
+                                <div class="flex justify-between text-sm">
+                                    <span class="font-bold">{"On now"}</span>
+                                    <span>{format!(
+                                        "{} – {}",
+                                        slot.start.with_timezone(&Local).format("%H:%M"),
+                                        slot.end().with_timezone(&Local).format("%H:%M")
+                                    )}</span>
+                                </div>
+                                <progress
+                                    class="progress progress-primary w-full"
+                                    value={guide.now.elapsed.to_string()}
+                                    max={(slot.duration as i64 * 60).to_string()}
+                                />
+                                <MediaCard media={slot.media.clone()} lite={true}>
+                                    if let Some(episode) = &slot.episode {
+                                        <span class="text-sm">{episode.to_string()}</span>
+                                    }
+                                </MediaCard>
+                            </div>
+                        },
+                        None => html!{<p>{"... nothing to play, add something to the channel's collection ..."}</p>},
+                    }
+                }
+                if !guide.now.up_next.is_empty() {
+                    <h3 class="text-lg font-bold pt-4">{"Up next"}</h3>
+                    <table class="table table-sm">
+                        <tbody>
+                            {
+                                guide.now.up_next.iter().map(|slot| html!{
+                                    <tr>
+                                        <td class="whitespace-nowrap">
+                                            {slot.start.with_timezone(&Local).format("%a %H:%M").to_string()}
+                                        </td>
+                                        <td>{slot.title()}</td>
+                                        <td class="text-right">{format!("{}m", slot.duration)}</td>
+                                    </tr>
+                                }).collect::<Html>()
+                            }
+                        </tbody>
+                    </table>
+                }
+            </>
+        },
+        (None, Some(e)) => html! {<p>{e.clone()}</p>},
+        (None, None) => html! {<Spinner />},
+    };
+
+    html! {
+        <section class="grid justify-items-stretch justify-center">
+            <div class="grid lg:w-[65vw] sm:w-[95wv]">
+                <div class="lg:w-3/5 grow flex flex-col justify-self-center justify-center">
+                    {body}
+                </div>
+            </div>
+        </section>
+    }
+}
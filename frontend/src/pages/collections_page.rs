@@ -1,16 +1,41 @@
-use crate::api::collections_api::api_get_user_collections;
+use crate::api::collections_api::{
+    api_bulk_collection_entries, api_collection_export_url, api_get_user_collections,
+    api_move_collection_entry, api_patch_collection_entry_meta, api_patch_collection_ranked,
+    api_patch_collection_sharing, api_patch_user_collection, api_post_collection_details,
+    api_search_collections,
+};
+use crate::api::members_api::{api_get_collection_members, api_get_memberships};
+use crate::api::progress_api::api_get_progress;
+use crate::api::revisions_api::{api_get_collection_revisions, api_restore_collection_revision};
+use crate::api::tags_api::{
+    api_delete_tag, api_get_tags, api_patch_collection_tags, api_rename_tag,
+};
+use crate::components::collection_members::CollectionMembers;
 use crate::components::media_card::MediaCard;
 use crate::components::media_selector::{MediaSelector, MediaSelectorOption};
+use crate::components::series_progress::SeriesProgressControls;
+use crate::components::tag_input::TagInput;
 use crate::router;
-use crate::store::{set_page_loading, set_show_alert, Store};
-use common::model::collections::{Media, UserCollection};
+use crate::store::{set_page_loading, set_show_alert, set_show_undo_alert, Store};
+use crate::ui_helpers::get_value_from_input_event;
+use common::model::collections::{BulkEntriesAction, Media, ShareType, UserCollection};
+use common::model::core::EntryMeta;
+use common::model::export::ExportFormat;
+use common::model::members::{added_by_name, CollectionMember, CollectionRole};
+use common::model::progress::ProgressSummary;
+use common::model::revisions::CollectionRevision;
+use common::model::search::SearchHit;
+use common::model::tags::TagUsage;
 use gloo::console::console;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlSelectElement;
 use yew::prelude::*;
 use yew_router::hooks::use_navigator;
+use yew_router::prelude::Link;
 use yewdux::functional::use_store;
+use yewdux::Dispatch;
 
 #[derive(Clone, Debug, PartialEq)]
 struct ContentCollections {
@@ -28,6 +53,60 @@ fn media_item(media: Media) -> Html {
     html! {<li>{li}</li>}
 }
 
+// Swaps an updated collection into the page and into the store, which holds the unfiltered list.
+fn replace_collection(
+    updated: UserCollection,
+    collections: &UseStateHandle<Vec<UserCollection>>,
+    active_col: &UseStateHandle<Option<UserCollection>>,
+    dispatch: &Dispatch<Store>,
+) {
+    let swap = |cols: &[UserCollection]| -> Vec<UserCollection> {
+        cols.iter()
+            .map(|c| match c.id == updated.id {
+                true => updated.clone(),
+                false => c.to_owned(),
+            })
+            .collect()
+    };
+
+    collections.set(swap(collections));
+    active_col.set(Some(updated.clone()));
+    dispatch.reduce_mut(|store| {
+        if let Some(cols) = store.collections.as_mut() {
+            *cols = swap(cols);
+        }
+    });
+}
+
+// Like `replace_collection`, for changes spanning several collections. The active one stays active.
+fn replace_collections(
+    updated: &[UserCollection],
+    collections: &UseStateHandle<Vec<UserCollection>>,
+    active_col: &UseStateHandle<Option<UserCollection>>,
+    dispatch: &Dispatch<Store>,
+) {
+    let swap = |cols: &[UserCollection]| -> Vec<UserCollection> {
+        cols.iter()
+            .map(|c| match updated.iter().find(|u| u.id == c.id) {
+                Some(u) => u.clone(),
+                None => c.to_owned(),
+            })
+            .collect()
+    };
+
+    collections.set(swap(collections));
+    if let Some(active) = active_col.as_ref() {
+        if let Some(u) = updated.iter().find(|u| u.id == active.id) {
+            active_col.set(Some(u.clone()));
+        }
+    }
+    dispatch.reduce_mut(|store| {
+        if let Some(cols) = store.collections.as_mut() {
+            *cols = swap(cols);
+        }
+    });
+}
+
 #[function_component(CollectionsPage)]
 pub fn collections_page() -> Html {
     let (store, dispatch) = use_store::<Store>();
@@ -39,22 +118,94 @@ pub fn collections_page() -> Html {
     let collections = use_state(|| store.collections.clone().unwrap_or_default());
     let media_selector_option = use_state(|| MediaSelectorOption::All);
     let active_col: UseStateHandle<Option<UserCollection>> = use_state(|| None);
+    let tags = use_state(Vec::<TagUsage>::new);
+    let tag_filter: UseStateHandle<Option<String>> = use_state(|| None);
+    let revisions: UseStateHandle<Option<Vec<CollectionRevision>>> = use_state(|| None);
+    let search_query = use_state(String::new);
+    let selected_entries = use_state(HashSet::<String>::new);
+    let bulk_target: UseStateHandle<Option<Uuid>> = use_state(|| None);
+    let search_results: UseStateHandle<Option<Vec<SearchHit>>> = use_state(|| None);
+    // Our roles on other people's collections, and who's on the active one.
+    let memberships = use_state(Vec::<CollectionMember>::new);
+    let members = use_state(Vec::<CollectionMember>::new);
+    // The identity of the entry being dragged to a new rank.
+    let dragging: UseStateHandle<Option<String>> = use_state(|| None);
+    // Where we are in each show, by its identity.
+    let progress = use_state(HashMap::<String, ProgressSummary>::new);
+
+    // Pick up changes made elsewhere, like an undo from the alert.
+    {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let unfiltered = tag_filter.is_none();
+        use_effect_with(store.collections.clone(), move |cols| {
+            if let (Some(cols), true) = (cols, unfiltered) {
+                collections.set(cols.clone());
+                if let Some(active) = &*active_col {
+                    active_col.set(cols.iter().find(|c| c.id == active.id).cloned());
+                }
+            }
+            || ()
+        });
+    }
+
+    // History and selections are per collection, so drop them when switching.
+    {
+        let revisions = revisions.clone();
+        let selected_entries = selected_entries.clone();
+        let members = members.clone();
+        use_effect_with(active_col.as_ref().map(|c| c.id), move |active_id| {
+            revisions.set(None);
+            selected_entries.set(HashSet::new());
+            members.set(vec![]);
+            if let Some(collection_id) = *active_id {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(list) = api_get_collection_members(collection_id).await {
+                        members.set(list);
+                    }
+                });
+            }
+            || ()
+        });
+    }
 
     {
         let collections = collections.clone();
+        let active_col = active_col.clone();
+        let tags = tags.clone();
+        let memberships = memberships.clone();
+        let progress = progress.clone();
         let dispatch = dispatch.clone();
-        use_effect_with((), move |_| {
+        use_effect_with((*tag_filter).clone(), move |tag_filter| {
             let dispatch = dispatch.clone();
+            let tag_filter = tag_filter.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 set_page_loading(true, &dispatch);
-                let response = api_get_user_collections().await;
+                if let Ok(usage) = api_get_tags().await {
+                    tags.set(usage);
+                }
+                if let Ok(list) = api_get_memberships().await {
+                    memberships.set(list);
+                }
+                if let Ok(list) = api_get_progress().await {
+                    progress.set(
+                        list.into_iter()
+                            .map(|p| (p.progress.show_key.clone(), p))
+                            .collect(),
+                    );
+                }
+                let response = api_get_user_collections(tag_filter.clone()).await;
                 match response {
                     Ok(cols) => {
                         set_page_loading(false, &dispatch);
                         collections.set(cols.clone());
-                        dispatch.reduce_mut(move |store| {
-                            store.collections = Some(cols);
-                        })
+                        active_col.set(None);
+                        // Only the full list belongs in the store.
+                        if tag_filter.is_none() {
+                            dispatch.reduce_mut(move |store| {
+                                store.collections = Some(cols);
+                            })
+                        }
                     }
                     Err(e) => {
                         console!(format!("Error getting user collections: {e}"));
@@ -70,6 +221,40 @@ pub fn collections_page() -> Html {
         });
     }
 
+    let my_id = user.as_ref().map(|u| u.id);
+    let role_of = |col: &UserCollection| -> CollectionRole {
+        match Some(col.owner_id) == my_id {
+            true => CollectionRole::Owner,
+            false => memberships
+                .iter()
+                .find(|m| m.collection_id == col.id)
+                .map(|m| m.member_role())
+                .unwrap_or(CollectionRole::Viewer),
+        }
+    };
+
+    let on_members_change = {
+        let members = members.clone();
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        Callback::from(move |list: Vec<CollectionMember>| {
+            // Having left, it's no longer ours to see.
+            if !list.iter().any(|m| Some(m.user_id) == my_id) {
+                if let Some(col) = active_col.as_ref() {
+                    collections.set(
+                        collections
+                            .iter()
+                            .filter(|c| c.id != col.id)
+                            .cloned()
+                            .collect(),
+                    );
+                }
+                active_col.set(None);
+            }
+            members.set(list);
+        })
+    };
+
     let media_filter = |media: &Media, option: &MediaSelectorOption| -> bool {
         match option {
             MediaSelectorOption::Movies => matches!(media, &Media::Movie(_)),
@@ -103,6 +288,537 @@ pub fn collections_page() -> Html {
         })
     };
 
+    let on_search_input = {
+        let search_query = search_query.clone();
+        let search_results = search_results.clone();
+        Callback::from(move |event: InputEvent| {
+            let q = get_value_from_input_event(event);
+            if q.trim().is_empty() {
+                search_results.set(None);
+            }
+            search_query.set(q);
+        })
+    };
+
+    let on_search_keypress = {
+        let search_query = search_query.clone();
+        let search_results = search_results.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |event: KeyboardEvent| {
+            if event.key() != "Enter" || search_query.trim().is_empty() {
+                return;
+            }
+            let q = (*search_query).clone();
+            let search_results = search_results.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_search_collections(&q).await {
+                    Ok(results) => search_results.set(Some(results)),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    // Search covers every collection, even ones hidden by the tag filter.
+    let on_search_hit_click = |collection_id: Uuid| {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let all_collections = store.collections.clone().unwrap_or_default();
+        Callback::from(move |_: MouseEvent| {
+            let found = collections
+                .iter()
+                .chain(all_collections.iter())
+                .find(|c| c.id == collection_id)
+                .cloned();
+            active_col.set(found);
+        })
+    };
+
+    let on_change_sharing = {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |event: Event| {
+            let selected = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .unwrap()
+                .value();
+            let sharing = match selected.as_str() {
+                "public" => ShareType::Public,
+                "friends" => ShareType::Friends,
+                _ => ShareType::Private,
+            };
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let dispatch = dispatch.clone();
+            if let Some(col) = (*active_col).clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_patch_collection_sharing(col.id, sharing).await {
+                        Ok(mut updated) => {
+                            // The PATCH doesn't evaluate smart collections, keep what we had.
+                            updated.collection = col.collection.clone();
+                            replace_collection(updated, &collections, &active_col, &dispatch);
+                        }
+                        Err(e) => set_show_alert(e, &dispatch),
+                    }
+                });
+            }
+        })
+    };
+
+    let on_entry_meta_change = |col: &UserCollection, media: &Media| {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let dispatch = dispatch.clone();
+        let col_id = col.id;
+        let entry = media.identity();
+        Callback::from(move |meta: EntryMeta| {
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let dispatch = dispatch.clone();
+            let entry = entry.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_patch_collection_entry_meta(col_id, entry, meta).await {
+                    Ok(updated) => {
+                        replace_collection(updated, &collections, &active_col, &dispatch)
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let set_collection_tags = {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let tags = tags.clone();
+        let dispatch = dispatch.clone();
+        move |add: Option<String>, remove: Option<String>| {
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let tags = tags.clone();
+            let dispatch = dispatch.clone();
+            let Some(col) = (*active_col).clone() else {
+                return;
+            };
+            let mut new_tags = col.tags.clone();
+            if let Some(tag) = add {
+                new_tags.insert(&tag);
+            }
+            if let Some(tag) = remove {
+                new_tags.remove(&tag);
+            }
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_patch_collection_tags(col.id, new_tags).await {
+                    Ok(mut updated) => {
+                        updated.collection = col.collection.clone();
+                        replace_collection(updated, &collections, &active_col, &dispatch);
+                        if let Ok(usage) = api_get_tags().await {
+                            tags.set(usage);
+                        }
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        }
+    };
+
+    let on_add_collection_tag = {
+        let set_collection_tags = set_collection_tags.clone();
+        Callback::from(move |tag: String| set_collection_tags(Some(tag), None))
+    };
+
+    let on_remove_collection_tag = |tag: String| {
+        let set_collection_tags = set_collection_tags.clone();
+        Callback::from(move |_: MouseEvent| set_collection_tags(None, Some(tag.clone())))
+    };
+
+    let on_change_tag_filter = {
+        let tag_filter = tag_filter.clone();
+        Callback::from(move |event: Event| {
+            let selected = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .unwrap()
+                .value();
+            tag_filter.set((!selected.is_empty()).then_some(selected));
+        })
+    };
+
+    // Renaming or deleting goes across every collection and entry, then starts the page over.
+    let on_rename_tag = {
+        let tag_filter = tag_filter.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(from) = (*tag_filter).clone() else {
+                return;
+            };
+            let to = web_sys::window()
+                .and_then(|w| {
+                    w.prompt_with_message_and_default("Rename tag to:", &from)
+                        .ok()
+                })
+                .flatten();
+            let tag_filter = tag_filter.clone();
+            let dispatch = dispatch.clone();
+            if let Some(to) = to.filter(|to| *to != from) {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_rename_tag(from, to).await {
+                        Ok(_) => tag_filter.set(None),
+                        Err(e) => set_show_alert(e, &dispatch),
+                    }
+                });
+            }
+        })
+    };
+
+    let on_delete_tag = {
+        let tag_filter = tag_filter.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(tag) = (*tag_filter).clone() else {
+                return;
+            };
+            let confirmed = web_sys::window()
+                .and_then(|w| {
+                    w.confirm_with_message(&format!("Remove '{tag}' from everything?"))
+                        .ok()
+                })
+                .unwrap_or(false);
+            let tag_filter = tag_filter.clone();
+            let dispatch = dispatch.clone();
+            if confirmed {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_delete_tag(&tag).await {
+                        Ok(_) => tag_filter.set(None),
+                        Err(e) => set_show_alert(e, &dispatch),
+                    }
+                });
+            }
+        })
+    };
+
+    let on_remove_entry = |media: &Media| {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let dispatch = dispatch.clone();
+        let identity = media.identity();
+        let name = media.to_string();
+        Callback::from(move |_: MouseEvent| {
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let dispatch = dispatch.clone();
+            let name = name.clone();
+            let Some(mut col) = (*active_col).clone() else {
+                return;
+            };
+            col.collection.entries.retain(|m| m.identity() != identity);
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_patch_user_collection(col).await {
+                    Ok(updated) => {
+                        set_show_undo_alert(
+                            format!("Removed {} from {}", name, updated.name),
+                            updated.id,
+                            &dispatch,
+                        );
+                        replace_collection(updated, &collections, &active_col, &dispatch);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_toggle_selected = |media: &Media| {
+        let selected_entries = selected_entries.clone();
+        let identity = media.identity();
+        Callback::from(move |_: Event| {
+            let mut selected = (*selected_entries).clone();
+            if !selected.remove(&identity) {
+                selected.insert(identity.clone());
+            }
+            selected_entries.set(selected);
+        })
+    };
+
+    let on_select_all = {
+        let selected_entries = selected_entries.clone();
+        let active_col = active_col.clone();
+        let media_selector_option = media_selector_option.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(col) = active_col.as_ref() {
+                selected_entries.set(
+                    col.collection
+                        .entries
+                        .iter()
+                        .filter(|m| media_filter(m, &media_selector_option))
+                        .map(|m| m.identity())
+                        .collect(),
+                );
+            }
+        })
+    };
+
+    let on_clear_selection = {
+        let selected_entries = selected_entries.clone();
+        Callback::from(move |_: MouseEvent| selected_entries.set(HashSet::new()))
+    };
+
+    let on_change_bulk_target = {
+        let bulk_target = bulk_target.clone();
+        Callback::from(move |event: Event| {
+            let selected = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .map(|s| s.value())
+                .unwrap_or_default();
+            bulk_target.set(Uuid::parse_str(&selected).ok());
+        })
+    };
+
+    let on_bulk = {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let selected_entries = selected_entries.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |action: BulkEntriesAction| {
+            let Some(col) = (*active_col).clone() else {
+                return;
+            };
+            let entries: Vec<String> = selected_entries.iter().cloned().collect();
+            if entries.is_empty() {
+                return;
+            }
+            if action == BulkEntriesAction::Delete {
+                let confirmed = web_sys::window()
+                    .and_then(|w| {
+                        w.confirm_with_message(&format!(
+                            "Delete {} entries from {}?",
+                            entries.len(),
+                            col.name
+                        ))
+                        .ok()
+                    })
+                    .unwrap_or(false);
+                if !confirmed {
+                    return;
+                }
+            }
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let selected_entries = selected_entries.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_bulk_collection_entries(col.id, entries.clone(), action).await {
+                    Ok(updated) => {
+                        // Moved entries are gone from here, anything else can stay selected.
+                        let remaining: HashSet<String> = updated
+                            .iter()
+                            .find(|u| u.id == col.id)
+                            .map(|u| u.collection.entries.iter().map(|m| m.identity()).collect())
+                            .unwrap_or_default();
+                        selected_entries.set(
+                            entries
+                                .into_iter()
+                                .filter(|e| remaining.contains(e))
+                                .collect(),
+                        );
+                        set_show_undo_alert(format!("Updated {}", col.name), col.id, &dispatch);
+                        replace_collections(&updated, &collections, &active_col, &dispatch);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_bulk_move_or_copy = |copy: bool| {
+        let bulk_target = bulk_target.clone();
+        let on_bulk = on_bulk.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| match *bulk_target {
+            Some(to) if copy => on_bulk.emit(BulkEntriesAction::Copy { to }),
+            Some(to) => on_bulk.emit(BulkEntriesAction::Move { to }),
+            None => set_show_alert("Pick a collection first".to_string(), &dispatch),
+        })
+    };
+
+    let on_bulk_tag = {
+        let on_bulk = on_bulk.clone();
+        Callback::from(move |tag: String| {
+            on_bulk.emit(BulkEntriesAction::Tag {
+                add: vec![tag].into(),
+                remove: Default::default(),
+            })
+        })
+    };
+
+    let on_bulk_rate = {
+        let on_bulk = on_bulk.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .map(|s| s.value())
+                .unwrap_or_default();
+            // The placeholder option has no value; "clear" has an empty rating.
+            match value.as_str() {
+                "" => {}
+                "clear" => on_bulk.emit(BulkEntriesAction::Rate { rating: None }),
+                v => on_bulk.emit(BulkEntriesAction::Rate {
+                    rating: v.parse().ok(),
+                }),
+            }
+        })
+    };
+
+    let on_toggle_ranked = {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: Event| {
+            let Some(col) = (*active_col).clone() else {
+                return;
+            };
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_patch_collection_ranked(col.id, !col.ranked).await {
+                    Ok(updated) => {
+                        replace_collection(updated, &collections, &active_col, &dispatch)
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_look_up_details = {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(col) = (*active_col).clone() else {
+                return;
+            };
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                set_page_loading(true, &dispatch);
+                let response = api_post_collection_details(col.id).await;
+                set_page_loading(false, &dispatch);
+                match response {
+                    Ok(updated) => {
+                        replace_collection(updated, &collections, &active_col, &dispatch)
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_progress_change = {
+        let progress = progress.clone();
+        Callback::from(move |summary: ProgressSummary| {
+            let mut next = (*progress).clone();
+            next.insert(summary.progress.show_key.clone(), summary);
+            progress.set(next);
+        })
+    };
+
+    let on_move_entry = {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |(entry, position): (String, usize)| {
+            let Some(col) = (*active_col).clone() else {
+                return;
+            };
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_move_collection_entry(col.id, entry, position).await {
+                    Ok(updated) => {
+                        replace_collection(updated, &collections, &active_col, &dispatch)
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_drag_start = |media: &Media| {
+        let dragging = dragging.clone();
+        let identity = media.identity();
+        Callback::from(move |_: DragEvent| dragging.set(Some(identity.clone())))
+    };
+
+    // Dropping onto an entry takes its place, pushing it down (or up, dragging from above).
+    let on_drop = |position: usize| {
+        let dragging = dragging.clone();
+        let on_move_entry = on_move_entry.clone();
+        Callback::from(move |event: DragEvent| {
+            event.prevent_default();
+            if let Some(entry) = (*dragging).clone() {
+                on_move_entry.emit((entry, position));
+            }
+            dragging.set(None);
+        })
+    };
+
+    let on_drag_over = Callback::from(|event: DragEvent| event.prevent_default());
+
+    let on_show_history = {
+        let active_col = active_col.clone();
+        let revisions = revisions.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let revisions = revisions.clone();
+            let dispatch = dispatch.clone();
+            if let Some(col) = (*active_col).clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_get_collection_revisions(col.id).await {
+                        Ok(revs) => revisions.set(Some(revs)),
+                        Err(e) => set_show_alert(e, &dispatch),
+                    }
+                });
+            }
+        })
+    };
+
+    let on_restore_revision = |revision: &CollectionRevision| {
+        let collections = collections.clone();
+        let active_col = active_col.clone();
+        let revisions = revisions.clone();
+        let dispatch = dispatch.clone();
+        let (collection_id, revision_id) = (revision.collection_id, revision.id);
+        Callback::from(move |_: MouseEvent| {
+            let collections = collections.clone();
+            let active_col = active_col.clone();
+            let revisions = revisions.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_restore_collection_revision(collection_id, revision_id).await {
+                    Ok(updated) => {
+                        set_show_undo_alert(
+                            format!("Restored {}", updated.name),
+                            updated.id,
+                            &dispatch,
+                        );
+                        replace_collection(updated, &collections, &active_col, &dispatch);
+                        revisions.set(None);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
     let on_media_click = {
         Callback::from(move |event: MouseEvent| {
             console!("You clicked! Huzzah for you!".to_string());
@@ -113,17 +829,68 @@ pub fn collections_page() -> Html {
         <section class="grid justify-items-stretch justify-center">
             <div class="grid lg:w-[65vw] sm:w-[95wv]">
                 <div class="lg:w-3/5 justify-self-center">
+                    if !tags.is_empty() {
+                        <div class="flex gap-2 items-center text-xs">
+                            <select class="select select-bordered select-xs" onchange={on_change_tag_filter}>
+                                <option value="" selected={tag_filter.is_none()}>{"All tags"}</option>
+                                {
+                                    tags.iter().map(|t| html!{
+                                        <option value={t.tag.clone()} selected={tag_filter.as_ref() == Some(&t.tag)}>
+                                            {format!("#{} ({})", t.tag, t.total())}
+                                        </option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                            if tag_filter.is_some() {
+                                <a class="link" onclick={on_rename_tag}>{"Rename"}</a>
+                                <a class="link" onclick={on_delete_tag}>{"Delete"}</a>
+                            }
+                        </div>
+                    }
+                    <input
+                        id="collections_search"
+                        class="input input-bordered input-sm w-full mt-1"
+                        type="search"
+                        placeholder="Search titles, overviews, notes and tags..."
+                        value={(*search_query).clone()}
+                        oninput={on_search_input}
+                        onkeypress={on_search_keypress}
+                    />
+                    if let Some(results) = &*search_results {
+                        <ul class="text-sm text-left p-2">
+                            if results.is_empty() {
+                                <li>{"... nothing found ..."}</li>
+                            }
+                            {
+                                results.iter().map(|hit| html!{
+                                    <li>
+                                        {format!("{} in ", hit.media)}
+                                        {
+                                            hit.collections.iter().map(|c| html!{
+                                                <a class="link mr-2" onclick={on_search_hit_click(c.id)}>{c.name.clone()}</a>
+                                            }).collect::<Html>()
+                                        }
+                                    </li>
+                                }).collect::<Html>()
+                            }
+                        </ul>
+                    }
                     <select
                         id="bng_Collection_Selector"
                         class="select select-bordered select-lg w-full mt-1"
                         onchange={on_change_collection_selector}>
                         // <option>Option 1</option>
-                        <option disabled={true} selected={true}>{"Collections..."}</option>
+                        <option disabled={true} selected={active_col.is_none()}>{"Collections..."}</option>
                         {
                             collections.iter().map(|col|{
-                                let name = format!("{} ({})",col.name, col.collection.entries.len());
+                                let name = match (col.is_smart(), Some(col.owner_id) == my_id) {
+                                    (true, _) => format!("🧠 {} ({})", col.name, col.collection.entries.len()),
+                                    (false, false) => format!("👥 {} ({})", col.name, col.collection.entries.len()),
+                                    (false, true) => format!("{} ({})", col.name, col.collection.entries.len()),
+                                };
 
-                                html!{<option value={col.id.to_string()}>{name}</option>}
+                                let selected = active_col.as_ref().is_some_and(|a| a.id == col.id);
+                                html!{<option value={col.id.to_string()} {selected}>{name}</option>}
                             }).collect::<Html>()
                         }
                     </select>
@@ -143,6 +910,10 @@ pub fn collections_page() -> Html {
                                     filtered_col
                                 })
                                 .map(|col| {
+                                    let role = role_of(&col);
+                                    let is_owner = role == CollectionRole::Owner;
+                                    // Smart collection entries live in (and are edited from) their source collections.
+                                    let can_edit_entries = role.can_edit() && !col.is_smart();
                                     html!{
                                     <>
                                         <div class="grow pb-2 pt-2">
@@ -151,17 +922,194 @@ pub fn collections_page() -> Html {
                                                 on_change={&on_change_media_selector}
                                             />
                                         </div>
+                                        <div class="flex justify-between gap-2 text-xs">
+                                            <div class="flex items-center gap-2">
+                                                {"Sharing:"}
+                                                <select class="select select-bordered select-xs" disabled={!is_owner} onchange={&on_change_sharing}>
+                                                    {
+                                                        [ShareType::Private, ShareType::Public, ShareType::Friends]
+                                                        .iter()
+                                                        .map(|st| html!{
+                                                            <option value={st.to_string()} selected={*st == col.share_type()}>
+                                                                {st.to_string()}
+                                                            </option>
+                                                        })
+                                                        .collect::<Html>()
+                                                    }
+                                                </select>
+                                                if col.share_type() != ShareType::Private {
+                                                    <Link<router::Route> to={router::Route::SharedCollectionPage { id: col.id.to_string() }} classes="link">
+                                                        {"Share link"}
+                                                    </Link<router::Route>>
+                                                }
+                                            </div>
+                                            <div class="flex items-center gap-2">
+                                                {"Export:"}
+                                                <a class="link" href={api_collection_export_url(Some(col.id), ExportFormat::Csv)}>{"CSV"}</a>
+                                                <a class="link" href={api_collection_export_url(Some(col.id), ExportFormat::Json)}>{"JSON"}</a>
+                                                <a class="link" href={api_collection_export_url(Some(col.id), ExportFormat::Letterboxd)}>{"Letterboxd"}</a>
+                                            </div>
+                                        </div>
+                                        <div class="flex flex-wrap gap-1 items-center pt-2 text-xs">
+                                            {
+                                                col.tags.iter().map(|tag| html!{
+                                                    <span class="badge badge-secondary gap-1">
+                                                        {tag.clone()}
+                                                        if is_owner {
+                                                            <a class="cursor-pointer" onclick={on_remove_collection_tag(tag.clone())}>{"✕"}</a>
+                                                        }
+                                                    </span>
+                                                }).collect::<Html>()
+                                            }
+                                            if is_owner {
+                                                <TagInput id="collection_tags" on_add={&on_add_collection_tag} />
+                                            }
+                                        </div>
+                                        if can_edit_entries {
+                                            <button
+                                                class="btn btn-xs btn-ghost justify-self-start"
+                                                title="Runtimes, seasons and episodes from TMDB, for scheduling"
+                                                onclick={&on_look_up_details}
+                                                disabled={store.page_loading}>
+                                                {"Look up runtimes"}
+                                            </button>
+                                        }
+                                        if is_owner && !col.is_smart() {
+                                            <label class="label cursor-pointer justify-start gap-2 text-xs">
+                                                <input type="checkbox" class="checkbox checkbox-xs" checked={col.ranked} onchange={&on_toggle_ranked} />
+                                                {"Ranked (drag entries to reorder)"}
+                                            </label>
+                                        }
+                                        <CollectionMembers
+                                            collection_id={col.id}
+                                            members={(*members).clone()}
+                                            {role}
+                                            on_change={&on_members_change}
+                                        />
+                                        if !col.is_smart() {
+                                            <div class="text-xs pt-2">
+                                                if let Some(revs) = &*revisions {
+                                                    <div class="flex flex-col gap-1 text-left">
+                                                        if revs.is_empty() {
+                                                            <p>{"... no changes recorded yet ..."}</p>
+                                                        }
+                                                        {
+                                                            revs.iter().map(|rev| {
+                                                                let names = |media: &[Media]| media.iter().map(|m| m.to_string()).collect::<Vec<String>>().join(", ");
+                                                                html!{
+                                                                    <div class="flex justify-between items-center gap-2">
+                                                                        <span>
+                                                                            {format!(
+                                                                                "{} {} {}",
+                                                                                rev.created_at.map(|d| d.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default(),
+                                                                                rev.actor_name.clone().unwrap_or_default(),
+                                                                                rev.summary(),
+                                                                            )}
+                                                                            if !rev.added.entries.is_empty() {
+                                                                                <span class="text-success">{format!(" +{}", names(&rev.added.entries))}</span>
+                                                                            }
+                                                                            if !rev.removed.entries.is_empty() {
+                                                                                <span class="text-error">{format!(" -{}", names(&rev.removed.entries))}</span>
+                                                                            }
+                                                                        </span>
+                                                                        if role.can_edit() {
+                                                                            <button class="btn btn-xs" onclick={on_restore_revision(rev)}>{"Restore"}</button>
+                                                                        }
+                                                                    </div>
+                                                                }
+                                                            }).collect::<Html>()
+                                                        }
+                                                    </div>
+                                                } else {
+                                                    <a class="link" onclick={&on_show_history}>{"History"}</a>
+                                                }
+                                            </div>
+                                        }
+                                        if can_edit_entries {
+                                            <div class="flex flex-wrap gap-2 items-center pt-2 text-xs">
+                                                {format!("{} selected", selected_entries.len())}
+                                                <a class="link" onclick={&on_select_all}>{"Select all"}</a>
+                                                if !selected_entries.is_empty() {
+                                                    <a class="link" onclick={&on_clear_selection}>{"Clear"}</a>
+                                                    <select class="select select-bordered select-xs" onchange={&on_change_bulk_target}>
+                                                        <option value="" selected={bulk_target.is_none()}>{"To collection..."}</option>
+                                                        {
+                                                            collections.iter()
+                                                            .filter(|c| c.id != col.id && !c.is_smart())
+                                                            .map(|c| html!{
+                                                                <option value={c.id.to_string()} selected={*bulk_target == Some(c.id)}>{c.name.clone()}</option>
+                                                            })
+                                                            .collect::<Html>()
+                                                        }
+                                                    </select>
+                                                    <button class="btn btn-xs" onclick={on_bulk_move_or_copy(false)}>{"Move"}</button>
+                                                    <button class="btn btn-xs" onclick={on_bulk_move_or_copy(true)}>{"Copy"}</button>
+                                                    <button class="btn btn-xs btn-error" onclick={on_bulk.reform(|_: MouseEvent| BulkEntriesAction::Delete)}>{"Delete"}</button>
+                                                    <select class="select select-bordered select-xs" onchange={&on_bulk_rate}>
+                                                        <option value="" selected={true}>{"Rate..."}</option>
+                                                        { EntryMeta::RATING_RANGE.map(|r| html!{<option value={r.to_string()}>{format!("{r}/10")}</option>}).collect::<Html>() }
+                                                        <option value="clear">{"Clear rating"}</option>
+                                                    </select>
+                                                    <TagInput id="bulk_tags" on_add={&on_bulk_tag} placeholder="Tag selected..." />
+                                                }
+                                            </div>
+                                        }
                                         {
                                             col.collection.entries
                                             .iter()
                                             .map(|media| {
+                                                // Ranks come from the full list, whatever the media filter is hiding.
+                                                let rank = active_col.as_ref().and_then(|c| c.rank_of(media));
+                                                let draggable = rank.is_some() && can_edit_entries;
                                                 html!{
-                                                    <div class="pt-2">
+                                                    <div
+                                                        class="pt-2"
+                                                        draggable={draggable.to_string()}
+                                                        ondragstart={draggable.then(|| on_drag_start(media))}
+                                                        ondragover={draggable.then(|| on_drag_over.clone())}
+                                                        ondrop={rank.filter(|_| draggable).map(|r| on_drop(r - 1))}
+                                                    >
+                                                        if let Some(rank) = rank {
+                                                            <div class="flex gap-2 items-center text-sm font-bold">
+                                                                {format!("#{rank}")}
+                                                                if can_edit_entries {
+                                                                    <button class="btn btn-xs btn-ghost" disabled={rank == 1}
+                                                                        onclick={on_move_entry.reform({ let id = media.identity(); move |_: MouseEvent| (id.clone(), rank.saturating_sub(2)) })}>
+                                                                        {"▲"}
+                                                                    </button>
+                                                                    <button class="btn btn-xs btn-ghost"
+                                                                        onclick={on_move_entry.reform({ let id = media.identity(); move |_: MouseEvent| (id.clone(), rank) })}>
+                                                                        {"▼"}
+                                                                    </button>
+                                                                }
+                                                            </div>
+                                                        }
                                                         <MediaCard
                                                             media={media.clone()}
                                                             lite={true}  // TODO: Make user-toggle
+                                                            on_entry_meta_change={can_edit_entries.then(|| on_entry_meta_change(&col, media))}
                                                             // onclick={on_media_click}
-                                                        />
+                                                        >
+                                                            if let Some(by) = media.entry_meta().and_then(|m| m.added_by).filter(|_| members.len() > 1) {
+                                                                <span class="text-xs">{format!("added by {}", added_by_name(&members, by))}</span>
+                                                            }
+                                                            if let Media::TvShow(show) = media {
+                                                                <SeriesProgressControls
+                                                                    show={show.clone()}
+                                                                    progress={progress.get(&media.identity()).cloned()}
+                                                                    on_change={&on_progress_change}
+                                                                />
+                                                            }
+                                                            if can_edit_entries {
+                                                                <input
+                                                                    type="checkbox"
+                                                                    class="checkbox checkbox-xs"
+                                                                    checked={selected_entries.contains(&media.identity())}
+                                                                    onchange={on_toggle_selected(media)}
+                                                                />
+                                                                <button class="btn btn-xs btn-ghost" onclick={on_remove_entry(media)}>{"✕ Remove"}</button>
+                                                            }
+                                                        </MediaCard>
                                                     </div>
                                                 }
                                             }).collect::<Html>()
@@ -174,6 +1122,11 @@ pub fn collections_page() -> Html {
                             }
                         } else {
                            <p>{"... no collection selected ..."}</p>
+                           <p class="text-xs pt-2">
+                               <a class="link" href={api_collection_export_url(None, ExportFormat::Json)}>
+                                   {"Export all collections (JSON)"}
+                               </a>
+                           </p>
                         }
                     </div>
                     // if active_collection.is_empty() {
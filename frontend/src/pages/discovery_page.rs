@@ -10,25 +10,22 @@ use crate::components::media_card::MediaCard;
 use crate::components::media_selector::{MediaSelector, MediaSelectorOption};
 use crate::components::spinner::Spinner;
 use crate::router;
-use crate::store::{set_page_loading, set_show_alert, Store};
+use crate::store::{
+    set_page_loading, set_show_alert, set_show_undo_alert, set_store_collection, Store,
+};
 use crate::ui_helpers::get_value_from_input_by_id;
 use common::model::collections::{Media, UserCollection};
 use common::model::core::{DiscoveryMeta, Movie};
+use common::model::special::SpecialCollection;
 use gloo::console::console;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 use yew::prelude::*;
 use yew::{function_component, html, Html};
 use yew_router::hooks::use_navigator;
+use yew_router::prelude::Link;
 use yewdux::functional::use_store;
 
-#[derive(Debug, Clone)]
-enum DiscoveryRatingOption {
-    UpVote,
-    DownVote,
-    Skip,
-}
-
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
 struct DiscoverySchema {
     media: String,
@@ -63,6 +60,17 @@ pub fn discovery_page() -> Html {
     let collections = use_state(|| store.collections.clone().unwrap_or_default());
     let media_selector_option = use_state(|| MediaSelectorOption::Movies);
 
+    // Pick up changes made elsewhere, like an undo from the alert.
+    {
+        let collections = collections.clone();
+        use_effect_with(store.collections.clone(), move |cols| {
+            if let Some(cols) = cols {
+                collections.set(cols.clone());
+            }
+            || ()
+        });
+    }
+
     // Feels ridiculous walking these values down scope? Im not understanding something.
     let do_discovery = {
         let discovery_queue = discovery_queue.clone();
@@ -133,9 +141,10 @@ pub fn discovery_page() -> Html {
         })
     };
 
-    let do_rating = |media: &Media, rating: DiscoveryRatingOption| {
+    let do_rating = |media: &Media, special: SpecialCollection| {
         let discovery_queue = discovery_queue.clone();
         let collections = collections.clone();
+        let dispatch = dispatch.clone();
         let media = media.clone();
 
         Callback::from(move |_: MouseEvent| {
@@ -143,43 +152,14 @@ pub fn discovery_page() -> Html {
             if let Some(media) = discovery_queue.get(0) {
                 let cols = collections.clone();
                 let dq = discovery_queue.clone();
+                let dispatch = dispatch.clone();
 
                 // Get a mutable of the local UC we're updating
-                let mut uc: UserCollection = match rating {
-                    DiscoveryRatingOption::UpVote => {
-                        let mut tu_col = cols
-                            .iter()
-                            .filter(|uc| uc.special.clone().is_some_and(|s| s == "thumbsup"))
-                            .collect::<Vec<&UserCollection>>();
-
-                        tu_col
-                            .get_mut(0)
-                            .expect("Missing Thumbs Up Collection?")
-                            .to_owned()
-                    }
-                    DiscoveryRatingOption::DownVote => {
-                        let mut td_id = cols
-                            .iter()
-                            .filter(|uc| uc.special.clone().is_some_and(|s| s == "thumbsdown"))
-                            .collect::<Vec<&UserCollection>>();
-
-                        td_id
-                            .get_mut(0)
-                            .expect("Missing Thumbs Down Collection?")
-                            .to_owned()
-                    }
-                    DiscoveryRatingOption::Skip => {
-                        let mut sk_id = cols
-                            .iter()
-                            .filter(|uc| uc.special.clone().is_some_and(|s| s == "skipped"))
-                            .collect::<Vec<&UserCollection>>();
-
-                        sk_id
-                            .get_mut(0)
-                            .expect("Missing Skipped Collection?")
-                            .to_owned()
-                    }
-                };
+                let mut uc: UserCollection = cols
+                    .iter()
+                    .find(|uc| uc.is_special(special))
+                    .unwrap_or_else(|| panic!("Missing {} Collection?", special.name()))
+                    .to_owned();
 
                 // Push our new item into our mutable collection
                 uc.collection.entries.push(media.clone());
@@ -195,6 +175,13 @@ pub fn discovery_page() -> Html {
                 wasm_bindgen_futures::spawn_local(async move {
                     match api_patch_user_collection(uc.clone()).await {
                         Ok(collection) => {
+                            // Misclicks happen, so make it easy to take back.
+                            set_show_undo_alert(
+                                format!("Added {} to {}", media_clone, collection.name),
+                                collection.id,
+                                &dispatch,
+                            );
+                            set_store_collection(collection.clone(), &dispatch);
                             new_cols.push(collection);
                             cols.set(new_cols.clone());
 
@@ -277,6 +264,9 @@ pub fn discovery_page() -> Html {
                                         <Spinner />
                                     } else {
                                         {"Hi! Your discovery queue is empty. 😟"}
+                                        <Link<router::Route> to={router::Route::StarterPacksPage} classes="link text-sm">
+                                            {"New here? Rate a starter pack first."}
+                                        </Link<router::Route>>
                                     }
                                 </h2>
                             </div>
@@ -295,23 +285,38 @@ pub fn discovery_page() -> Html {
                                             </a>
                                             <button
                                                 class="basis-1/4 btn btn-outline btn-error"
-                                                onclick={do_rating(media, DiscoveryRatingOption::DownVote)}>
+                                                onclick={do_rating(media, SpecialCollection::ThumbsDown)}>
                                                 <FaceFrown />
                                             </button>
                                             <button
                                                 class="btn btn-ghost"
-                                                onclick={do_rating(media, DiscoveryRatingOption::Skip)}>
+                                                title="Not interested"
+                                                onclick={do_rating(media, SpecialCollection::Skipped)}>
                                                 {"Skip"}
                                             </button>
                                             <button
                                                 class="basis-1/4 btn btn-outline btn-success"
-                                                onclick={do_rating(media, DiscoveryRatingOption::UpVote)}>
+                                                onclick={do_rating(media, SpecialCollection::ThumbsUp)}>
                                                 <FaceSmile />
                                             </button>
                                             <a class="btn btn-ghost" onclick={on_shuffle(ShuffleDirection::Right)}>
                                                 {"❯"}
                                             </a>
                                         </div>
+                                        <div class="card-actions justify-center pt-2">
+                                            <button
+                                                class="btn btn-sm btn-ghost"
+                                                title="Already seen it"
+                                                onclick={do_rating(media, SpecialCollection::Seen)}>
+                                                {"👁 Seen it"}
+                                            </button>
+                                            <button
+                                                class="btn btn-sm btn-ghost"
+                                                title="Want to watch"
+                                                onclick={do_rating(media, SpecialCollection::Watchlist)}>
+                                                {"📋 Watchlist"}
+                                            </button>
+                                        </div>
                                     </MediaCard>
                                 }
                             }).collect::<Html>()
@@ -0,0 +1,245 @@
+use crate::api::friends_api::{
+    api_accept_friend_request, api_block_friend, api_decline_friend_request, api_get_blocked,
+    api_get_friend_collections, api_get_friend_requests, api_get_friends, api_send_friend_request,
+    api_unblock_friend, api_unfriend,
+};
+use crate::router::{self, Route};
+use crate::store::{set_page_loading, set_show_alert, Store};
+use crate::ui_helpers::{get_value_from_input_by_id, set_value_for_input_by_id};
+use common::model::collections::UserCollection;
+use common::model::friends::{Friend, Friendship};
+use gloo::console::console;
+use uuid::Uuid;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::functional::use_store;
+
+#[derive(Debug, Clone, Copy)]
+enum FriendAction {
+    Accept,
+    Decline,
+    Block,
+    Unblock,
+    Unfriend,
+}
+
+#[function_component(FriendsPage)]
+pub fn friends_page() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let navigator = use_navigator().unwrap();
+    if store.auth_user.is_none() {
+        navigator.push(&router::Route::LoginPage);
+    }
+    let friends = use_state(Vec::<Friend>::new);
+    let requests = use_state(Vec::<Friend>::new);
+    let blocked = use_state(Vec::<Friend>::new);
+    let friend_cols: UseStateHandle<Option<(Friend, Vec<UserCollection>)>> = use_state(|| None);
+    // Bumped after every change so the lists get fetched again.
+    let refresh = use_state(|| 0_u32);
+
+    {
+        let friends = friends.clone();
+        let requests = requests.clone();
+        let blocked = blocked.clone();
+        let dispatch = dispatch.clone();
+        use_effect_with(*refresh, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                set_page_loading(true, &dispatch);
+                let (friends_res, requests_res, blocked_res) = futures::join!(
+                    api_get_friends(),
+                    api_get_friend_requests(),
+                    api_get_blocked()
+                );
+                set_page_loading(false, &dispatch);
+                match (friends_res, requests_res, blocked_res) {
+                    (Ok(f), Ok(r), Ok(b)) => {
+                        friends.set(f);
+                        requests.set(r);
+                        blocked.set(b);
+                    }
+                    (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                        console!(format!("Error getting friends: {e}"));
+                        if e.contains("You are not logged in") {
+                            set_show_alert(e.to_string(), &dispatch);
+                            navigator.push(&router::Route::LoginPage);
+                        }
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_send_request = {
+        let refresh = refresh.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let refresh = refresh.clone();
+            let dispatch = dispatch.clone();
+            let email =
+                get_value_from_input_by_id("#friend_request_email").unwrap_or(String::from(""));
+            if email.is_empty() {
+                return;
+            }
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_send_friend_request(email).await {
+                    Ok(_) => {
+                        set_value_for_input_by_id("#friend_request_email", String::new());
+                        set_show_alert("Friend request sent".to_string(), &dispatch);
+                        refresh.set(*refresh + 1);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let do_action = |friendship_id: Uuid, action: FriendAction| {
+        let refresh = refresh.clone();
+        let friend_cols = friend_cols.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let refresh = refresh.clone();
+            let friend_cols = friend_cols.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let res: Result<Friendship, String> = match action {
+                    FriendAction::Accept => api_accept_friend_request(friendship_id).await,
+                    FriendAction::Decline => api_decline_friend_request(friendship_id).await,
+                    FriendAction::Block => api_block_friend(friendship_id).await,
+                    FriendAction::Unblock => api_unblock_friend(friendship_id).await,
+                    FriendAction::Unfriend => api_unfriend(friendship_id).await,
+                };
+                match res {
+                    Ok(_) => {
+                        friend_cols.set(None);
+                        refresh.set(*refresh + 1);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let show_collections = |friend: &Friend| {
+        let friend = friend.clone();
+        let friend_cols = friend_cols.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let friend = friend.clone();
+            let friend_cols = friend_cols.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_get_friend_collections(friend.user_id).await {
+                    Ok(cols) => friend_cols.set(Some((friend, cols))),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <section class="grid justify-items-stretch justify-center">
+            <div class="grid lg:w-[65vw] sm:w-[95wv]">
+                <div class="lg:w-3/5 justify-self-center flex flex-col gap-4">
+                    <div class="flex gap-2 pt-2">
+                        <input
+                            id="friend_request_email"
+                            class="input input-bordered grow"
+                            placeholder="Friend's email"
+                            disabled={store.page_loading}
+                        />
+                        <button class="btn" onclick={on_send_request} disabled={store.page_loading}>
+                            {"Add Friend"}
+                        </button>
+                    </div>
+                    if !requests.is_empty() {
+                        <div class="card bg-base-200">
+                            <div class="card-body p-4">
+                                <h2 class="card-title">{"Requests"}</h2>
+                                {
+                                    requests.iter().map(|req| {
+                                        html!{
+                                            <div class="flex justify-between items-center">
+                                                <span>{req.name.clone()}</span>
+                                                if req.incoming.unwrap_or(false) {
+                                                    <div class="flex gap-2">
+                                                        <button class="btn btn-xs btn-success" onclick={do_action(req.friendship_id, FriendAction::Accept)}>{"Accept"}</button>
+                                                        <button class="btn btn-xs" onclick={do_action(req.friendship_id, FriendAction::Decline)}>{"Decline"}</button>
+                                                        <button class="btn btn-xs btn-error" onclick={do_action(req.friendship_id, FriendAction::Block)}>{"Block"}</button>
+                                                    </div>
+                                                } else {
+                                                    <div class="flex gap-2 items-center">
+                                                        <span class="text-xs">{"Pending..."}</span>
+                                                        <button class="btn btn-xs btn-ghost" onclick={do_action(req.friendship_id, FriendAction::Unfriend)}>{"Cancel"}</button>
+                                                    </div>
+                                                }
+                                            </div>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </div>
+                        </div>
+                    }
+                    <div class="card bg-base-200">
+                        <div class="card-body p-4">
+                            <h2 class="card-title">{"Friends"}</h2>
+                            if friends.is_empty() {
+                                <p>{"... no friends yet ..."}</p>
+                            }
+                            {
+                                friends.iter().map(|friend| {
+                                    html!{
+                                        <div class="flex justify-between items-center">
+                                            <a class="link" onclick={show_collections(friend)}>{friend.name.clone()}</a>
+                                            <div class="flex gap-2">
+                                                <button class="btn btn-xs btn-ghost" onclick={do_action(friend.friendship_id, FriendAction::Unfriend)}>{"Unfriend"}</button>
+                                                <button class="btn btn-xs btn-ghost" onclick={do_action(friend.friendship_id, FriendAction::Block)}>{"Block"}</button>
+                                            </div>
+                                        </div>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    </div>
+                    if !blocked.is_empty() {
+                        <div class="card bg-base-200">
+                            <div class="card-body p-4">
+                                <h2 class="card-title">{"Blocked"}</h2>
+                                {
+                                    blocked.iter().map(|b| {
+                                        html!{
+                                            <div class="flex justify-between items-center">
+                                                <span>{b.name.clone()}</span>
+                                                <button class="btn btn-xs" onclick={do_action(b.friendship_id, FriendAction::Unblock)}>{"Unblock"}</button>
+                                            </div>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </div>
+                        </div>
+                    }
+                    if let Some((friend, cols)) = &*friend_cols {
+                        <div class="card bg-base-200">
+                            <div class="card-body p-4">
+                                <h2 class="card-title">{format!("{}'s Collections", friend.name)}</h2>
+                                if cols.is_empty() {
+                                    <p>{"... nothing shared with you ..."}</p>
+                                }
+                                {
+                                    cols.iter().map(|col| {
+                                        html!{
+                                            <Link<Route> to={Route::SharedCollectionPage { id: col.id.to_string() }} classes="link">
+                                                {format!("{} ({})", col.name, col.collection.entries.len())}
+                                            </Link<Route>>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </div>
+                        </div>
+                    }
+                </div>
+            </div>
+        </section>
+    }
+}
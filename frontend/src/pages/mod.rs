@@ -1,7 +1,12 @@
+pub mod channel_page;
 pub mod collections_page;
 pub mod discovery_page;
+pub mod friends_page;
 pub mod home_page;
 pub mod login_page;
 pub mod profile_page;
 pub mod register_page;
 pub mod schedules_page;
+pub mod shared_collection_page;
+pub mod starter_packs_page;
+pub mod stats_page;
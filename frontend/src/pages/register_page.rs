@@ -152,7 +152,8 @@ pub fn register_page() -> Html {
                             Ok(_) => {
                                 set_page_loading(false, &dispatch);
                                 set_show_alert(
-                                    "Account registered successfully".to_string(),
+                                    "Account registered! Log in and try a starter pack to get going."
+                                        .to_string(),
                                     &dispatch,
                                 );
                                 navigator.push(&router::Route::LoginPage);
@@ -1,15 +1,513 @@
+use crate::api::schedules_api::{
+    api_create_schedule, api_delete_schedule, api_get_schedules, api_reflow_schedule,
+    api_schedule_calendar_url, api_schedule_guide_url, api_schedule_playlist_url,
+    api_update_schedule,
+};
+use crate::api::user_api::api_calendar_token;
+use crate::components::new_channel::NewChannel;
+use crate::components::routine_editor::RoutineEditor;
+use crate::components::schedule_generator::ScheduleGenerator;
+use crate::components::stream_urls::StreamUrlsEditor;
+use crate::router;
+use crate::store::{set_page_loading, set_show_alert, set_store_schedules, Store};
+use crate::ui_helpers::get_value_from_input_event;
+use chrono::prelude::*;
+use common::model::schedule_reflow::{find_conflicts, ReflowEdit, ReflowPolicy, ReflowSchema};
+use common::model::schedules::{
+    NewScheduleSchema, Schedule, ScheduleSlot, ScheduleSlots, UpdateScheduleSchema,
+};
+use common::model::tags::Tags;
+use gloo::console::console;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
 use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+// The slots by the (local) day they start on, with where they are in the schedule.
+fn slots_by_day(slots: &ScheduleSlots) -> BTreeMap<NaiveDate, Vec<(usize, &ScheduleSlot)>> {
+    let mut days: BTreeMap<NaiveDate, Vec<(usize, &ScheduleSlot)>> = BTreeMap::new();
+    for (index, slot) in slots.slots.iter().enumerate() {
+        days.entry(slot.start.with_timezone(&Local).date_naive())
+            .or_default()
+            .push((index, slot));
+    }
+    days
+}
+
+// Swaps `updated` in for the schedule with the same id, or drops it when `removed`.
+fn replace_schedule(
+    updated: Schedule,
+    removed: bool,
+    schedules: &UseStateHandle<Vec<Schedule>>,
+    dispatch: &Dispatch<Store>,
+) {
+    let mut next: Vec<Schedule> = schedules
+        .iter()
+        .filter(|s| s.id != updated.id)
+        .cloned()
+        .collect();
+    if !removed {
+        match schedules.iter().position(|s| s.id == updated.id) {
+            Some(i) => next.insert(i, updated),
+            None => next.push(updated),
+        }
+    }
+    set_store_schedules(next.clone(), dispatch);
+    schedules.set(next);
+}
 
 #[function_component(SchedulesPage)]
 pub fn schedules_page() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let navigator = use_navigator().unwrap();
+    if store.auth_user.is_none() {
+        navigator.push(&router::Route::LoginPage);
+    }
+
+    let schedules: UseStateHandle<Vec<Schedule>> =
+        use_state(|| store.schedules.clone().unwrap_or_default());
+    let active_id: UseStateHandle<Option<Uuid>> = use_state(|| None);
+    let new_name = use_state(String::new);
+    // Only fetched once the user asks to subscribe.
+    let calendar_token: UseStateHandle<Option<String>> = use_state(|| None);
+    let policy = use_state(ReflowPolicy::default);
+    let late_minutes = use_state(|| 30_u32);
+
+    {
+        let schedules = schedules.clone();
+        let active_id = active_id.clone();
+        let dispatch = dispatch.clone();
+        let navigator = navigator.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                set_page_loading(true, &dispatch);
+                let response = api_get_schedules().await;
+                set_page_loading(false, &dispatch);
+                match response {
+                    Ok(s) => {
+                        active_id.set(s.first().map(|s| s.id));
+                        set_store_schedules(s.clone(), &dispatch);
+                        schedules.set(s);
+                    }
+                    Err(e) => {
+                        console!(format!("Error getting schedules: {e}"));
+                        set_show_alert(e.to_string(), &dispatch);
+                        if e.contains("You are not logged in") {
+                            navigator.push(&router::Route::LoginPage);
+                        }
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let active: Option<Schedule> = active_id
+        .and_then(|id| schedules.iter().find(|s| s.id == id))
+        .cloned();
+    let conflicts = active
+        .as_ref()
+        .map(|s| find_conflicts(&s.schedule))
+        .unwrap_or_default();
+
+    let on_select = {
+        let active_id = active_id.clone();
+        Callback::from(move |event: Event| {
+            let selected = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .unwrap()
+                .value();
+            active_id.set(Uuid::parse_str(&selected).ok());
+        })
+    };
+
+    let on_name_input = {
+        let new_name = new_name.clone();
+        Callback::from(move |event: InputEvent| {
+            new_name.set(get_value_from_input_event(event));
+        })
+    };
+
+    let on_create = {
+        let schedules = schedules.clone();
+        let active_id = active_id.clone();
+        let new_name = new_name.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let name = new_name.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let schedules = schedules.clone();
+            let active_id = active_id.clone();
+            let new_name = new_name.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let new_schedule = NewScheduleSchema {
+                    name,
+                    schedule: ScheduleSlots::default(),
+                    tags: Tags::default(),
+                };
+                match api_create_schedule(new_schedule).await {
+                    Ok(schedule) => {
+                        active_id.set(Some(schedule.id));
+                        new_name.set(String::new());
+                        replace_schedule(schedule, false, &schedules, &dispatch);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_toggle_lock = {
+        let schedules = schedules.clone();
+        let active = active.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(schedule) = active.clone() else {
+                return;
+            };
+            let schedules = schedules.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let update = UpdateScheduleSchema {
+                    locked: Some(!schedule.locked),
+                    ..Default::default()
+                };
+                match api_update_schedule(schedule.id, update).await {
+                    Ok(updated) => replace_schedule(updated, false, &schedules, &dispatch),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_delete = {
+        let schedules = schedules.clone();
+        let active = active.clone();
+        let active_id = active_id.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(schedule) = active.clone() else {
+                return;
+            };
+            let confirmed = web_sys::window()
+                .and_then(|w| {
+                    w.confirm_with_message(&format!("Delete the schedule '{}'?", schedule.name))
+                        .ok()
+                })
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            let schedules = schedules.clone();
+            let active_id = active_id.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_delete_schedule(schedule.id).await {
+                    Ok(deleted) => {
+                        active_id.set(None);
+                        replace_schedule(deleted, true, &schedules, &dispatch);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_calendar_token = |rotate: bool| {
+        let calendar_token = calendar_token.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            if rotate {
+                let confirmed = web_sys::window()
+                    .and_then(|w| {
+                        w.confirm_with_message(
+                            "Make a new link? Calendars subscribed with the old one stop updating.",
+                        )
+                        .ok()
+                    })
+                    .unwrap_or(false);
+                if !confirmed {
+                    return;
+                }
+            }
+            let calendar_token = calendar_token.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_calendar_token(rotate).await {
+                    Ok(token) => calendar_token.set(Some(token)),
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_policy = {
+        let policy = policy.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+                .unwrap()
+                .value();
+            policy.set(match value.as_str() {
+                "drop" => ReflowPolicy::Drop,
+                "split" => ReflowPolicy::Split,
+                _ => ReflowPolicy::Shift,
+            });
+        })
+    };
+
+    let on_late_input = {
+        let late_minutes = late_minutes.clone();
+        Callback::from(move |event: InputEvent| {
+            late_minutes.set(get_value_from_input_event(event).parse().unwrap_or(0));
+        })
+    };
+
+    // Every edit to the slots goes through the server, which moves the rest to fit.
+    let on_reflow = |schedule_id: Uuid, edit: ReflowEdit| {
+        let schedules = schedules.clone();
+        let policy = policy.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            // Running late as of when the button is pressed, not when it was drawn.
+            let edit = match &edit {
+                ReflowEdit::Delay { minutes, .. } => ReflowEdit::Delay {
+                    at: Utc::now(),
+                    minutes: *minutes,
+                },
+                edit => edit.clone(),
+            };
+            let reflow = ReflowSchema {
+                edit,
+                policy: *policy,
+                dry_run: false,
+            };
+            let schedules = schedules.clone();
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_reflow_schedule(schedule_id, reflow).await {
+                    Ok(reflowed) => {
+                        if !reflowed.dropped.is_empty() {
+                            set_show_alert(
+                                format!(
+                                    "Taken off: {}",
+                                    reflowed
+                                        .dropped
+                                        .iter()
+                                        .map(|s| s.title())
+                                        .collect::<Vec<String>>()
+                                        .join(", ")
+                                ),
+                                &dispatch,
+                            );
+                        }
+                        replace_schedule(reflowed.schedule, false, &schedules, &dispatch);
+                    }
+                    Err(e) => set_show_alert(e, &dispatch),
+                }
+            });
+        })
+    };
+
+    let on_generated = {
+        let schedules = schedules.clone();
+        let active_id = active_id.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |schedule: Schedule| {
+            active_id.set(Some(schedule.id));
+            replace_schedule(schedule, false, &schedules, &dispatch);
+        })
+    };
+    let on_created = on_generated.clone();
+    let on_routine_created = on_generated.clone();
+
+    let on_routine_saved = {
+        let schedules = schedules.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |schedule: Schedule| {
+            replace_schedule(schedule, false, &schedules, &dispatch);
+        })
+    };
+
     html! {
-      <>
-        // <Header />
-        <section class="bg-ct-blue-600 min-h-screen pt-20">
-            <div class="max-w-4xl mx-auto bg-ct-dark-100 rounded-md h-[20rem] flex justify-center items-center">
-                <p class="text-3xl font-semibold">{"Schedules"}</p>
+        <section class="grid justify-items-stretch justify-center">
+            <div class="grid lg:w-[65vw] sm:w-[95wv]">
+                <div class="lg:w-3/5 justify-self-center flex flex-col gap-4 pt-4">
+                    <div class="join">
+                        <input
+                            class="input input-bordered join-item grow"
+                            placeholder="New schedule name"
+                            value={(*new_name).clone()}
+                            oninput={on_name_input}
+                        />
+                        <button class="btn join-item" onclick={on_create} disabled={new_name.trim().is_empty()}>
+                            {"Create"}
+                        </button>
+                    </div>
+                    <ScheduleGenerator {on_generated} />
+                    <NewChannel {on_created} />
+                    <RoutineEditor schedule_id={None::<Uuid>} on_saved={on_routine_created} />
+                    if schedules.is_empty() {
+                        <p>{"... no schedules yet ..."}</p>
+                    } else {
+                        <select class="select select-bordered" onchange={on_select}>
+                            {
+                                schedules.iter().map(|s| html!{
+                                    <option value={s.id.to_string()} selected={Some(s.id) == *active_id}>
+                                        {
+                                            match (s.locked, s.is_channel()) {
+                                                (true, _) => format!("🔒 {}", s.name),
+                                                (false, true) => format!("📺 {}", s.name),
+                                                (false, false) => s.name.clone(),
+                                            }
+                                        }
+                                    </option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    }
+                    if let Some(schedule) = active {
+                        <div class="flex gap-2 justify-end items-center">
+                            <a class="link text-sm" href={api_schedule_calendar_url(schedule.id, None)}>{"Download .ics"}</a>
+                            <a class="link text-sm" href={api_schedule_guide_url(schedule.id, None)}>{"XMLTV"}</a>
+                            <a class="link text-sm" href={api_schedule_playlist_url(schedule.id, None)}>{"M3U"}</a>
+                            <button class="btn btn-sm btn-ghost" onclick={on_toggle_lock}>
+                                {if schedule.locked { "Unlock" } else { "Lock" }}
+                            </button>
+                            <button class="btn btn-sm btn-outline btn-error" onclick={on_delete} disabled={schedule.locked}>
+                                {"Delete"}
+                            </button>
+                        </div>
+                        match &*calendar_token {
+                            Some(token) => html!{
+                                <div class="join w-full">
+                                    <input
+                                        class="input input-bordered input-sm join-item grow"
+                                        readonly=true
+                                        title="Add this to your calendar app as a subscription"
+                                        value={api_schedule_calendar_url(schedule.id, Some(token))}
+                                    />
+                                    <button class="btn btn-sm join-item" onclick={on_calendar_token(true)}>{"New link"}</button>
+                                </div>
+                                <input
+                                    class="input input-bordered input-sm w-full"
+                                    readonly=true
+                                    title="The guide for your IPTV player or media center's EPG"
+                                    value={api_schedule_guide_url(schedule.id, Some(token))}
+                                />
+                                <input
+                                    class="input input-bordered input-sm w-full"
+                                    readonly=true
+                                    title="The playlist for your IPTV player"
+                                    value={api_schedule_playlist_url(schedule.id, Some(token))}
+                                />
+                            },
+                            None => html!{
+                                <button class="btn btn-sm btn-ghost justify-self-start" onclick={on_calendar_token(false)}>
+                                    {"Subscribe in your calendar"}
+                                </button>
+                            },
+                        }
+                        <StreamUrlsEditor schedule_id={schedule.id} />
+                        if !schedule.is_channel() && !schedule.locked {
+                            <RoutineEditor schedule_id={Some(schedule.id)} on_saved={on_routine_saved} />
+                        }
+                        if !schedule.is_channel() && !schedule.schedule.slots.is_empty() {
+                            <div class="flex flex-wrap gap-2 items-center text-sm">
+                                <span>{"Running late by"}</span>
+                                <input
+                                    type="number"
+                                    min="1"
+                                    class="input input-bordered input-sm w-20"
+                                    value={late_minutes.to_string()}
+                                    oninput={on_late_input}
+                                />
+                                <span>{"minutes"}</span>
+                                <button
+                                    class="btn btn-sm"
+                                    onclick={on_reflow(schedule.id, ReflowEdit::Delay { at: Utc::now(), minutes: *late_minutes })}
+                                    disabled={schedule.locked || *late_minutes == 0}>
+                                    {"Delay"}
+                                </button>
+                                <select class="select select-bordered select-sm" onchange={on_policy} title="What happens to slots that no longer fit">
+                                    <option value="shift" selected={*policy == ReflowPolicy::Shift}>{"Shift the rest along"}</option>
+                                    <option value="drop" selected={*policy == ReflowPolicy::Drop}>{"Drop what doesn't fit"}</option>
+                                    <option value="split" selected={*policy == ReflowPolicy::Split}>{"Split across windows"}</option>
+                                </select>
+                            </div>
+                        }
+                        if !conflicts.is_empty() {
+                            <div class="alert alert-warning text-sm flex flex-col items-start">
+                                {
+                                    conflicts.iter().map(|c| html!{
+                                        <span>{c.describe(&schedule.schedule)}</span>
+                                    }).collect::<Html>()
+                                }
+                            </div>
+                        }
+                        if schedule.is_channel() {
+                            <p>
+                                {"📺 A channel, playing its collection on a loop. "}
+                                <Link<router::Route> to={router::Route::ChannelPage { id: schedule.id.to_string() }} classes="link">
+                                    {"Open guide"}
+                                </Link<router::Route>>
+                            </p>
+                        } else if schedule.schedule.slots.is_empty() {
+                            <p>{"... nothing scheduled yet ..."}</p>
+                        }
+                        {
+                            slots_by_day(&schedule.schedule).into_iter().map(|(day, slots)| html!{
+                                <div class="card bg-base-200">
+                                    <div class="card-body p-4">
+                                        <h2 class="card-title">{day.format("%A %e %B %Y").to_string()}</h2>
+                                        <table class="table table-sm">
+                                            <tbody>
+                                                {
+                                                    slots.iter().map(|(index, slot)| html!{
+                                                        <tr>
+                                                            <td class="whitespace-nowrap">
+                                                                {format!(
+                                                                    "{} – {}",
+                                                                    slot.start.with_timezone(&Local).format("%H:%M"),
+                                                                    slot.end().with_timezone(&Local).format("%H:%M")
+                                                                )}
+                                                            </td>
+                                                            <td>
+                                                                {slot.title()}
+                                                                if let Some(meta) = slot.media.baing_meta() {
+                                                                    <p class="text-xs opacity-70">{meta.reason.clone()}</p>
+                                                                }
+                                                            </td>
+                                                            <td class="text-right">{format!("{}m", slot.duration)}</td>
+                                                            <td class="text-right">
+                                                                <button
+                                                                    class="btn btn-xs btn-ghost"
+                                                                    title="Take this off the schedule"
+                                                                    onclick={on_reflow(schedule.id, ReflowEdit::Remove { index: *index })}
+                                                                    disabled={schedule.locked}>
+                                                                    {"✕"}
+                                                                </button>
+                                                            </td>
+                                                        </tr>
+                                                    }).collect::<Html>()
+                                                }
+                                            </tbody>
+                                        </table>
+                                    </div>
+                                </div>
+                            }).collect::<Html>()
+                        }
+                    }
+                </div>
             </div>
         </section>
-      </>
     }
 }
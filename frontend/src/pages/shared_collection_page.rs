@@ -0,0 +1,115 @@
+use crate::api::collections_api::{api_copy_collection, api_get_shared_collection};
+use crate::components::media_card::MediaCard;
+use crate::components::spinner::Spinner;
+use crate::router;
+use crate::store::{set_page_loading, set_show_alert, Store};
+use common::model::collections::UserCollection;
+use gloo::console::console;
+use yew::prelude::*;
+use yew_router::hooks::use_navigator;
+use yewdux::functional::use_store;
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct SharedCollectionPageProps {
+    pub id: String,
+}
+
+// Read-only view of somebody else's (public or friends) collection.
+#[function_component(SharedCollectionPage)]
+pub fn shared_collection_page(props: &SharedCollectionPageProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let navigator = use_navigator().unwrap();
+    let shared_col: UseStateHandle<Option<UserCollection>> = use_state(|| None);
+    let error = use_state(|| None::<String>);
+
+    {
+        let shared_col = shared_col.clone();
+        let error = error.clone();
+        let dispatch = dispatch.clone();
+        use_effect_with(props.id.clone(), move |id| {
+            let id = id.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                set_page_loading(true, &dispatch);
+                match api_get_shared_collection(&id).await {
+                    Ok(col) => shared_col.set(Some(col)),
+                    Err(e) => {
+                        console!(format!("Error getting shared collection: {e}"));
+                        error.set(Some(e));
+                    }
+                }
+                set_page_loading(false, &dispatch);
+            });
+            || ()
+        });
+    }
+
+    let on_copy = {
+        let shared_col = shared_col.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let dispatch = dispatch.clone();
+            let navigator = navigator.clone();
+            if let Some(col) = (*shared_col).clone() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_copy_collection(col.id).await {
+                        Ok(copy) => {
+                            set_show_alert(format!("Copied to '{}'", copy.name), &dispatch);
+                            dispatch.reduce_mut(move |store| {
+                                if let Some(cols) = store.collections.as_mut() {
+                                    cols.push(copy);
+                                }
+                            });
+                            navigator.push(&router::Route::CollectionsPage);
+                        }
+                        Err(e) => set_show_alert(e, &dispatch),
+                    }
+                });
+            }
+        })
+    };
+
+    let can_copy = match (&store.auth_user, &*shared_col) {
+        (Some(user), Some(col)) => user.id != col.owner_id,
+        _ => false,
+    };
+
+    let body = match (&*shared_col, &*error) {
+        (Some(col), _) => html! {
+            <>
+                <div class="flex justify-between items-center pb-2 pt-2">
+                    <h2 class="text-2xl font-bold">
+                        {format!("{} ({})", col.name, col.collection.entries.len())}
+                    </h2>
+                    if can_copy {
+                        <button class="btn btn-outline btn-sm" onclick={on_copy}>
+                            {"Copy to my collections"}
+                        </button>
+                    }
+                </div>
+                {
+                    col.collection.entries
+                    .iter()
+                    .map(|media| {
+                        html!{
+                            <div class="pt-2">
+                                <MediaCard media={media.clone()} lite={true} />
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+            </>
+        },
+        (None, Some(e)) => html! {<p>{e.clone()}</p>},
+        (None, None) => html! {<Spinner />},
+    };
+
+    html! {
+        <section class="grid justify-items-stretch justify-center">
+            <div class="grid lg:w-[65vw] sm:w-[95wv]">
+                <div class="lg:w-3/5 grow flex flex-col justify-self-center justify-center">
+                    {body}
+                </div>
+            </div>
+        </section>
+    }
+}
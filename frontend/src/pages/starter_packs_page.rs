@@ -0,0 +1,272 @@
+use crate::api::coalesce_media;
+use crate::api::starter_packs_api::{api_apply_starter_pack, api_get_starter_packs};
+use crate::components::figures::{FaceFrown, FaceSmile};
+use crate::components::media_card::MediaCard;
+use crate::components::spinner::Spinner;
+use crate::router;
+use crate::store::{set_page_loading, set_show_alert, set_store_collection, Store};
+use common::model::collections::Media;
+use common::model::starter_packs::StarterPack;
+use gloo::console::console;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::functional::use_store;
+
+#[derive(Clone, Copy)]
+enum Swipe {
+    Up,
+    Down,
+    Pass,
+}
+
+// Onboarding: pick a curated pack and swipe through it, so discovery has some taste to go on.
+#[function_component(StarterPacksPage)]
+pub fn starter_packs_page() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let navigator = use_navigator().unwrap();
+    if store.auth_user.is_none() {
+        navigator.push(&router::Route::LoginPage);
+    }
+    let tmdb_key = store
+        .auth_user
+        .as_ref()
+        .and_then(|u| u.tmdb_api_key.clone());
+
+    let packs: UseStateHandle<Vec<StarterPack>> = use_state(Vec::new);
+    let active_pack: UseStateHandle<Option<StarterPack>> = use_state(|| None);
+    let queue: UseStateHandle<Vec<Media>> = use_state(Vec::new);
+    let thumbs_up: UseStateHandle<Vec<Media>> = use_state(Vec::new);
+    let thumbs_down: UseStateHandle<Vec<Media>> = use_state(Vec::new);
+
+    {
+        let packs = packs.clone();
+        let dispatch = dispatch.clone();
+        let navigator = navigator.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                set_page_loading(true, &dispatch);
+                let response = api_get_starter_packs().await;
+                set_page_loading(false, &dispatch);
+                match response {
+                    Ok(p) => packs.set(p),
+                    Err(e) => {
+                        console!(format!("Error getting starter packs: {e}"));
+                        set_show_alert(e.to_string(), &dispatch);
+                        if e.contains("You are not logged in") {
+                            navigator.push(&router::Route::LoginPage);
+                        }
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_pick = |pack: &StarterPack| {
+        let pack = pack.clone();
+        let active_pack = active_pack.clone();
+        let queue = queue.clone();
+        let thumbs_up = thumbs_up.clone();
+        let thumbs_down = thumbs_down.clone();
+        let dispatch = dispatch.clone();
+        let tmdb_key = tmdb_key.clone();
+        Callback::from(move |_: MouseEvent| {
+            let pack = pack.clone();
+            let active_pack = active_pack.clone();
+            let queue = queue.clone();
+            let dispatch = dispatch.clone();
+            let tmdb_key = tmdb_key.clone();
+            thumbs_up.set(vec![]);
+            thumbs_down.set(vec![]);
+            wasm_bindgen_futures::spawn_local(async move {
+                set_page_loading(true, &dispatch);
+                // The bundled packs are bare titles, TMDB fills in the posters and overviews.
+                let entries = match tmdb_key {
+                    Some(key) => coalesce_media(&key, &pack.entries.entries)
+                        .await
+                        .unwrap_or_else(|e| {
+                            console!(format!("Error Coalescing with TMDB: {}", e));
+                            pack.entries.entries.to_vec()
+                        }),
+                    None => pack.entries.entries.to_vec(),
+                };
+                set_page_loading(false, &dispatch);
+                queue.set(entries);
+                active_pack.set(Some(pack));
+            });
+        })
+    };
+
+    let on_swipe = |swipe: Swipe| {
+        let queue = queue.clone();
+        let thumbs_up = thumbs_up.clone();
+        let thumbs_down = thumbs_down.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut rest = queue.to_vec();
+            if rest.is_empty() {
+                return;
+            }
+            let media = rest.remove(0);
+            match swipe {
+                Swipe::Up => thumbs_up.set([thumbs_up.to_vec(), vec![media]].concat()),
+                Swipe::Down => thumbs_down.set([thumbs_down.to_vec(), vec![media]].concat()),
+                Swipe::Pass => {}
+            }
+            queue.set(rest);
+        })
+    };
+
+    let on_done = {
+        let active_pack = active_pack.clone();
+        let queue = queue.clone();
+        let thumbs_up = thumbs_up.clone();
+        let thumbs_down = thumbs_down.clone();
+        let dispatch = dispatch.clone();
+        let navigator = navigator.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(pack) = (*active_pack).clone() else {
+                return;
+            };
+            let active_pack = active_pack.clone();
+            let queue = queue.clone();
+            let up = thumbs_up.to_vec();
+            let down = thumbs_down.to_vec();
+            let dispatch = dispatch.clone();
+            let navigator = navigator.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let rated = up.len() + down.len();
+                match api_apply_starter_pack(pack.id, up, down).await {
+                    Ok(collections) => {
+                        collections
+                            .into_iter()
+                            .for_each(|c| set_store_collection(c, &dispatch));
+                        set_show_alert(
+                            format!("Rated {rated} titles from {}", pack.name),
+                            &dispatch,
+                        );
+                        active_pack.set(None);
+                        queue.set(vec![]);
+                        navigator.push(&router::Route::DiscoveryPage);
+                    }
+                    Err(e) => {
+                        console!(format!("Error applying starter pack: {e}"));
+                        set_show_alert(e.to_string(), &dispatch);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_back = {
+        let active_pack = active_pack.clone();
+        let queue = queue.clone();
+        Callback::from(move |_: MouseEvent| {
+            active_pack.set(None);
+            queue.set(vec![]);
+        })
+    };
+
+    html! {
+        <section class="grid justify-items-stretch justify-center place-content-center">
+            <div class="grid lg:w-[65vw]">
+                if let Some(pack) = &*active_pack {
+                    <div class="w-4/5 justify-self-center flex flex-col gap-2 pb-2">
+                        <div class="text-center">
+                            <h2 class="text-3xl font-bold">{pack.name.clone()}</h2>
+                            <p>{format!("👍 {}  👎 {}  ·  {} to go", thumbs_up.len(), thumbs_down.len(), queue.len())}</p>
+                        </div>
+                        <div class="flex justify-center gap-2">
+                            <button class="btn btn-ghost" onclick={on_back}>{"Back"}</button>
+                            <button
+                                class="btn btn-primary"
+                                onclick={on_done}
+                                disabled={store.page_loading || (thumbs_up.is_empty() && thumbs_down.is_empty())}>
+                                {"Done"}
+                            </button>
+                        </div>
+                    </div>
+                    if queue.is_empty() {
+                        <div class="stack w-4/5 grid justify-stretch justify-self-center">
+                            <div class="text-center border border-base-content bg-base-200 card image-full">
+                                <div class="card-body">
+                                    <h2 class="card-title place-content-center">
+                                        if store.page_loading {
+                                            <Spinner />
+                                        } else {
+                                            {"That's the lot! Hit Done to save your ratings."}
+                                        }
+                                    </h2>
+                                </div>
+                            </div>
+                        </div>
+                    } else {
+                        <div class="stack w-4/5 grid justify-stretch justify-self-center">
+                            {
+                                queue.iter().map(|media| html!{
+                                    <MediaCard media={media.to_owned()}>
+                                        <div class="card-actions justify-around pt-4">
+                                            <button
+                                                class="basis-1/4 btn btn-outline btn-error"
+                                                onclick={on_swipe(Swipe::Down)}>
+                                                <FaceFrown />
+                                            </button>
+                                            <button
+                                                class="btn btn-ghost"
+                                                title="Haven't seen it"
+                                                onclick={on_swipe(Swipe::Pass)}>
+                                                {"Pass"}
+                                            </button>
+                                            <button
+                                                class="basis-1/4 btn btn-outline btn-success"
+                                                onclick={on_swipe(Swipe::Up)}>
+                                                <FaceSmile />
+                                            </button>
+                                        </div>
+                                    </MediaCard>
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    }
+                } else {
+                    <div class="w-4/5 justify-self-center flex flex-col gap-4">
+                        <div class="text-center">
+                            <h2 class="text-3xl font-bold">{"Starter Packs"}</h2>
+                            <p>{"Rate a few titles you know and discovery will have something to go on."}</p>
+                        </div>
+                        if packs.is_empty() {
+                            <p class="text-center">{"... no starter packs yet ..."}</p>
+                        }
+                        {
+                            packs.iter().map(|pack| html!{
+                                <div class="card bg-base-200">
+                                    <div class="card-body p-4">
+                                        <h2 class="card-title">{pack.name.clone()}</h2>
+                                        <p>{pack.description.clone()}</p>
+                                        <p class="text-sm opacity-70">
+                                            {
+                                                pack.entries.entries.iter()
+                                                    .take(5)
+                                                    .map(|m| m.to_string())
+                                                    .collect::<Vec<String>>()
+                                                    .join(", ")
+                                            }
+                                            {"…"}
+                                        </p>
+                                        <div class="card-actions justify-end">
+                                            <button
+                                                class="btn btn-sm btn-primary"
+                                                onclick={on_pick(pack)}
+                                                disabled={store.page_loading}>
+                                                {format!("Rate {} titles", pack.entries.entries.len())}
+                                            </button>
+                                        </div>
+                                    </div>
+                                </div>
+                            }).collect::<Html>()
+                        }
+                    </div>
+                }
+            </div>
+        </section>
+    }
+}
@@ -0,0 +1,130 @@
+use crate::api::collections_api::api_get_collection_stats;
+use crate::router;
+use crate::store::{set_page_loading, set_show_alert, Store};
+use common::model::stats::{CollectionStats, StatCount};
+use gloo::console::console;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::functional::use_store;
+
+// How many rows the long-tailed breakdowns show.
+const TOP_N: usize = 10;
+
+fn bars(title: &str, counts: &[StatCount], limit: usize) -> Html {
+    let max = counts.iter().map(|c| c.count).max().unwrap_or(0);
+
+    html! {
+        <div class="card bg-base-200">
+            <div class="card-body p-4">
+                <h2 class="card-title">{title}</h2>
+                if counts.is_empty() {
+                    <p>{"... nothing yet ..."}</p>
+                }
+                {
+                    counts.iter().take(limit).map(|c| html!{
+                        <div class="grid grid-cols-4 gap-2 items-center text-sm">
+                            <span class="truncate">{c.label.clone()}</span>
+                            <progress class="progress progress-primary col-span-2" value={c.count.to_string()} max={max.to_string()} />
+                            <span class="text-right">{c.count}</span>
+                        </div>
+                    }).collect::<Html>()
+                }
+            </div>
+        </div>
+    }
+}
+
+fn trends(stats: &CollectionStats) -> Html {
+    let max = stats.trends.iter().map(|t| t.added).max().unwrap_or(0);
+
+    html! {
+        <div class="card bg-base-200">
+            <div class="card-body p-4">
+                <h2 class="card-title">{"Over time"}</h2>
+                if stats.trends.is_empty() {
+                    <p>{"... nothing yet ..."}</p>
+                }
+                {
+                    stats.trends.iter().map(|t| html!{
+                        <div class="grid grid-cols-4 gap-2 items-center text-sm">
+                            <span>{t.period.clone()}</span>
+                            <progress class="progress progress-secondary col-span-2" value={t.added.to_string()} max={max.to_string()} />
+                            <span class="text-right">{format!("{} (👍{} 👎{})", t.added, t.thumbs_up, t.thumbs_down)}</span>
+                        </div>
+                    }).collect::<Html>()
+                }
+            </div>
+        </div>
+    }
+}
+
+// The user's taste profile: what's in their collections, broken down a few ways.
+#[function_component(StatsPage)]
+pub fn stats_page() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let navigator = use_navigator().unwrap();
+    if store.auth_user.is_none() {
+        navigator.push(&router::Route::LoginPage);
+    }
+    let stats: UseStateHandle<Option<CollectionStats>> = use_state(|| None);
+
+    {
+        let stats = stats.clone();
+        let dispatch = dispatch.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                set_page_loading(true, &dispatch);
+                let response = api_get_collection_stats().await;
+                set_page_loading(false, &dispatch);
+                match response {
+                    Ok(s) => stats.set(Some(s)),
+                    Err(e) => {
+                        console!(format!("Error getting stats: {e}"));
+                        set_show_alert(e.to_string(), &dispatch);
+                        if e.contains("You are not logged in") {
+                            navigator.push(&router::Route::LoginPage);
+                        }
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    html! {
+        <section class="grid justify-items-stretch justify-center">
+            <div class="grid lg:w-[65vw] sm:w-[95wv]">
+                <div class="lg:w-3/5 justify-self-center flex flex-col gap-4 pt-4">
+                    if let Some(stats) = &*stats {
+                        <div class="stats stats-vertical lg:stats-horizontal shadow">
+                            <div class="stat">
+                                <div class="stat-title">{"Titles"}</div>
+                                <div class="stat-value">{stats.total_entries}</div>
+                            </div>
+                            <div class="stat">
+                                <div class="stat-title">{"Average TMDB vote"}</div>
+                                <div class="stat-value">
+                                    {stats.average_vote.map_or("-".to_string(), |v| format!("{v:.1}"))}
+                                </div>
+                            </div>
+                            <div class="stat">
+                                <div class="stat-title">{"👍 / 👎"}</div>
+                                <div class="stat-value">{format!("{} / {}", stats.thumbs_up, stats.thumbs_down)}</div>
+                                <div class="stat-desc">
+                                    {stats.up_down_ratio.map_or(String::new(), |r| format!("{r:.2} up for every down"))}
+                                </div>
+                            </div>
+                        </div>
+                        { bars("Media types", &stats.by_media_type, usize::MAX) }
+                        { bars("Genres", &stats.genres, TOP_N) }
+                        { bars("Decades", &stats.decades, usize::MAX) }
+                        { bars("Languages", &stats.languages, TOP_N) }
+                        { trends(stats) }
+                    } else {
+                        <p>{"... no stats yet ..."}</p>
+                    }
+                </div>
+            </div>
+        </section>
+    }
+}
@@ -1,7 +1,12 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::pages::channel_page::ChannelPage;
+use crate::pages::friends_page::FriendsPage;
 use crate::pages::schedules_page::SchedulesPage;
+use crate::pages::shared_collection_page::SharedCollectionPage;
+use crate::pages::starter_packs_page::StarterPacksPage;
+use crate::pages::stats_page::StatsPage;
 use crate::pages::{
     collections_page::CollectionsPage, discovery_page::DiscoveryPage, home_page::HomePage,
     login_page::LoginPage, profile_page::ProfilePage, register_page::RegisterPage,
@@ -23,6 +28,16 @@ pub enum Route {
     DiscoveryPage,
     #[at("/schedules")]
     SchedulesPage,
+    #[at("/channel/:id")]
+    ChannelPage { id: String },
+    #[at("/shared/collection/:id")]
+    SharedCollectionPage { id: String },
+    #[at("/friends")]
+    FriendsPage,
+    #[at("/stats")]
+    StatsPage,
+    #[at("/starter-packs")]
+    StarterPacksPage,
 }
 
 pub fn switch(routes: Route) -> Html {
@@ -34,5 +49,10 @@ pub fn switch(routes: Route) -> Html {
         Route::CollectionsPage => html! {<CollectionsPage/> },
         Route::DiscoveryPage => html! {<DiscoveryPage/> },
         Route::SchedulesPage => html! {<SchedulesPage/> },
+        Route::ChannelPage { id } => html! {<ChannelPage id={id} /> },
+        Route::SharedCollectionPage { id } => html! {<SharedCollectionPage id={id} /> },
+        Route::FriendsPage => html! {<FriendsPage/> },
+        Route::StatsPage => html! {<StatsPage/> },
+        Route::StarterPacksPage => html! {<StarterPacksPage/> },
     }
 }
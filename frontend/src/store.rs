@@ -1,12 +1,16 @@
 use common::model::collections::UserCollection;
+use common::model::schedules::Schedule;
 use common::model::user::User;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use yewdux::prelude::*;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Default, Clone)]
 pub struct AlertInput {
     pub show_alert: bool,
     pub alert_message: String,
+    // Set when the alert is about a change to this collection that can be undone.
+    pub undo_collection_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Store)]
@@ -15,7 +19,7 @@ pub struct Store {
     pub page_loading: bool,
     pub alert_input: AlertInput,
     pub collections: Option<Vec<UserCollection>>,
-    pub schedules: Option<Vec<String>>,
+    pub schedules: Option<Vec<Schedule>>,
 }
 
 pub fn set_page_loading(loading: bool, dispatch: &Dispatch<Store>) {
@@ -35,10 +39,38 @@ pub fn set_show_alert(message: String, dispatch: &Dispatch<Store>) {
         store.alert_input = AlertInput {
             alert_message: message,
             show_alert: true,
+            undo_collection_id: None,
         };
     })
 }
 
+pub fn set_show_undo_alert(message: String, collection_id: Uuid, dispatch: &Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.alert_input = AlertInput {
+            alert_message: message,
+            show_alert: true,
+            undo_collection_id: Some(collection_id),
+        };
+    })
+}
+
+pub fn set_store_collection(collection: UserCollection, dispatch: &Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        if let Some(cols) = store.collections.as_mut() {
+            match cols.iter_mut().find(|c| c.id == collection.id) {
+                Some(col) => *col = collection,
+                None => cols.push(collection),
+            }
+        }
+    })
+}
+
+pub fn set_store_schedules(schedules: Vec<Schedule>, dispatch: &Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.schedules = Some(schedules);
+    })
+}
+
 pub fn set_hide_alert(dispatch: &Dispatch<Store>) {
     dispatch.reduce_mut(move |store| {
         store.alert_input.show_alert = false;
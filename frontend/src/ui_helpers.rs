@@ -56,3 +56,11 @@ pub fn redirect_to(url: &str) {
     let location = window.location();
     let _ = location.set_href(url);
 }
+
+// The browser's IANA zone, e.g. "Europe/London".
+pub fn browser_time_zone() -> Option<String> {
+    let format = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new());
+    js_sys::Reflect::get(&format.resolved_options(), &"timeZone".into())
+        .ok()?
+        .as_string()
+}